@@ -0,0 +1,557 @@
+// Headless Peep: prints the same numbers as the app, one-shot or as a
+// refreshing stream, for machines without a display.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use peep_core::{
+    BatterySnapshot, Collector, Connection, ConnectionFilter, ConnectionState, CpuSnapshot, Exporter, ExporterOptions, DiskFilter,
+    DiskSnapshot, ListeningPort, MemorySnapshot, NetworkSnapshot, OsInfo, OtlpOptions, ProcessNode, ProcessRecord, ProcessSignal,
+    Protocol, Recorder, Recording, Replay, SignalScope, TreeOrder,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+mod table;
+mod tui;
+
+#[derive(Parser)]
+#[command(name = "peep", version, about = "Headless Peep system monitor")]
+struct Cli {
+    #[arg(short, long, value_enum, default_value_t = Format::Table, global = true, help = "Output format")]
+    format: Format,
+
+    #[arg(short, long, global = true, help = "Keep refreshing instead of printing once")]
+    watch: bool,
+
+    #[arg(short, long, default_value_t = 2.0, value_name = "SECS", global = true, help = "Seconds between refreshes with --watch")]
+    interval: f64,
+
+    #[arg(long, value_name = "FILE", global = true, help = "Report from a recording made with `peep record` instead of this machine")]
+    replay: Option<PathBuf>,
+
+    #[arg(long, default_value_t = 1.0, global = true, help = "Replay speed, e.g. 10 plays ten times faster")]
+    speed: f64,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+    Jsonl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SortKey {
+    Cpu,
+    Memory,
+    Pid,
+    Name,
+    // TCP traffic in both directions (Linux only)
+    Net,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    #[command(about = "CPU usage, overall and per core")]
+    Cpu,
+    #[command(about = "Memory and swap usage")]
+    Mem,
+    #[command(about = "Disk I/O and volumes")]
+    Disk {
+        #[arg(long, help = "List every volume, including virtual and duplicate mounts")]
+        all: bool,
+    },
+    #[command(about = "Network I/O per interface")]
+    Net {
+        #[arg(long, help = "Also list bridges, tunnels, container links and other virtual interfaces")]
+        all: bool,
+    },
+    #[command(about = "Running processes")]
+    Ps {
+        #[arg(long, help = "Include threads as separate entries")]
+        threads: bool,
+        #[arg(short, long, value_enum, default_value_t = SortKey::Cpu, help = "Sort order")]
+        sort: SortKey,
+        #[arg(short = 'n', long, help = "Only show the first N processes")]
+        limit: Option<usize>,
+        #[arg(long, conflicts_with = "threads", help = "Nest processes under their parents, sorted and limited by subtree totals")]
+        tree: bool,
+    },
+    #[command(about = "Battery state and health")]
+    Battery,
+    #[command(about = "Operating system and host")]
+    Os,
+    #[command(about = "TCP and UDP sockets with their owning processes (Linux only)")]
+    Conns {
+        #[arg(short, long = "proto", value_name = "PROTO", value_parser = parse_name::<Protocol>, help = "Only tcp, tcp6, udp or udp6 sockets; repeatable")]
+        protocols: Vec<Protocol>,
+        #[arg(short, long = "state", value_name = "STATE", value_parser = parse_name::<ConnectionState>, help = "Only sockets in this state, e.g. established, listen or timeWait; repeatable")]
+        states: Vec<ConnectionState>,
+        #[arg(long, help = "Only sockets owned by this process")]
+        pid: Option<u32>,
+    },
+    #[command(about = "Listening TCP and UDP ports with their owning processes (Linux only)")]
+    Ports {
+        #[arg(help = "Only this port, e.g. to find what is using it")]
+        port: Option<u16>,
+        #[arg(short, long = "proto", value_name = "PROTO", value_parser = parse_name::<Protocol>, help = "Only tcp or udp ports, in either address family")]
+        protocol: Option<Protocol>,
+    },
+    #[command(about = "Kill or signal a process by PID, or whatever listens on a port")]
+    Kill(KillArgs),
+    #[command(about = "Full-screen terminal dashboard and process list")]
+    Top,
+    #[command(about = "Serve Prometheus metrics over HTTP")]
+    Serve {
+        #[arg(short, long, default_value = "127.0.0.1:9101", help = "Address to listen on")]
+        listen: String,
+        #[arg(long, default_value_t = 20, help = "Number of processes, by CPU usage, to export")]
+        top: usize,
+    },
+    #[command(about = "Push OpenTelemetry metrics to an OTLP/HTTP collector")]
+    Push {
+        #[arg(short, long, default_value = "http://localhost:4318", help = "Collector endpoint; /v1/metrics is used when there's no path")]
+        endpoint: String,
+        #[arg(long, default_value_t = 10.0, value_name = "SECS", help = "Seconds between pushes")]
+        every: f64,
+        #[arg(long, default_value_t = 20, help = "Number of processes, by CPU usage, to export")]
+        top: usize,
+        #[arg(short = 'H', long = "header", value_name = "NAME=VALUE", help = "Extra request header, repeatable")]
+        headers: Vec<String>,
+    },
+    #[command(about = "Record system info, processes and battery to a file for replay")]
+    Record {
+        path: PathBuf,
+        #[arg(short, long, value_name = "SECS", help = "Stop after this many seconds instead of at Ctrl-C")]
+        duration: Option<f64>,
+    },
+}
+
+#[derive(Args)]
+struct KillArgs {
+    #[arg(required_unless_present = "port")]
+    pid: Option<u32>,
+    #[arg(long, conflicts_with = "pid", help = "Kill the process listening on this port instead")]
+    port: Option<u16>,
+    #[arg(short, long = "proto", value_name = "PROTO", default_value = "tcp", value_parser = parse_name::<Protocol>, help = "Protocol of --port: tcp or udp")]
+    protocol: Protocol,
+    #[arg(short, long, value_parser = str::parse::<ProcessSignal>, help = "Send this signal instead of KILL, e.g. TERM, HUP or USR1")]
+    signal: Option<ProcessSignal>,
+    #[arg(
+        long,
+        value_name = "MS",
+        num_args = 0..=1,
+        default_missing_value = "5000",
+        conflicts_with_all = ["signal", "tree", "group", "session"],
+        help = "Send TERM, then KILL if the process is still running after MS milliseconds (default 5000)"
+    )]
+    graceful: Option<u64>,
+    #[arg(long, conflicts_with_all = ["group", "session"], help = "Also signal every descendant, children before parents")]
+    tree: bool,
+    #[arg(long, requires = "tree", help = "With --tree, signal parents before their children")]
+    top_down: bool,
+    #[arg(long, conflicts_with = "session", help = "Signal every process in the process's group")]
+    group: bool,
+    #[arg(long, help = "Signal every process in the process's session")]
+    session: bool,
+    #[arg(long, help = "Signal protected processes too (init, kernel threads, the window server, ...)")]
+    force: bool,
+}
+
+// One reading of whatever the subcommand reports
+enum Report {
+    Cpu(CpuSnapshot),
+    Memory(MemorySnapshot),
+    Disk(DiskSnapshot),
+    Network(NetworkSnapshot),
+    Processes(Vec<ProcessRecord>),
+    ProcessTree(Vec<ProcessNode>),
+    Battery(BatterySnapshot),
+    Os(OsInfo),
+    Connections(Vec<Connection>),
+    Ports(Vec<ListeningPort>),
+}
+
+impl Report {
+    fn to_value(&self) -> Value {
+        let value = match self {
+            Report::Cpu(cpu) => serde_json::to_value(cpu),
+            Report::Memory(memory) => serde_json::to_value(memory),
+            Report::Disk(disk) => serde_json::to_value(disk),
+            Report::Network(network) => serde_json::to_value(network),
+            Report::Processes(processes) => serde_json::to_value(processes),
+            Report::ProcessTree(tree) => serde_json::to_value(tree),
+            Report::Battery(battery) => serde_json::to_value(battery),
+            Report::Os(os) => serde_json::to_value(os),
+            Report::Connections(connections) => serde_json::to_value(connections),
+            Report::Ports(ports) => serde_json::to_value(ports),
+        };
+        value.unwrap_or(Value::Null)
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let result = match cli.command {
+        _ if cli.replay.is_some() => replay(&cli),
+        Command::Kill(ref args) => kill(args, cli.format),
+        Command::Top => tui::run(refresh_interval(cli.interval)).map(|_| ExitCode::SUCCESS),
+        Command::Serve { ref listen, top } => serve(listen, top),
+        Command::Push { ref endpoint, every, top, ref headers } => push(endpoint, every, top, headers),
+        Command::Record { ref path, duration } => record(path, refresh_interval(cli.interval), duration),
+        _ => monitor(&cli),
+    };
+
+    match result {
+        Ok(code) => code,
+        // Output piped into something like `head` that stopped reading
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("peep: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn monitor(cli: &Cli) -> io::Result<ExitCode> {
+    let mut collector = Collector::new();
+    if let Command::Disk { all: true } = cli.command {
+        collector = collector.with_disk_filter(DiskFilter::none());
+    }
+
+    // CPU usage and I/O rates are deltas between refreshes, so take a baseline first
+    if let Command::Ps { threads, .. } = cli.command {
+        collector.processes(threads);
+    }
+    if matches!(cli.command, Command::Cpu | Command::Disk { .. } | Command::Net { .. } | Command::Ps { .. }) {
+        thread::sleep(peep_core::MINIMUM_CPU_UPDATE_INTERVAL);
+    }
+
+    let interval = refresh_interval(cli.interval);
+    let clear_screen = cli.watch && cli.format == Format::Table && io::stdout().is_terminal();
+
+    loop {
+        let report = collect(&mut collector, &cli.command)?;
+        let timestamp = peep_core::now_millis();
+
+        let mut out = io::stdout().lock();
+        if clear_screen {
+            write!(out, "\x1b[2J\x1b[H")?;
+        }
+        print(&mut out, &report, cli.format, timestamp)?;
+        out.flush()?;
+        drop(out);
+
+        if !cli.watch {
+            return Ok(ExitCode::SUCCESS);
+        }
+        thread::sleep(interval);
+    }
+}
+
+// CPU usage is meaningless when refreshed faster than sysinfo's minimum interval
+fn refresh_interval(secs: f64) -> Duration {
+    Duration::from_secs_f64(secs.max(0.0)).max(peep_core::MINIMUM_CPU_UPDATE_INTERVAL)
+}
+
+fn collect(collector: &mut Collector, command: &Command) -> io::Result<Report> {
+    Ok(match *command {
+        Command::Cpu => Report::Cpu(collector.cpu()),
+        Command::Mem => Report::Memory(collector.memory()),
+        Command::Disk { .. } => Report::Disk(collector.disks()),
+        Command::Net { all } => network_report(collector.network(), all),
+        Command::Ps { threads, sort, limit, tree } => process_report(collector.processes(threads), sort, limit, tree),
+        Command::Battery => Report::Battery(collector.battery()),
+        Command::Os => Report::Os(peep_core::os_info()),
+        Command::Conns { ref protocols, ref states, pid } => {
+            let filter = ConnectionFilter { protocols: protocols.clone(), states: states.clone(), pid };
+            Report::Connections(peep_core::connections(&filter)?)
+        }
+        Command::Ports { port, protocol } => {
+            let mut ports = peep_core::listening_ports()?;
+            ports.retain(|l| {
+                port.is_none_or(|port| l.port == port) && protocol.is_none_or(|p| l.protocol.is_tcp() == p.is_tcp())
+            });
+            Report::Ports(ports)
+        }
+        _ => unreachable!("not a monitor command"),
+    })
+}
+
+// Parse a protocol or state by its JSON name, e.g. "tcp6" or "timeWait"
+fn parse_name<T: DeserializeOwned>(name: &str) -> Result<T, String> {
+    serde_json::from_value(Value::String(name.to_string())).map_err(|_| format!("unknown value: {}", name))
+}
+
+fn process_report(mut processes: Vec<ProcessRecord>, sort: SortKey, limit: Option<usize>, tree: bool) -> Report {
+    if tree {
+        let mut tree = peep_core::process_tree(&processes);
+        sort_tree(&mut tree, sort);
+        if let Some(limit) = limit {
+            tree.truncate(limit);
+        }
+        return Report::ProcessTree(tree);
+    }
+    sort_processes(&mut processes, sort);
+    if let Some(limit) = limit {
+        processes.truncate(limit);
+    }
+    Report::Processes(processes)
+}
+
+// Like `monitor`, but reading a recording. With --watch the recording plays
+// through once, at --speed, then exits.
+fn replay(cli: &Cli) -> io::Result<ExitCode> {
+    let Some(path) = &cli.replay else {
+        return monitor(cli);
+    };
+    if !matches!(cli.command, Command::Cpu | Command::Mem | Command::Disk { .. } | Command::Net { .. } | Command::Ps { .. } | Command::Battery | Command::Os) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "--replay only applies to cpu, mem, disk, net, ps, battery and os"));
+    }
+    let recording = Recording::open(path).map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+    let replay = Replay::new(recording, cli.speed, false);
+    let clear_screen = cli.watch && cli.format == Format::Table && io::stdout().is_terminal();
+    // The interval is in recording time, so faster replays print just as often
+    let interval = refresh_interval(cli.interval).div_f64(if cli.speed > 0.0 { cli.speed } else { 1.0 });
+
+    loop {
+        let report = replayed(&replay, &cli.command)?;
+        let timestamp = replay.recording().started_at + replay.offset();
+
+        let mut out = io::stdout().lock();
+        if clear_screen {
+            write!(out, "\x1b[2J\x1b[H")?;
+        }
+        print(&mut out, &report, cli.format, timestamp)?;
+        out.flush()?;
+        drop(out);
+
+        if !cli.watch || replay.finished() {
+            return Ok(ExitCode::SUCCESS);
+        }
+        thread::sleep(interval);
+    }
+}
+
+// Virtual interfaces are only reported with --all
+fn network_report(mut network: NetworkSnapshot, all: bool) -> Report {
+    if !all {
+        network.virtual_interfaces.clear();
+    }
+    Report::Network(network)
+}
+
+fn replayed(replay: &Replay, command: &Command) -> io::Result<Report> {
+    let missing = |what: &str| io::Error::new(io::ErrorKind::InvalidData, format!("Recording has no {}", what));
+    let system = || replay.system().ok_or_else(|| missing("system info"));
+
+    Ok(match *command {
+        Command::Cpu => Report::Cpu(system()?.cpu.clone()),
+        Command::Mem => Report::Memory(system()?.memory.clone()),
+        Command::Disk { .. } => Report::Disk(system()?.disk.clone()),
+        Command::Net { all } => network_report(system()?.network.clone(), all),
+        Command::Ps { threads, sort, limit, tree } => {
+            let processes = replay.processes(threads).ok_or_else(|| missing("processes"))?;
+            process_report(processes, sort, limit, tree)
+        }
+        Command::Battery => Report::Battery(replay.battery().cloned().ok_or_else(|| missing("battery info"))?),
+        Command::Os => Report::Os(replay.recording().os.clone().ok_or_else(|| missing("OS info"))?),
+        _ => unreachable!("not a monitor command"),
+    })
+}
+
+fn sort_processes(processes: &mut [ProcessRecord], sort: SortKey) {
+    match sort {
+        SortKey::Cpu => processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu)),
+        SortKey::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+        SortKey::Pid => processes.sort_by_key(|p| p.pid),
+        SortKey::Name => processes.sort_by_cached_key(|p| p.name.to_lowercase()),
+        SortKey::Net => {
+            let traffic = |p: &ProcessRecord| p.net_rx.unwrap_or(0.0) + p.net_tx.unwrap_or(0.0);
+            processes.sort_by(|a, b| traffic(b).total_cmp(&traffic(a)))
+        }
+    }
+}
+
+// Siblings by their subtree's CPU or memory; the other keys only look at the
+// process itself
+fn sort_tree(nodes: &mut [ProcessNode], sort: SortKey) {
+    match sort {
+        SortKey::Cpu => nodes.sort_by(|a, b| b.total.cpu.total_cmp(&a.total.cpu)),
+        SortKey::Memory => nodes.sort_by_key(|n| std::cmp::Reverse(n.total.memory)),
+        SortKey::Pid => nodes.sort_by_key(|n| n.process.pid),
+        SortKey::Name => nodes.sort_by_cached_key(|n| n.process.name.to_lowercase()),
+        SortKey::Net => {
+            let traffic = |n: &ProcessNode| n.process.net_rx.unwrap_or(0.0) + n.process.net_tx.unwrap_or(0.0);
+            nodes.sort_by(|a, b| traffic(b).total_cmp(&traffic(a)))
+        }
+    }
+    for node in nodes {
+        sort_tree(&mut node.children, sort);
+    }
+}
+
+fn kill(args: &KillArgs, format: Format) -> io::Result<ExitCode> {
+    let scope = if args.tree {
+        Some(SignalScope::Tree(if args.top_down { TreeOrder::TopDown } else { TreeOrder::BottomUp }))
+    } else if args.group {
+        Some(SignalScope::Group)
+    } else if args.session {
+        Some(SignalScope::Session)
+    } else {
+        None
+    };
+
+    if scope.is_none() && args.signal.is_none() && args.graceful.is_none() {
+        let mut collector = Collector::new();
+        // The collector only knows about processes it has refreshed
+        collector.processes(false);
+        let result = match (args.pid, args.port) {
+            (_, Some(port)) => collector.kill_port(port, args.protocol, args.force),
+            (Some(pid), None) => collector.kill(pid, args.force),
+            (None, None) => unreachable!("clap requires a pid or --port"),
+        };
+        return print_result(&result, result.success, &result.message, format);
+    }
+
+    let pid = match (args.pid, args.port) {
+        (Some(pid), _) => pid,
+        (None, Some(port)) => peep_core::find_process_by_port(port, args.protocol)?
+            .and_then(|listener| listener.pid)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("No known process is listening on port {}", port)))?,
+        (None, None) => unreachable!("clap requires a pid or --port"),
+    };
+    if let Some(scope) = scope {
+        let outcomes = peep_core::signal_processes(pid, args.signal.unwrap_or(ProcessSignal::Kill), scope, args.force);
+        let success = outcomes.iter().all(|outcome| outcome.success);
+        return print_result(&outcomes, success, table::render_signal_outcomes(&outcomes).trim_end(), format);
+    }
+    match (args.signal, args.graceful) {
+        (_, Some(ms)) => {
+            let result = peep_core::terminate_gracefully(pid, Duration::from_millis(ms), args.force);
+            print_result(&result, result.success, &result.message, format)
+        }
+        (Some(signal), None) => {
+            let result = peep_core::send_signal(pid, signal, args.force);
+            print_result(&result, result.success, &result.message, format)
+        }
+        (None, None) => unreachable!(),
+    }
+}
+
+// The message as a line of text, or the whole result as JSON
+fn print_result<T: Serialize>(result: &T, success: bool, message: &str, format: Format) -> io::Result<ExitCode> {
+    let mut out = io::stdout().lock();
+    match format {
+        Format::Table => writeln!(out, "{}", message)?,
+        Format::Json => writeln!(out, "{}", to_json(result, true))?,
+        Format::Jsonl => writeln!(out, "{}", to_json(result, false))?,
+    }
+    Ok(if success { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn serve(listen: &str, top: usize) -> io::Result<ExitCode> {
+    let exporter = Exporter::start(listen, ExporterOptions { top_processes: top })?;
+    eprintln!("Serving metrics on http://{}/metrics", exporter.local_addr());
+    exporter.wait();
+    Ok(ExitCode::SUCCESS)
+}
+
+fn push(endpoint: &str, every: f64, top: usize, headers: &[String]) -> io::Result<ExitCode> {
+    let headers = headers
+        .iter()
+        .map(|header| match header.split_once('=') {
+            Some((name, value)) => Ok((name.trim().to_string(), value.trim().to_string())),
+            None => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Expected NAME=VALUE header: {}", header))),
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let options = OtlpOptions {
+        endpoint: endpoint.to_string(),
+        interval: refresh_interval(every),
+        top_processes: top,
+        headers,
+    };
+
+    let mut collector = Collector::new();
+    // Baseline so the first push already has CPU usage
+    collector.processes(false);
+    thread::sleep(peep_core::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    eprintln!("Pushing metrics to {} every {:?}", endpoint, options.interval);
+    loop {
+        // Keep going through collector outages, like the app does
+        if let Err(err) = peep_core::export_otlp(&mut collector, &options) {
+            if err.kind() == io::ErrorKind::InvalidInput {
+                return Err(err);
+            }
+            eprintln!("peep: {}", err);
+        }
+        thread::sleep(options.interval);
+    }
+}
+
+fn record(path: &Path, interval: Duration, duration: Option<f64>) -> io::Result<ExitCode> {
+    let mut collector = Collector::new();
+    // Baseline so the first frame already has CPU usage and rates
+    collector.processes(false);
+    thread::sleep(peep_core::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let started = peep_core::now_millis();
+    let stop_at = duration.map(|secs| started + (secs.max(0.0) * 1000.0) as u64);
+    let mut recorder = Recorder::create(path, started, Some(&peep_core::os_info()))?;
+    eprintln!("Recording to {} every {:?}, Ctrl-C to stop", path.display(), interval);
+
+    loop {
+        // Frames are flushed as they're written, so Ctrl-C loses nothing
+        let timestamp = peep_core::now_millis();
+        recorder.record_system(timestamp, &collector.system())?;
+        recorder.record_processes(timestamp, &collector.processes(false))?;
+        recorder.record_battery(timestamp, &collector.battery())?;
+
+        if stop_at.is_some_and(|stop_at| timestamp + interval.as_millis() as u64 > stop_at) {
+            eprintln!("Recorded {} frames", recorder.frames());
+            return Ok(ExitCode::SUCCESS);
+        }
+        thread::sleep(interval);
+    }
+}
+
+fn print(out: &mut impl Write, report: &Report, format: Format, timestamp: u64) -> io::Result<()> {
+    match format {
+        Format::Table => write!(out, "{}", table::render(report)),
+        // Exactly the shape the matching native export returns
+        Format::Json => writeln!(out, "{}", to_json(&report.to_value(), true)),
+        // One timestamped object per line; process lists get one line per process
+        Format::Jsonl => {
+            let lines = match report.to_value() {
+                Value::Array(items) => items,
+                value => vec![value],
+            };
+            for mut line in lines {
+                if let Value::Object(fields) = &mut line {
+                    fields.insert("timestamp".to_string(), timestamp.into());
+                }
+                writeln!(out, "{}", line)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn to_json<T: Serialize>(value: &T, pretty: bool) -> String {
+    let json = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    json.unwrap_or_else(|err| format!("{{\"error\":\"{}\"}}", err))
+}
@@ -0,0 +1,381 @@
+use peep_core::{
+    BatterySnapshot, Connection, CpuSnapshot, DiskSnapshot, ListeningPort, MemorySnapshot, NetworkSnapshot, OsInfo, ProcessNode, ProcessRecord, SignalOutcome,
+};
+use std::fmt::Write;
+use std::net::IpAddr;
+
+use crate::Report;
+
+// Human-readable tables for the terminal. Units and formatting follow the
+// renderer's helpers in src/shared/utils.ts so both show the same numbers.
+
+pub fn render(report: &Report) -> String {
+    match report {
+        Report::Cpu(cpu) => render_cpu(cpu),
+        Report::Memory(memory) => render_memory(memory),
+        Report::Disk(disk) => render_disk(disk),
+        Report::Network(network) => render_network(network),
+        Report::Processes(processes) => render_processes(processes),
+        Report::ProcessTree(tree) => render_process_tree(tree),
+        Report::Battery(battery) => render_battery(battery),
+        Report::Os(os) => render_os(os),
+        Report::Connections(connections) => render_connections(connections),
+        Report::Ports(ports) => render_ports(ports),
+    }
+}
+
+fn render_cpu(cpu: &CpuSnapshot) -> String {
+    let mut rows = vec![
+        row(["CPU", &cpu.brand.clone().unwrap_or_else(|| "Unknown".to_string())]),
+        row(["Cores", &cpu.cores.to_string()]),
+        row(["Usage", &format_percentage(cpu.usage as f64)]),
+    ];
+    for (core, usage) in cpu.per_core.iter().enumerate() {
+        rows.push(row([&format!("Core {}", core), &format_percentage(*usage as f64)]));
+    }
+    key_values(rows)
+}
+
+fn render_memory(memory: &MemorySnapshot) -> String {
+    let percent = |used: u64, total: u64| if total > 0 { used as f64 / total as f64 * 100.0 } else { 0.0 };
+    columns(
+        &["", "TOTAL", "USED", "FREE", "USE%"],
+        &[false, true, true, true, true],
+        vec![
+            row([
+                "Memory",
+                &format_bytes(memory.total as f64),
+                &format_bytes(memory.used as f64),
+                &format_bytes(memory.free as f64),
+                &format_percentage(percent(memory.used, memory.total)),
+            ]),
+            row([
+                "Swap",
+                &format_bytes(memory.total_swap as f64),
+                &format_bytes(memory.used_swap as f64),
+                &format_bytes(memory.free_swap as f64),
+                &format_percentage(percent(memory.used_swap, memory.total_swap)),
+            ]),
+        ],
+    )
+}
+
+fn render_disk(disk: &DiskSnapshot) -> String {
+    let mut out = key_values(vec![
+        row(["Read", &format_throughput(disk.read_rate)]),
+        row(["Write", &format_throughput(disk.write_rate)]),
+        row(["Total read", &format_bytes(disk.total_read as f64)]),
+        row(["Total written", &format_bytes(disk.total_write as f64)]),
+    ]);
+
+    if !disk.disks.is_empty() {
+        out.push('\n');
+        let rows = disk.disks.iter()
+            .map(|d| {
+                let percent = if d.total_space > 0 {
+                    d.used_space as f64 / d.total_space as f64 * 100.0
+                } else {
+                    0.0
+                };
+                row([
+                    &d.mount_point,
+                    &d.name,
+                    &d.file_system,
+                    &format_bytes(d.total_space as f64),
+                    &format_bytes(d.used_space as f64),
+                    &format_bytes(d.available_space as f64),
+                    &format_percentage(percent),
+                ])
+            })
+            .collect();
+        out.push_str(&columns(
+            &["MOUNT", "DEVICE", "FS", "SIZE", "USED", "AVAIL", "USE%"],
+            &[false, false, false, true, true, true, true],
+            rows,
+        ));
+    }
+    out
+}
+
+fn render_network(network: &NetworkSnapshot) -> String {
+    let mut out = key_values(vec![
+        row(["Download", &format_throughput(network.rx_rate)]),
+        row(["Upload", &format_throughput(network.tx_rate)]),
+        row(["Total received", &format_bytes(network.total_rx as f64)]),
+        row(["Total sent", &format_bytes(network.total_tx as f64)]),
+    ]);
+
+    if !network.interfaces.is_empty() || !network.virtual_interfaces.is_empty() {
+        out.push('\n');
+        let rows = network.interfaces.iter()
+            .chain(&network.virtual_interfaces)
+            .map(|i| row([
+                &i.name,
+                &i.interface_type,
+                &format_throughput(i.received_rate),
+                &format_throughput(i.transmitted_rate),
+                &format_bytes(i.total_received as f64),
+                &format_bytes(i.total_transmitted as f64),
+            ]))
+            .collect();
+        out.push_str(&columns(
+            &["INTERFACE", "TYPE", "RX", "TX", "TOTAL RX", "TOTAL TX"],
+            &[false, false, true, true, true, true],
+            rows,
+        ));
+    }
+    out
+}
+
+fn render_processes(processes: &[ProcessRecord]) -> String {
+    // Traffic columns only where the platform attributes it to processes
+    let network = processes.iter().any(|p| p.net_rx.is_some());
+    let rate = |rate: Option<f64>| format_throughput(rate.unwrap_or(0.0));
+    let rows = processes.iter()
+        .map(|p| {
+            let mut cells = row([
+                &p.pid.to_string(),
+                &p.ppid.to_string(),
+                &p.user,
+                &format!("{:.1}", p.cpu),
+                &format_bytes(p.memory as f64),
+                &p.status,
+                &format_duration(p.run_time),
+                &p.name,
+            ]);
+            if network {
+                cells.splice(5..5, [rate(p.net_rx), rate(p.net_tx)]);
+            }
+            cells
+        })
+        .collect();
+
+    if network {
+        columns(
+            &["PID", "PPID", "USER", "CPU%", "MEM", "NET IN", "NET OUT", "STATUS", "TIME", "NAME"],
+            &[true, true, false, true, true, true, true, false, true, false],
+            rows,
+        )
+    } else {
+        columns(
+            &["PID", "PPID", "USER", "CPU%", "MEM", "STATUS", "TIME", "NAME"],
+            &[true, true, false, true, true, false, true, false],
+            rows,
+        )
+    }
+}
+
+// CPU and memory are subtree totals; names are indented by depth
+fn render_process_tree(tree: &[ProcessNode]) -> String {
+    fn rows(nodes: &[ProcessNode], depth: usize, out: &mut Vec<Vec<String>>) {
+        for node in nodes {
+            out.push(row([
+                &node.process.pid.to_string(),
+                &node.process.user,
+                &node.total.processes.to_string(),
+                &format!("{:.1}", node.total.cpu),
+                &format_bytes(node.total.memory as f64),
+                &format!("{}{}", "  ".repeat(depth), node.process.name),
+            ]));
+            rows(&node.children, depth + 1, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    rows(tree, 0, &mut out);
+    columns(
+        &["PID", "USER", "PROCS", "CPU%", "MEM", "NAME"],
+        &[true, false, true, true, true, false],
+        out,
+    )
+}
+
+// One line per signalled process, in the order they were signalled
+pub fn render_signal_outcomes(outcomes: &[SignalOutcome]) -> String {
+    let rows = outcomes.iter()
+        .map(|o| row([&o.pid.to_string(), &o.name, &o.message]))
+        .collect();
+    columns(&["PID", "NAME", "RESULT"], &[true, false, false], rows)
+}
+
+fn render_battery(battery: &BatterySnapshot) -> String {
+    if !battery.available {
+        return "No battery found\n".to_string();
+    }
+
+    let mut rows = Vec::new();
+    if let Some(percentage) = battery.percentage {
+        rows.push(row(["Charge", &format_percentage(percentage)]));
+    }
+    if let Some(state) = &battery.state {
+        rows.push(row(["State", state]));
+    }
+    if let Some(health) = battery.health {
+        rows.push(row(["Health", &format_percentage(health)]));
+    }
+    if let Some(minutes) = battery.time_to_full {
+        rows.push(row(["Time to full", &format_duration((minutes * 60.0) as u64)]));
+    }
+    if let Some(minutes) = battery.time_to_empty {
+        rows.push(row(["Time to empty", &format_duration((minutes * 60.0) as u64)]));
+    }
+    if let (Some(energy), Some(full)) = (battery.energy, battery.energy_full) {
+        rows.push(row(["Energy", &format!("{:.1} / {:.1} Wh", energy, full)]));
+    }
+    if let Some(temperature) = battery.temperature {
+        rows.push(row(["Temperature", &format!("{:.1} °C", temperature)]));
+    }
+    key_values(rows)
+}
+
+fn render_os(os: &OsInfo) -> String {
+    let mut name = format!("{} {}", os.name, os.version);
+    if let Some(marketing_name) = &os.marketing_name {
+        write!(name, " ({})", marketing_name).unwrap();
+    }
+
+    let mut rows = vec![
+        row(["OS", &name]),
+        row(["Kernel", &os.kernel_version]),
+        row(["Hostname", &os.hostname]),
+    ];
+    if let Some(model) = &os.model {
+        rows.push(row(["Model", model]));
+    }
+    rows.push(row(["Uptime", &format_duration(os.uptime)]));
+    key_values(rows)
+}
+
+fn render_connections(connections: &[Connection]) -> String {
+    let rows = connections.iter()
+        .map(|c| {
+            let owner = match (c.pid, &c.process_name) {
+                (Some(pid), Some(name)) => format!("{}/{}", pid, name),
+                (Some(pid), None) => pid.to_string(),
+                _ => "-".to_string(),
+            };
+            row([
+                c.protocol.name(),
+                &endpoint(c.local_address, c.local_port),
+                &endpoint(c.remote_address, c.remote_port),
+                c.state.label(),
+                &c.send_queue.to_string(),
+                &c.receive_queue.to_string(),
+                &owner,
+            ])
+        })
+        .collect();
+    columns(
+        &["PROTO", "LOCAL", "REMOTE", "STATE", "SEND-Q", "RECV-Q", "PID/PROGRAM"],
+        &[false, false, false, false, true, true, false],
+        rows,
+    )
+}
+
+fn render_ports(ports: &[ListeningPort]) -> String {
+    if ports.is_empty() {
+        return "Nothing is listening\n".to_string();
+    }
+    let rows = ports.iter()
+        .map(|l| row([
+            l.protocol.name(),
+            &endpoint(l.address, l.port),
+            &l.pid.map(|pid| pid.to_string()).unwrap_or_else(|| "-".to_string()),
+            l.user.as_deref().unwrap_or("-"),
+            &if l.command.is_empty() { l.process_name.clone().unwrap_or_else(|| "-".to_string()) } else { l.command.join(" ") },
+        ]))
+        .collect();
+    columns(&["PROTO", "ADDRESS", "PID", "USER", "COMMAND"], &[false, false, true, false, false], rows)
+}
+
+// "127.0.0.1:80", "[::1]:80", and "*" for an unbound port
+fn endpoint(address: IpAddr, port: u16) -> String {
+    let port = if port == 0 { "*".to_string() } else { port.to_string() };
+    match address {
+        IpAddr::V4(address) => format!("{}:{}", address, port),
+        IpAddr::V6(address) => format!("[{}]:{}", address, port),
+    }
+}
+
+fn row<const N: usize>(cells: [&str; N]) -> Vec<String> {
+    cells.iter().map(|cell| cell.to_string()).collect()
+}
+
+// Two-column "Label  value" listing
+fn key_values(rows: Vec<Vec<String>>) -> String {
+    let width = rows.iter().map(|r| r[0].chars().count()).max().unwrap_or(0);
+    let mut out = String::new();
+    for r in rows {
+        writeln!(out, "{:<width$}  {}", r[0], r[1], width = width).unwrap();
+    }
+    out
+}
+
+// Aligned columns with a header row; `right` marks numeric columns
+fn columns(headers: &[&str], right: &[bool], rows: Vec<Vec<String>>) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for r in &rows {
+        for (width, cell) in widths.iter_mut().zip(r) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let header = headers.iter().map(|h| h.to_string()).collect();
+    for r in std::iter::once(header).chain(rows) {
+        let cells: Vec<String> = r.iter()
+            .zip(&widths)
+            .zip(right)
+            .map(|((cell, &width), &right)| {
+                if right {
+                    format!("{:>width$}", cell, width = width)
+                } else {
+                    format!("{:<width$}", cell, width = width)
+                }
+            })
+            .collect();
+        writeln!(out, "{}", cells.join("  ").trim_end()).unwrap();
+    }
+    out
+}
+
+// Same units as formatBytes: powers of 1024, two decimals
+pub fn format_bytes(bytes: f64) -> String {
+    format_scaled(bytes, &["B", "KB", "MB", "GB", "TB"])
+}
+
+pub fn format_throughput(bytes_per_second: f64) -> String {
+    format_scaled(bytes_per_second, &["B/s", "KB/s", "MB/s", "GB/s", "TB/s"])
+}
+
+fn format_scaled(value: f64, units: &[&str]) -> String {
+    if value <= 0.0 {
+        return format!("0 {}", units[0]);
+    }
+    if value < 1.0 {
+        return format!("{:.2} {}", value, units[0]);
+    }
+
+    let i = ((value.ln() / 1024f64.ln()).floor() as usize).min(units.len() - 1);
+    format!("{:.2} {}", value / 1024f64.powi(i as i32), units[i])
+}
+
+pub fn format_percentage(value: f64) -> String {
+    format!("{:.1}%", value)
+}
+
+// Same as formatDuration: the two most significant units
+pub fn format_duration(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    let hours = minutes / 60;
+    let days = hours / 24;
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if minutes < 60 {
+        format!("{}m", minutes)
+    } else if hours < 24 {
+        format!("{}h {}m", hours, minutes % 60)
+    } else {
+        format!("{}d {}h", days, hours % 24)
+    }
+}
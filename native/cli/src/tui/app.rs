@@ -0,0 +1,204 @@
+use peep_core::{
+    Collector, CpuSnapshot, DiskDetail, MemorySnapshot, ProcessDetail, ProcessIdentity, ProcessRecord, RefreshPlan,
+};
+use ratatui::widgets::TableState;
+use std::collections::VecDeque;
+
+use crate::{sort_processes, SortKey};
+
+// Enough points to fill a wide terminal; sparklines show the newest that fit
+const HISTORY_LEN: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    // Typing into the process filter
+    Filter,
+    // Waiting for y/n before killing this process, which is only killed if
+    // its pid hasn't been reused meanwhile
+    ConfirmKill { identity: ProcessIdentity, name: String },
+}
+
+// A rolling series of per-second rates for a sparkline
+#[derive(Default)]
+pub struct Series {
+    pub points: VecDeque<u64>,
+}
+
+impl Series {
+    fn push(&mut self, rate: f64) {
+        if self.points.len() == HISTORY_LEN {
+            self.points.pop_front();
+        }
+        self.points.push_back(rate.max(0.0) as u64);
+    }
+
+    pub fn latest(&self) -> u64 {
+        self.points.back().copied().unwrap_or(0)
+    }
+
+    // The newest `width` points, oldest first
+    pub fn tail(&self, width: usize) -> Vec<u64> {
+        let skip = self.points.len().saturating_sub(width);
+        self.points.iter().skip(skip).copied().collect()
+    }
+}
+
+pub struct App {
+    collector: Collector,
+    pub cpu: Option<CpuSnapshot>,
+    pub memory: Option<MemorySnapshot>,
+    pub disk_read: Series,
+    pub disk_write: Series,
+    pub network_rx: Series,
+    pub network_tx: Series,
+    processes: Vec<ProcessRecord>,
+    // Filtered and sorted view of `processes` shown in the table
+    pub visible: Vec<ProcessRecord>,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub filter: String,
+    pub threads: bool,
+    pub mode: Mode,
+    pub table: TableState,
+    // Result of the last action, shown in the footer
+    pub status: Option<String>,
+}
+
+impl App {
+    pub fn new(collector: Collector) -> Self {
+        App {
+            collector,
+            cpu: None,
+            memory: None,
+            disk_read: Series::default(),
+            disk_write: Series::default(),
+            network_rx: Series::default(),
+            network_tx: Series::default(),
+            processes: Vec::new(),
+            visible: Vec::new(),
+            sort: SortKey::Cpu,
+            reverse: false,
+            filter: String::new(),
+            threads: false,
+            mode: Mode::Normal,
+            table: TableState::default().with_selected(0),
+            status: None,
+        }
+    }
+
+    // Only what the screen shows: no volume space, no process command lines
+    fn plan(&self) -> RefreshPlan {
+        RefreshPlan {
+            cpu: true,
+            memory: true,
+            disks: Some(DiskDetail::Io),
+            network: true,
+            processes: Some(ProcessDetail { threads: self.threads, command: false, user: true, network: false }),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let plan = self.plan();
+        let sample = self.collector.collect(&plan);
+
+        if let Some(cpu) = sample.cpu {
+            self.cpu = Some(cpu);
+        }
+        if let Some(memory) = sample.memory {
+            self.memory = Some(memory);
+        }
+        if let Some(disk) = sample.disk {
+            self.disk_read.push(disk.read_rate);
+            self.disk_write.push(disk.write_rate);
+        }
+        if let Some(network) = sample.network {
+            self.network_rx.push(network.rx_rate);
+            self.network_tx.push(network.tx_rate);
+        }
+        if let Some(processes) = sample.processes {
+            self.processes = processes;
+        }
+        self.update_visible();
+    }
+
+    // Re-apply filter and sort, keeping the same process selected if it's still shown
+    pub fn update_visible(&mut self) {
+        let selected_pid = self.selected().map(|p| p.pid);
+
+        let filter = self.filter.to_lowercase();
+        self.visible = self.processes.iter()
+            .filter(|p| {
+                filter.is_empty()
+                    || p.name.to_lowercase().contains(&filter)
+                    || p.user.to_lowercase().contains(&filter)
+                    || p.pid.to_string() == filter
+            })
+            .cloned()
+            .collect();
+        sort_processes(&mut self.visible, self.sort);
+        if self.reverse {
+            self.visible.reverse();
+        }
+
+        let index = selected_pid
+            .and_then(|pid| self.visible.iter().position(|p| p.pid == pid))
+            .unwrap_or(0);
+        self.table.select((!self.visible.is_empty()).then_some(index));
+    }
+
+    pub fn selected(&self) -> Option<&ProcessRecord> {
+        self.table.selected().and_then(|i| self.visible.get(i))
+    }
+
+    pub fn move_selection(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let last = self.visible.len() - 1;
+        let current = self.table.selected().unwrap_or(0);
+        let next = current.saturating_add_signed(delta).min(last);
+        self.table.select(Some(next));
+    }
+
+    pub fn select_first(&mut self) {
+        self.table.select((!self.visible.is_empty()).then_some(0));
+    }
+
+    pub fn select_last(&mut self) {
+        self.table.select(self.visible.len().checked_sub(1));
+    }
+
+    // Pressing the active sort key again reverses the order
+    pub fn sort_by(&mut self, sort: SortKey) {
+        if self.sort == sort {
+            self.reverse = !self.reverse;
+        } else {
+            self.sort = sort;
+            self.reverse = false;
+        }
+        self.update_visible();
+    }
+
+    pub fn toggle_threads(&mut self) {
+        self.threads = !self.threads;
+        self.refresh();
+    }
+
+    pub fn request_kill(&mut self) {
+        if let Some(process) = self.selected() {
+            self.mode = Mode::ConfirmKill { identity: process.identity(), name: process.name.clone() };
+        }
+    }
+
+    // Same path as the `kill_process` export
+    pub fn kill(&mut self, identity: ProcessIdentity) {
+        let pid = identity.pid;
+        let result = self.collector.kill(identity, false);
+        self.status = Some(format!("{}: {}", pid, result.message));
+        self.mode = Mode::Normal;
+        if result.success {
+            self.refresh();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+// Full-screen terminal interface (`peep top`): the dashboard and process list
+// from the app, drawn with ratatui so it works over SSH.
+
+use peep_core::Collector;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::io;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::SortKey;
+
+mod app;
+mod ui;
+
+use app::{App, Mode};
+
+pub fn run(interval: Duration) -> io::Result<()> {
+    let mut collector = Collector::new();
+    // Baseline so the first frame already has CPU usage and rates
+    collector.processes(false);
+    thread::sleep(peep_core::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let mut app = App::new(collector);
+    app.refresh();
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, interval);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut ratatui::DefaultTerminal, app: &mut App, interval: Duration) -> io::Result<()> {
+    let mut next_refresh = Instant::now() + interval;
+
+    loop {
+        terminal.draw(|frame| ui::draw(frame, app))?;
+
+        let timeout = next_refresh.saturating_duration_since(Instant::now());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !handle_key(app, key) {
+                    return Ok(());
+                }
+            }
+        }
+
+        if Instant::now() >= next_refresh {
+            app.refresh();
+            next_refresh = Instant::now() + interval;
+        }
+    }
+}
+
+// Returns false when the user asked to quit
+fn handle_key(app: &mut App, key: KeyEvent) -> bool {
+    if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+        return false;
+    }
+
+    match app.mode.clone() {
+        Mode::Filter => match key.code {
+            KeyCode::Enter => app.mode = Mode::Normal,
+            KeyCode::Esc => {
+                app.filter.clear();
+                app.mode = Mode::Normal;
+                app.update_visible();
+            }
+            KeyCode::Backspace => {
+                app.filter.pop();
+                app.update_visible();
+            }
+            KeyCode::Char(c) => {
+                app.filter.push(c);
+                app.update_visible();
+            }
+            _ => {}
+        },
+        Mode::ConfirmKill { identity, .. } => match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => app.kill(identity),
+            _ => app.mode = Mode::Normal,
+        },
+        Mode::Normal => {
+            app.status = None;
+            match key.code {
+                KeyCode::Char('q') => return false,
+                KeyCode::Esc if !app.filter.is_empty() => {
+                    app.filter.clear();
+                    app.update_visible();
+                }
+                KeyCode::Esc => return false,
+                KeyCode::Up => app.move_selection(-1),
+                KeyCode::Down => app.move_selection(1),
+                KeyCode::PageUp => app.move_selection(-20),
+                KeyCode::PageDown => app.move_selection(20),
+                KeyCode::Home => app.select_first(),
+                KeyCode::End => app.select_last(),
+                KeyCode::Char('c') => app.sort_by(SortKey::Cpu),
+                KeyCode::Char('m') => app.sort_by(SortKey::Memory),
+                KeyCode::Char('p') => app.sort_by(SortKey::Pid),
+                KeyCode::Char('n') => app.sort_by(SortKey::Name),
+                KeyCode::Char('/') => app.mode = Mode::Filter,
+                KeyCode::Char('t') => app.toggle_threads(),
+                KeyCode::Char('k') | KeyCode::Delete | KeyCode::F(9) => app.request_kill(),
+                _ => {}
+            }
+        }
+    }
+    true
+}
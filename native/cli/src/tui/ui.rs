@@ -0,0 +1,208 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Cell, Gauge, Paragraph, Row, Sparkline, Table};
+use ratatui::Frame;
+
+use crate::table::{format_bytes, format_duration, format_percentage, format_throughput};
+use crate::SortKey;
+use super::app::{App, Mode, Series};
+
+pub fn draw(frame: &mut Frame, app: &mut App) {
+    let cores = app.cpu.as_ref().map_or(0, |cpu| cpu.per_core.len());
+    // Two core bars per line on wide terminals, capped so the process table keeps most of the screen
+    let core_lines = cores.div_ceil(2).clamp(1, 8) as u16;
+
+    let [top, rates, processes, footer] = Layout::vertical([
+        Constraint::Length(core_lines + 3),
+        Constraint::Length(6),
+        Constraint::Min(5),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    let [cpu, memory] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(top);
+    let [disk, network] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(rates);
+
+    draw_cpu(frame, app, cpu);
+    draw_memory(frame, app, memory);
+    draw_rates(frame, "Disk", ("read", &app.disk_read), ("write", &app.disk_write), disk);
+    draw_rates(frame, "Network", ("rx", &app.network_rx), ("tx", &app.network_tx), network);
+    draw_processes(frame, app, processes);
+    draw_footer(frame, app, footer);
+}
+
+fn usage_color(percent: f64) -> Color {
+    if percent >= 90.0 {
+        Color::Red
+    } else if percent >= 60.0 {
+        Color::Yellow
+    } else {
+        Color::Green
+    }
+}
+
+fn draw_cpu(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(cpu) = &app.cpu else {
+        frame.render_widget(Block::bordered().title(" CPU "), area);
+        return;
+    };
+
+    let title = format!(" CPU {} — {} ", cpu.brand.as_deref().unwrap_or(""), format_percentage(cpu.usage as f64));
+    let block = Block::bordered().title(title);
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [total, cores] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(inner);
+    frame.render_widget(bar("all", cpu.usage as f64, total.width), total);
+
+    // Lay the cores out in as many columns as it takes to fit the available lines
+    let lines = cores.height.max(1) as usize;
+    let columns = cpu.per_core.len().div_ceil(lines).max(1);
+    let areas = Layout::horizontal(vec![Constraint::Ratio(1, columns as u32); columns]).split(cores);
+    for (column, area) in areas.iter().enumerate() {
+        let lines: Vec<Line> = cpu.per_core.iter()
+            .enumerate()
+            .skip(column * lines)
+            .take(lines)
+            .map(|(core, usage)| bar(&core.to_string(), *usage as f64, area.width.saturating_sub(1)))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), *area);
+    }
+}
+
+// "12 [||||||      ] 45.0%" sized to `width` columns
+fn bar(label: &str, percent: f64, width: u16) -> Line<'static> {
+    let value = format!("{:>6}", format_percentage(percent));
+    let fixed = label.len() + value.len() + 4;
+    let inner = (width as usize).saturating_sub(fixed);
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * inner as f64).round() as usize;
+
+    Line::from(vec![
+        Span::raw(format!("{:>3} [", label)).bold(),
+        Span::styled("|".repeat(filled), Style::new().fg(usage_color(percent))),
+        Span::raw(" ".repeat(inner - filled)),
+        Span::raw("]"),
+        Span::raw(value),
+    ])
+}
+
+fn draw_memory(frame: &mut Frame, app: &App, area: Rect) {
+    let block = Block::bordered().title(" Memory ");
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(memory) = &app.memory else {
+        return;
+    };
+
+    let [ram, swap] = Layout::vertical([Constraint::Length(2), Constraint::Length(2)]).areas(inner);
+    frame.render_widget(gauge("RAM", memory.used, memory.total), ram);
+    frame.render_widget(gauge("Swap", memory.used_swap, memory.total_swap), swap);
+}
+
+fn gauge(title: &str, used: u64, total: u64) -> Gauge<'static> {
+    let ratio = if total > 0 { used as f64 / total as f64 } else { 0.0 };
+    Gauge::default()
+        .block(Block::new().title(format!("{} {} / {}", title, format_bytes(used as f64), format_bytes(total as f64))))
+        .gauge_style(Style::new().fg(usage_color(ratio * 100.0)))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(format_percentage(ratio * 100.0))
+}
+
+fn draw_rates(frame: &mut Frame, title: &str, first: (&str, &Series), second: (&str, &Series), area: Rect) {
+    let block = Block::bordered().title(format!(" {} ", title));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let areas = Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(inner);
+    for ((label, series), (area, color)) in [first, second].into_iter().zip(areas.iter().zip([Color::Cyan, Color::Magenta])) {
+        let [label_area, spark_area] = Layout::horizontal([Constraint::Length(18), Constraint::Min(0)]).areas(*area);
+        let text = format!("{:<6}{}", label, format_throughput(series.latest() as f64));
+        frame.render_widget(Paragraph::new(text).fg(color), label_area);
+        frame.render_widget(
+            Sparkline::default().data(series.tail(spark_area.width as usize)).style(Style::new().fg(color)),
+            spark_area,
+        );
+    }
+}
+
+fn draw_processes(frame: &mut Frame, app: &mut App, area: Rect) {
+    let arrow = if app.reverse { "▲" } else { "▼" };
+    let heading = |name: &str, key: Option<SortKey>| {
+        if key == Some(app.sort) {
+            Cell::from(format!("{}{}", name, arrow)).underlined()
+        } else {
+            Cell::from(name.to_string())
+        }
+    };
+
+    let header = Row::new([
+        heading("PID", Some(SortKey::Pid)),
+        heading("USER", None),
+        heading("CPU%", Some(SortKey::Cpu)),
+        heading("MEM", Some(SortKey::Memory)),
+        heading("STATUS", None),
+        heading("TIME", None),
+        heading("NAME", Some(SortKey::Name)),
+    ])
+    .bold();
+
+    let rows = app.visible.iter().map(|p| {
+        Row::new([
+            Cell::from(p.pid.to_string()),
+            Cell::from(p.user.clone()),
+            Cell::from(format!("{:.1}", p.cpu)).fg(usage_color(p.cpu as f64)),
+            Cell::from(format_bytes(p.memory as f64)),
+            Cell::from(p.status.clone()),
+            Cell::from(format_duration(p.run_time)),
+            Cell::from(p.name.clone()),
+        ])
+    });
+
+    let mut title = format!(" Processes ({}) ", app.visible.len());
+    if !app.filter.is_empty() {
+        title = format!(" Processes ({}) — filter: {} ", app.visible.len(), app.filter);
+    }
+    if app.threads {
+        title.push_str("[threads] ");
+    }
+
+    let table = Table::new(rows, [
+        Constraint::Length(8),
+        Constraint::Length(10),
+        Constraint::Length(6),
+        Constraint::Length(11),
+        Constraint::Length(9),
+        Constraint::Length(8),
+        Constraint::Min(10),
+    ])
+    .header(header)
+    .block(Block::new().borders(Borders::TOP).title(title))
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+
+    frame.render_stateful_widget(table, area, &mut app.table);
+}
+
+fn draw_footer(frame: &mut Frame, app: &App, area: Rect) {
+    let line = match &app.mode {
+        Mode::Filter => Line::from(vec![
+            Span::raw("Filter: ").bold(),
+            Span::raw(app.filter.clone()),
+            Span::raw("_").slow_blink(),
+            Span::raw("   Enter keep · Esc clear").dim(),
+        ]),
+        Mode::ConfirmKill { identity, name } => Line::from(vec![
+            Span::raw(format!("Kill {} ({})? ", name, identity.pid)).bold().fg(Color::Red),
+            Span::raw("y/n"),
+        ]),
+        Mode::Normal => {
+            let help = "q quit · ↑↓ select · c/m/p/n sort · / filter · t threads · k kill";
+            match &app.status {
+                Some(status) => Line::from(vec![Span::raw(status.clone()).bold(), Span::raw("   "), Span::raw(help).dim()]),
+                None => Line::from(Span::raw(help).dim()),
+            }
+        }
+    };
+    frame.render_widget(Paragraph::new(line), area);
+}
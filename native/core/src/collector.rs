@@ -0,0 +1,212 @@
+use crate::connections::Protocol;
+use crate::control::{ControlError, ControlErrorCode, ProcessTarget};
+use crate::disk::disk_snapshot;
+use crate::disk_filter::{disk_filter, DiskFilter};
+use crate::network::network_snapshot;
+use crate::plan::{DiskDetail, ProcessDetail, RefreshPlan};
+use crate::ports::{find_process_by_port, ListeningPort};
+use crate::power::battery_from;
+use crate::process::process_record;
+use crate::rate::Tracked;
+use crate::snapshot::{
+    CpuSnapshot, MemorySnapshot, DiskSnapshot, NetworkSnapshot, SystemSnapshot,
+    PartialSnapshot, ProcessRecord, BatterySnapshot, KillResult,
+};
+use crate::source::{DiskReading, InterfaceReading, MetricsSource, ProcessReading};
+use crate::sysinfo_source::SysinfoSource;
+use crate::tree::{process_tree, ProcessNode};
+
+// Turns a source's raw readings into snapshots, keeping the previous I/O
+// counters so deltas and rates can be reported. Each consumer that samples
+// independently should own its own collector so refreshes don't skew each
+// other's deltas.
+pub struct Collector {
+    source: Box<dyn MetricsSource>,
+    disks: Tracked<DiskReading>,
+    networks: Tracked<InterfaceReading>,
+    processes: Tracked<ProcessReading>,
+    disk_filter: Option<DiskFilter>,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector {
+    // Collect from this machine
+    pub fn new() -> Self {
+        Self::with_source(SysinfoSource::new())
+    }
+
+    pub fn with_source(source: impl MetricsSource + 'static) -> Self {
+        // The source's first readings are the baseline for the first deltas
+        let now = source.now();
+        Collector {
+            disks: Tracked::new(now, source.disks()),
+            networks: Tracked::new(now, source.networks()),
+            processes: Tracked::new(now, Vec::new()),
+            disk_filter: None,
+            source: Box::new(source),
+        }
+    }
+
+    // List volumes with `filter` rather than the process-wide filter
+    pub fn with_disk_filter(mut self, filter: DiskFilter) -> Self {
+        self.disk_filter = Some(filter);
+        self
+    }
+
+    // Refresh and report exactly what `plan` asks for
+    pub fn collect(&mut self, plan: &RefreshPlan) -> PartialSnapshot {
+        self.refresh(plan);
+        PartialSnapshot {
+            cpu: plan.cpu.then(|| self.cpu_snapshot()),
+            memory: plan.memory.then(|| self.memory_snapshot()),
+            disk: plan.disks.map(|detail| self.disk_snapshot(detail)),
+            network: plan.network.then(|| network_snapshot(&self.networks)),
+            processes: plan.processes.map(|detail| self.process_records(detail)),
+        }
+    }
+
+    // Get CPU usage information
+    pub fn cpu(&mut self) -> CpuSnapshot {
+        self.refresh(&RefreshPlan::cpu());
+        self.cpu_snapshot()
+    }
+
+    // Get memory information
+    pub fn memory(&mut self) -> MemorySnapshot {
+        self.refresh(&RefreshPlan::memory());
+        self.memory_snapshot()
+    }
+
+    // Get disk I/O and volume information
+    pub fn disks(&mut self) -> DiskSnapshot {
+        self.refresh(&RefreshPlan::disks(DiskDetail::Volumes));
+        self.disk_snapshot(DiskDetail::Volumes)
+    }
+
+    // Get network I/O information
+    pub fn network(&mut self) -> NetworkSnapshot {
+        self.refresh(&RefreshPlan::network());
+        network_snapshot(&self.networks)
+    }
+
+    // Get all system information
+    pub fn system(&mut self) -> SystemSnapshot {
+        self.refresh(&RefreshPlan::system());
+        SystemSnapshot {
+            cpu: self.cpu_snapshot(),
+            memory: self.memory_snapshot(),
+            disk: self.disk_snapshot(DiskDetail::Volumes),
+            network: network_snapshot(&self.networks),
+        }
+    }
+
+    // Get list of processes, optionally including threads. This is the
+    // process table's view, so per-process traffic is included.
+    pub fn processes(&mut self, show_threads: bool) -> Vec<ProcessRecord> {
+        let detail = ProcessDetail { threads: show_threads, network: true, ..ProcessDetail::default() };
+        self.refresh(&RefreshPlan::processes(detail));
+        self.process_records(detail)
+    }
+
+    // Get processes nested by parent, with subtree totals
+    pub fn process_tree(&mut self) -> Vec<ProcessNode> {
+        process_tree(&self.processes(false))
+    }
+
+    // Get battery information for the first battery found
+    pub fn battery(&self) -> BatterySnapshot {
+        battery_from(self.source.battery())
+    }
+
+    // Kill a process. An identity target is only killed while its pid still
+    // belongs to the process it was taken from, and protected processes only
+    // when `force` is set.
+    pub fn kill(&mut self, target: impl Into<ProcessTarget>, force: bool) -> KillResult {
+        let target = target.into();
+        let pid = target.pid();
+        if let ProcessTarget::Identity(expected) = &target {
+            match self.source.identity(pid) {
+                Some(current) if expected.matches(&current) => {}
+                Some(_) => return ControlError::replaced(pid).into(),
+                None => return ControlError::not_found().into(),
+            }
+        }
+        match self.source.kill(pid, force) {
+            Ok(()) => KillResult::ok("Process killed successfully"),
+            Err(err) => err.into(),
+        }
+    }
+
+    // Kill whatever listens on `port`, in either address family
+    pub fn kill_port(&mut self, port: u16, protocol: Protocol, force: bool) -> KillResult {
+        let transport = if protocol.is_tcp() { "TCP" } else { "UDP" };
+        let failure = |code: ControlErrorCode, message: String| KillResult::from(ControlError::new(code, message));
+        match find_process_by_port(port, protocol) {
+            Ok(Some(ListeningPort { pid: Some(pid), .. })) => self.kill(pid, force),
+            // Only the owner (or root) can see which process holds a socket
+            Ok(Some(_)) => failure(
+                ControlErrorCode::PermissionDenied,
+                format!("The owner of {} port {} is unknown (it may belong to another user)", transport, port),
+            ),
+            Ok(None) => failure(ControlErrorCode::NoSuchProcess, format!("Nothing is listening on {} port {}", transport, port)),
+            Err(err) => KillResult::from(ControlError { errno: err.raw_os_error(), ..ControlError::new(ControlErrorCode::Failed, err.to_string()) }),
+        }
+    }
+
+    fn refresh(&mut self, plan: &RefreshPlan) {
+        self.source.refresh(plan);
+        let now = self.source.now();
+        if plan.disks.is_some() {
+            self.disks.update(now, self.source.disks());
+        }
+        if plan.network {
+            self.networks.update(now, self.source.networks());
+        }
+        if let Some(detail) = plan.processes {
+            self.processes.update(now, self.source.processes(detail));
+        }
+    }
+
+    fn process_records(&self, detail: ProcessDetail) -> Vec<ProcessRecord> {
+        self.processes.current()
+            .iter()
+            // Filter out threads unless asked for, only keep actual processes
+            .filter(|process| detail.threads || !process.is_thread)
+            .map(|process| process_record(process, &self.processes, detail))
+            .collect()
+    }
+
+    fn disk_snapshot(&self, detail: DiskDetail) -> DiskSnapshot {
+        match &self.disk_filter {
+            Some(filter) => disk_snapshot(&self.disks, detail, filter),
+            None => disk_snapshot(&self.disks, detail, &disk_filter()),
+        }
+    }
+
+    fn cpu_snapshot(&self) -> CpuSnapshot {
+        let cpu = self.source.cpu();
+        CpuSnapshot {
+            usage: cpu.usage,
+            cores: cpu.per_core.len(),
+            brand: cpu.brand,
+            per_core: cpu.per_core,
+        }
+    }
+
+    fn memory_snapshot(&self) -> MemorySnapshot {
+        let memory = self.source.memory();
+        MemorySnapshot {
+            total: memory.total,
+            used: memory.used,
+            free: memory.free,
+            total_swap: memory.total_swap,
+            used_swap: memory.used_swap,
+            free_swap: memory.free_swap,
+        }
+    }
+}
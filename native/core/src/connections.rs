@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use crate::sockets::socket_owners;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Tcp6,
+    Udp,
+    Udp6,
+}
+
+impl Protocol {
+    pub const ALL: [Protocol; 4] = [Protocol::Tcp, Protocol::Tcp6, Protocol::Udp, Protocol::Udp6];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Tcp6 => "tcp6",
+            Protocol::Udp => "udp",
+            Protocol::Udp6 => "udp6",
+        }
+    }
+
+    pub fn is_tcp(self) -> bool {
+        matches!(self, Protocol::Tcp | Protocol::Tcp6)
+    }
+}
+
+// TCP states as the kernel numbers them in /proc/net/tcp. UDP sockets only
+// use two: `Established` once connect()ed, `Close` (shown as unconnected by
+// ss) otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionState {
+    Established,
+    SynSent,
+    SynRecv,
+    FinWait1,
+    FinWait2,
+    TimeWait,
+    Close,
+    CloseWait,
+    LastAck,
+    Listen,
+    Closing,
+    NewSynRecv,
+}
+
+impl ConnectionState {
+    fn from_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0x01 => ConnectionState::Established,
+            0x02 => ConnectionState::SynSent,
+            0x03 => ConnectionState::SynRecv,
+            0x04 => ConnectionState::FinWait1,
+            0x05 => ConnectionState::FinWait2,
+            0x06 => ConnectionState::TimeWait,
+            0x07 => ConnectionState::Close,
+            0x08 => ConnectionState::CloseWait,
+            0x09 => ConnectionState::LastAck,
+            0x0A => ConnectionState::Listen,
+            0x0B => ConnectionState::Closing,
+            0x0C => ConnectionState::NewSynRecv,
+            _ => return None,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ConnectionState::Established => "ESTABLISHED",
+            ConnectionState::SynSent => "SYN_SENT",
+            ConnectionState::SynRecv => "SYN_RECV",
+            ConnectionState::FinWait1 => "FIN_WAIT1",
+            ConnectionState::FinWait2 => "FIN_WAIT2",
+            ConnectionState::TimeWait => "TIME_WAIT",
+            ConnectionState::Close => "CLOSE",
+            ConnectionState::CloseWait => "CLOSE_WAIT",
+            ConnectionState::LastAck => "LAST_ACK",
+            ConnectionState::Listen => "LISTEN",
+            ConnectionState::Closing => "CLOSING",
+            ConnectionState::NewSynRecv => "NEW_SYN_RECV",
+        }
+    }
+}
+
+// One socket from /proc/net/{tcp,tcp6,udp,udp6}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection {
+    pub protocol: Protocol,
+    pub local_address: IpAddr,
+    pub local_port: u16,
+    pub remote_address: IpAddr,
+    pub remote_port: u16,
+    pub state: ConnectionState,
+    // Bytes waiting to be sent and to be read by the application; for
+    // listening TCP sockets, the accept backlog instead
+    pub send_queue: u64,
+    pub receive_queue: u64,
+    pub uid: u32,
+    pub inode: u64,
+    // The owning process, when its descriptors could be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+}
+
+// Which connections `connections` returns; empty lists match everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConnectionFilter {
+    pub protocols: Vec<Protocol>,
+    pub states: Vec<ConnectionState>,
+    pub pid: Option<u32>,
+}
+
+impl ConnectionFilter {
+    fn matches(&self, connection: &Connection) -> bool {
+        (self.protocols.is_empty() || self.protocols.contains(&connection.protocol))
+            && (self.states.is_empty() || self.states.contains(&connection.state))
+            && self.pid.is_none_or(|pid| connection.pid == Some(pid))
+    }
+}
+
+// Every socket of this network namespace that `filter` lets through, with
+// its owning process. Only Linux is supported; elsewhere there are none, so
+// callers that poll don't fail on every call.
+pub fn connections(filter: &ConnectionFilter) -> io::Result<Vec<Connection>> {
+    if !cfg!(target_os = "linux") {
+        return Ok(Vec::new());
+    }
+
+    let proc_root = Path::new("/proc");
+    let mut connections = Vec::new();
+    for protocol in Protocol::ALL {
+        if !filter.protocols.is_empty() && !filter.protocols.contains(&protocol) {
+            continue;
+        }
+        match fs::read_to_string(proc_root.join("net").join(protocol.name())) {
+            Ok(table) => connections.extend(parse_table(&table, protocol)),
+            // No IPv6 in this kernel
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let owners = socket_owners(proc_root);
+    let mut names: HashMap<u32, Option<String>> = HashMap::new();
+    for connection in &mut connections {
+        connection.pid = owners.get(&connection.inode).copied();
+        connection.process_name = connection.pid.and_then(|pid| {
+            names.entry(pid).or_insert_with(|| process_name(proc_root, pid)).clone()
+        });
+    }
+
+    connections.retain(|connection| filter.matches(connection));
+    Ok(connections)
+}
+
+fn process_name(proc_root: &Path, pid: u32) -> Option<String> {
+    let comm = fs::read_to_string(proc_root.join(pid.to_string()).join("comm")).ok()?;
+    Some(comm.trim_end_matches('\n').to_string())
+}
+
+// Parse the contents of one /proc/net/{tcp,tcp6,udp,udp6} table, skipping
+// its header and any line that doesn't parse. Owners are left unresolved.
+pub fn parse_table(table: &str, protocol: Protocol) -> Vec<Connection> {
+    table.lines().skip(1).filter_map(|line| parse_line(line, protocol)).collect()
+}
+
+//   sl  local_address rem_address   st tx_queue:rx_queue tr:tm->when retrnsmt   uid  timeout inode
+//    0: 0100007F:0CEA 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41325 ...
+fn parse_line(line: &str, protocol: Protocol) -> Option<Connection> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 10 {
+        return None;
+    }
+    let (local_address, local_port) = parse_endpoint(fields[1])?;
+    let (remote_address, remote_port) = parse_endpoint(fields[2])?;
+    let state = ConnectionState::from_code(u8::from_str_radix(fields[3], 16).ok()?)?;
+    let (send_queue, receive_queue) = fields[4].split_once(':')?;
+
+    Some(Connection {
+        protocol,
+        local_address,
+        local_port,
+        remote_address,
+        remote_port,
+        state,
+        send_queue: u64::from_str_radix(send_queue, 16).ok()?,
+        receive_queue: u64::from_str_radix(receive_queue, 16).ok()?,
+        uid: fields[7].parse().ok()?,
+        inode: fields[9].parse().ok()?,
+        pid: None,
+        process_name: None,
+    })
+}
+
+// "0100007F:0CEA": the address is the raw network-order bytes printed as
+// native-endian 32-bit words, the port is in host order
+fn parse_endpoint(endpoint: &str) -> Option<(IpAddr, u16)> {
+    let (address, port) = endpoint.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+
+    let mut bytes = Vec::with_capacity(16);
+    for word in 0..address.len() / 8 {
+        let word = u32::from_str_radix(address.get(word * 8..word * 8 + 8)?, 16).ok()?;
+        bytes.extend(word.to_ne_bytes());
+    }
+    let address = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some((address, port))
+}
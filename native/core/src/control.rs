@@ -0,0 +1,590 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, Signal, System, ThreadKind, UpdateKind, SUPPORTED_SIGNALS};
+
+use crate::protection::protection_policy;
+use crate::snapshot::{KillResult, ProcessIdentity};
+use crate::sysinfo_source::{exe_path, identity_of, is_alive};
+
+// How often `terminate_gracefully` checks whether the process has exited
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+// How long a SIGKILLed process gets to disappear before we give up on it
+const KILL_WAIT: Duration = Duration::from_secs(1);
+
+// POSIX errno values, the same on Linux and macOS
+const EPERM: i32 = 1;
+const ESRCH: i32 = 3;
+const EINVAL: i32 = 22;
+
+// The signals sysinfo can deliver, by their usual names without "SIG".
+// Which ones work depends on the platform: Windows only supports KILL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProcessSignal {
+    Hup,
+    Int,
+    Quit,
+    Ill,
+    Trap,
+    Abrt,
+    Iot,
+    Bus,
+    Fpe,
+    Kill,
+    Usr1,
+    Segv,
+    Usr2,
+    Pipe,
+    Alrm,
+    Term,
+    Chld,
+    Cont,
+    Stop,
+    Tstp,
+    Ttin,
+    Ttou,
+    Urg,
+    Xcpu,
+    Xfsz,
+    Vtalrm,
+    Prof,
+    Winch,
+    Io,
+    Poll,
+    Pwr,
+    Sys,
+}
+
+const SIGNALS: [(ProcessSignal, &str, Signal); 32] = [
+    (ProcessSignal::Hup, "HUP", Signal::Hangup),
+    (ProcessSignal::Int, "INT", Signal::Interrupt),
+    (ProcessSignal::Quit, "QUIT", Signal::Quit),
+    (ProcessSignal::Ill, "ILL", Signal::Illegal),
+    (ProcessSignal::Trap, "TRAP", Signal::Trap),
+    (ProcessSignal::Abrt, "ABRT", Signal::Abort),
+    (ProcessSignal::Iot, "IOT", Signal::IOT),
+    (ProcessSignal::Bus, "BUS", Signal::Bus),
+    (ProcessSignal::Fpe, "FPE", Signal::FloatingPointException),
+    (ProcessSignal::Kill, "KILL", Signal::Kill),
+    (ProcessSignal::Usr1, "USR1", Signal::User1),
+    (ProcessSignal::Segv, "SEGV", Signal::Segv),
+    (ProcessSignal::Usr2, "USR2", Signal::User2),
+    (ProcessSignal::Pipe, "PIPE", Signal::Pipe),
+    (ProcessSignal::Alrm, "ALRM", Signal::Alarm),
+    (ProcessSignal::Term, "TERM", Signal::Term),
+    (ProcessSignal::Chld, "CHLD", Signal::Child),
+    (ProcessSignal::Cont, "CONT", Signal::Continue),
+    (ProcessSignal::Stop, "STOP", Signal::Stop),
+    (ProcessSignal::Tstp, "TSTP", Signal::TSTP),
+    (ProcessSignal::Ttin, "TTIN", Signal::TTIN),
+    (ProcessSignal::Ttou, "TTOU", Signal::TTOU),
+    (ProcessSignal::Urg, "URG", Signal::Urgent),
+    (ProcessSignal::Xcpu, "XCPU", Signal::XCPU),
+    (ProcessSignal::Xfsz, "XFSZ", Signal::XFSZ),
+    (ProcessSignal::Vtalrm, "VTALRM", Signal::VirtualAlarm),
+    (ProcessSignal::Prof, "PROF", Signal::Profiling),
+    (ProcessSignal::Winch, "WINCH", Signal::Winch),
+    (ProcessSignal::Io, "IO", Signal::IO),
+    (ProcessSignal::Poll, "POLL", Signal::Poll),
+    (ProcessSignal::Pwr, "PWR", Signal::Power),
+    (ProcessSignal::Sys, "SYS", Signal::Sys),
+];
+
+impl ProcessSignal {
+    fn entry(self) -> &'static (ProcessSignal, &'static str, Signal) {
+        SIGNALS.iter().find(|(signal, _, _)| *signal == self).unwrap()
+    }
+
+    // "TERM"
+    pub fn name(self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn is_supported(self) -> bool {
+        SUPPORTED_SIGNALS.contains(&self.entry().2)
+    }
+
+    fn sysinfo(self) -> Signal {
+        self.entry().2
+    }
+}
+
+impl fmt::Display for ProcessSignal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SIG{}", self.name())
+    }
+}
+
+// "TERM", "term" or "SIGTERM"
+impl FromStr for ProcessSignal {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.to_ascii_uppercase();
+        let name = upper.strip_prefix("SIG").unwrap_or(&upper);
+        SIGNALS.iter()
+            .find(|(_, n, _)| *n == name)
+            .map(|(signal, _, _)| *signal)
+            .ok_or_else(|| format!("Unknown signal: {}", s))
+    }
+}
+
+// Which step of `terminate_gracefully` ended the process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TerminationStep {
+    Term,
+    Kill,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminateResult {
+    pub success: bool,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_by: Option<TerminationStep>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ControlErrorCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errno: Option<i32>,
+}
+
+// Why a control call failed, so callers can react to the cause (e.g. offer
+// to retry with elevated privileges) instead of parsing the message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ControlErrorCode {
+    // EPERM: usually another user's process
+    PermissionDenied,
+    // ESRCH: it has exited, or never existed
+    NoSuchProcess,
+    // The signal doesn't exist on this platform
+    SignalUnsupported,
+    // Refused because of what the process is, e.g. Peep itself
+    ProtectedProcess,
+    // The pid was reused after the target's identity was taken
+    IdentityMismatch,
+    // Anything else, with the errno when there is one
+    Failed,
+}
+
+impl ControlErrorCode {
+    fn from_errno(errno: i32) -> Self {
+        match errno {
+            EPERM => ControlErrorCode::PermissionDenied,
+            ESRCH => ControlErrorCode::NoSuchProcess,
+            EINVAL => ControlErrorCode::SignalUnsupported,
+            _ => ControlErrorCode::Failed,
+        }
+    }
+
+    // What kill(2) would set errno to for the same condition
+    fn errno(self) -> Option<i32> {
+        match self {
+            ControlErrorCode::PermissionDenied | ControlErrorCode::ProtectedProcess => Some(EPERM),
+            ControlErrorCode::NoSuchProcess | ControlErrorCode::IdentityMismatch => Some(ESRCH),
+            ControlErrorCode::SignalUnsupported => Some(EINVAL),
+            ControlErrorCode::Failed => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ControlError {
+    pub code: ControlErrorCode,
+    pub errno: Option<i32>,
+    pub message: String,
+}
+
+impl ControlError {
+    pub fn new(code: ControlErrorCode, message: impl Into<String>) -> Self {
+        ControlError { code, errno: code.errno(), message: message.into() }
+    }
+
+    pub fn not_found() -> Self {
+        ControlError::new(ControlErrorCode::NoSuchProcess, "Process not found")
+    }
+
+    pub fn replaced(pid: u32) -> Self {
+        ControlError::new(ControlErrorCode::IdentityMismatch, format!("PID {} now belongs to a different process", pid))
+    }
+
+    fn unsupported(signal: ProcessSignal) -> Self {
+        ControlError::new(ControlErrorCode::SignalUnsupported, format!("{} is not supported on this platform", signal))
+    }
+
+    // From the errno a failed kill(2) left behind
+    fn from_errno(errno: Option<i32>, signal: ProcessSignal, pid: u32) -> Self {
+        let code = errno.map_or(ControlErrorCode::Failed, ControlErrorCode::from_errno);
+        let message = match (code, errno) {
+            (ControlErrorCode::PermissionDenied, _) => {
+                format!("Not permitted to send {} to PID {} (it may belong to another user)", signal, pid)
+            }
+            (ControlErrorCode::NoSuchProcess, _) => "Process not found".to_string(),
+            (ControlErrorCode::SignalUnsupported, _) => format!("{} is not supported on this platform", signal),
+            (_, Some(errno)) => format!("Failed to send {}: {}", signal, io::Error::from_raw_os_error(errno)),
+            (_, None) => format!("Failed to send {}", signal),
+        };
+        ControlError { code, errno, message }
+    }
+}
+
+impl fmt::Display for ControlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ControlError {}
+
+impl From<ControlError> for KillResult {
+    fn from(err: ControlError) -> Self {
+        KillResult { success: false, message: err.message, code: Some(err.code), errno: err.errno }
+    }
+}
+
+// The order `signal_processes` walks a process tree in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TreeOrder {
+    // Descendants before their parents, so nothing is orphaned while it's
+    // still running
+    #[default]
+    BottomUp,
+    // Parents first, so a supervisor can't restart children it sees exit
+    TopDown,
+}
+
+// Which processes `signal_processes` signals, starting from one pid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignalScope {
+    // The process and all its descendants
+    Tree(TreeOrder),
+    // Every process in its process group (Unix only)
+    Group,
+    // Every process in its session (Unix only)
+    Session,
+}
+
+// What happened to one process of a `signal_processes` call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalOutcome {
+    pub pid: u32,
+    pub name: String,
+    pub success: bool,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ControlErrorCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errno: Option<i32>,
+}
+
+impl SignalOutcome {
+    fn new(pid: u32, name: String, result: Result<String, ControlError>) -> Self {
+        match result {
+            Ok(message) => SignalOutcome { pid, name, success: true, message, code: None, errno: None },
+            Err(err) => SignalOutcome { pid, name, success: false, message: err.message, code: Some(err.code), errno: err.errno },
+        }
+    }
+}
+
+// The process a control call acts on: a bare pid, or the identity it had
+// when it was listed, which is checked first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessTarget {
+    Pid(u32),
+    Identity(ProcessIdentity),
+}
+
+impl ProcessTarget {
+    pub fn pid(&self) -> u32 {
+        match self {
+            ProcessTarget::Pid(pid) => *pid,
+            ProcessTarget::Identity(identity) => identity.pid,
+        }
+    }
+}
+
+impl From<u32> for ProcessTarget {
+    fn from(pid: u32) -> Self {
+        ProcessTarget::Pid(pid)
+    }
+}
+
+impl From<ProcessIdentity> for ProcessTarget {
+    fn from(identity: ProcessIdentity) -> Self {
+        ProcessTarget::Identity(identity)
+    }
+}
+
+// Send `signal` to the target. Success means it was delivered, not that the
+// process acted on it. Protected processes are refused unless `force`.
+pub fn send_signal(target: impl Into<ProcessTarget>, signal: ProcessSignal, force: bool) -> KillResult {
+    match deliver(&mut System::new(), &target.into(), signal, force) {
+        Ok(()) => KillResult::ok(format!("Sent {}", signal)),
+        Err(err) => err.into(),
+    }
+}
+
+// Send `signal` to every process in `scope`, one at a time, reporting on
+// each. Protected processes in scope are refused unless `force`, and Peep
+// itself always is; each shows up as a failed `protectedProcess` outcome.
+// Processes that exit on their own before their turn count as a success.
+pub fn signal_processes(
+    target: impl Into<ProcessTarget>,
+    signal: ProcessSignal,
+    scope: SignalScope,
+    force: bool,
+) -> Vec<SignalOutcome> {
+    let target = target.into();
+    let pid = target.pid();
+    let mut system = System::new();
+    if let Err(err) = lookup(&mut system, &target) {
+        return vec![SignalOutcome::new(pid, String::new(), Err(err))];
+    }
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::nothing());
+
+    let pids = match scope {
+        SignalScope::Tree(order) => tree_pids(&system, pid, order),
+        SignalScope::Group | SignalScope::Session => {
+            let id = if scope == SignalScope::Group { process_group } else { session };
+            match id(pid) {
+                Some(target) => {
+                    let mut pids: Vec<u32> = system.processes().iter()
+                        .filter(|(_, process)| process.thread_kind().is_none() && is_alive(process))
+                        .map(|(pid, _)| pid.as_u32())
+                        .filter(|&pid| id(pid) == Some(target))
+                        .collect();
+                    pids.sort();
+                    pids
+                }
+                None => {
+                    let message = if cfg!(unix) { "Failed to read the process group" } else { "Process groups are not supported on this platform" };
+                    return vec![SignalOutcome::new(pid, String::new(), Err(ControlError::new(ControlErrorCode::Failed, message)))];
+                }
+            }
+        }
+    };
+
+    pids.into_iter()
+        .map(|pid| {
+            let name = system.process(Pid::from_u32(pid))
+                .map(|process| process.name().to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let result = match deliver(&mut system, &ProcessTarget::Pid(pid), signal, force) {
+                Ok(()) => Ok(format!("Sent {}", signal)),
+                // e.g. a parent that exits once its children are gone
+                Err(err) if err.code == ControlErrorCode::NoSuchProcess => Ok("Exited before it was signalled".to_string()),
+                Err(err) => Err(err),
+            };
+            SignalOutcome::new(pid, name, result)
+        })
+        .collect()
+}
+
+// `pid` and its descendants, parents before children or the reverse. A
+// child that started before its parent is a pid-reuse leftover, not a child.
+fn tree_pids(system: &System, pid: u32, order: TreeOrder) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (child, process) in system.processes() {
+        if process.thread_kind().is_some() || !is_alive(process) {
+            continue;
+        }
+        let Some(parent) = process.parent().and_then(|parent| system.process(parent)) else {
+            continue;
+        };
+        if parent.pid() != *child && parent.start_time() <= process.start_time() {
+            children.entry(parent.pid().as_u32()).or_default().push(child.as_u32());
+        }
+    }
+
+    // Breadth first, so every parent comes before its children
+    let mut pids = vec![pid];
+    let mut seen = HashSet::from([pid]);
+    let mut next = 0;
+    while let Some(&parent) = pids.get(next) {
+        next += 1;
+        let mut kids = children.remove(&parent).unwrap_or_default();
+        kids.sort();
+        pids.extend(kids.into_iter().filter(|kid| seen.insert(*kid)));
+    }
+    if order == TreeOrder::BottomUp {
+        pids.reverse();
+    }
+    pids
+}
+
+#[cfg(unix)]
+fn process_group(pid: u32) -> Option<u32> {
+    let group = unsafe { libc::getpgid(pid as libc::pid_t) };
+    (group > 0).then_some(group as u32)
+}
+
+#[cfg(unix)]
+fn session(pid: u32) -> Option<u32> {
+    let session = unsafe { libc::getsid(pid as libc::pid_t) };
+    (session > 0).then_some(session as u32)
+}
+
+#[cfg(not(unix))]
+fn process_group(_pid: u32) -> Option<u32> {
+    None
+}
+
+#[cfg(not(unix))]
+fn session(_pid: u32) -> Option<u32> {
+    None
+}
+
+// Ask the target to exit with SIGTERM and wait up to `timeout` for it to do
+// so, then SIGKILL it. Where SIGTERM isn't supported it's killed straight away.
+pub fn terminate_gracefully(target: impl Into<ProcessTarget>, timeout: Duration, force: bool) -> TerminateResult {
+    let target = target.into();
+    let mut system = System::new();
+    let ended = |message: String, step: TerminationStep| {
+        TerminateResult { success: true, message, ended_by: Some(step), code: None, errno: None }
+    };
+    let failed = |err: ControlError| {
+        TerminateResult { success: false, message: err.message, ended_by: None, code: Some(err.code), errno: err.errno }
+    };
+
+    // Pin a bare pid to the process it names now, so a pid reused while
+    // waiting is neither taken for the target nor sent SIGKILL
+    let target = match target {
+        ProcessTarget::Pid(pid) => match resolve(&mut system, pid) {
+            Ok(identity) => ProcessTarget::Identity(identity),
+            Err(err) => return failed(err),
+        },
+        target => target,
+    };
+
+    let killed = match deliver(&mut system, &target, ProcessSignal::Term, force) {
+        Ok(()) => {
+            if wait_for_exit(&mut system, &target, timeout) {
+                return ended("Process exited after SIGTERM".to_string(), TerminationStep::Term);
+            }
+            format!("Process killed after ignoring SIGTERM for {} ms", timeout.as_millis())
+        }
+        Err(err) if err.code == ControlErrorCode::SignalUnsupported => {
+            "Process killed (SIGTERM is not supported on this platform)".to_string()
+        }
+        Err(err) => return failed(err),
+    };
+
+    match deliver(&mut system, &target, ProcessSignal::Kill, force) {
+        Ok(()) if wait_for_exit(&mut system, &target, KILL_WAIT) => ended(killed, TerminationStep::Kill),
+        Ok(()) => failed(ControlError::new(ControlErrorCode::Failed, "Process still running after SIGKILL")),
+        // It exited just as the timeout ran out
+        Err(err) if matches!(err.code, ControlErrorCode::NoSuchProcess | ControlErrorCode::IdentityMismatch) => {
+            ended("Process exited after SIGTERM".to_string(), TerminationStep::Term)
+        }
+        Err(err) => failed(err),
+    }
+}
+
+fn deliver(system: &mut System, target: &ProcessTarget, signal: ProcessSignal, force: bool) -> Result<(), ControlError> {
+    lookup(system, target)?;
+    let Some(process) = system.process(Pid::from_u32(target.pid())) else {
+        return Err(ControlError::not_found());
+    };
+    check_protection(process, force)?;
+    signal_process(process, signal)
+}
+
+// Refuse what the protection policy covers, unless forced. Peep itself is
+// refused even then.
+pub(crate) fn check_protection(process: &Process, force: bool) -> Result<(), ControlError> {
+    let pid = process.pid().as_u32();
+    if force && pid != std::process::id() {
+        return Ok(());
+    }
+    let name = process.name().to_string_lossy();
+    let exe = exe_path(process);
+    let kernel_thread = process.thread_kind() == Some(ThreadKind::Kernel);
+    match protection_policy().reason(pid, &name, exe.as_deref(), kernel_thread, started_by_peep(process)) {
+        Some(reason) => Err(ControlError::new(ControlErrorCode::ProtectedProcess, reason)),
+        None => Ok(()),
+    }
+}
+
+// Whether Peep is among the process's ancestors. Each parent is looked up
+// fresh, since the caller usually only refreshed the process itself.
+fn started_by_peep(process: &Process) -> bool {
+    let peep = Pid::from_u32(std::process::id());
+    let mut system = System::new();
+    let mut seen = HashSet::new();
+    let mut parent = process.parent();
+    while let Some(pid) = parent {
+        if pid == peep {
+            return true;
+        }
+        // Stop at init, and at a loop a process exiting mid-walk could cause
+        if pid.as_u32() <= 1 || !seen.insert(pid) {
+            return false;
+        }
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, ProcessRefreshKind::nothing().without_tasks());
+        parent = system.process(pid).and_then(|process| process.parent());
+    }
+    false
+}
+
+// Send `signal` to a process sysinfo has already looked up
+pub(crate) fn signal_process(process: &Process, signal: ProcessSignal) -> Result<(), ControlError> {
+    match process.kill_with(signal.sysinfo()) {
+        Some(true) => Ok(()),
+        Some(false) => Err(ControlError::from_errno(last_errno(), signal, process.pid().as_u32())),
+        None => Err(ControlError::unsupported(signal)),
+    }
+}
+
+// sysinfo returns straight after kill(2) fails, so errno is still its
+#[cfg(unix)]
+fn last_errno() -> Option<i32> {
+    io::Error::last_os_error().raw_os_error()
+}
+
+#[cfg(not(unix))]
+fn last_errno() -> Option<i32> {
+    None
+}
+
+// Refresh just the target's pid and check it's still the target. A zombie
+// only waits for its parent to collect the exit status, so counts as gone.
+fn lookup(system: &mut System, target: &ProcessTarget) -> Result<(), ControlError> {
+    let pid = Pid::from_u32(target.pid());
+    let kind = ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, kind);
+    match (system.process(pid).filter(|process| is_alive(process)), target) {
+        (None, _) => Err(ControlError::not_found()),
+        (Some(process), ProcessTarget::Identity(expected)) if !expected.matches(&identity_of(process)) => {
+            Err(ControlError::replaced(target.pid()))
+        }
+        (Some(_), _) => Ok(()),
+    }
+}
+
+fn resolve(system: &mut System, pid: u32) -> Result<ProcessIdentity, ControlError> {
+    lookup(system, &ProcessTarget::Pid(pid))?;
+    system.process(Pid::from_u32(pid)).map(identity_of).ok_or_else(ControlError::not_found)
+}
+
+fn wait_for_exit(system: &mut System, target: &ProcessTarget, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if lookup(system, target).is_err() {
+            return true;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline - now));
+    }
+}
@@ -0,0 +1,49 @@
+use crate::disk_filter::DiskFilter;
+use crate::plan::DiskDetail;
+use crate::rate::Tracked;
+use crate::snapshot::{DiskDetails, DiskSnapshot};
+use crate::source::DiskReading;
+
+// I/O totals always cover every device; volumes are only listed at
+// `DiskDetail::Volumes`, and only those `filter` lets through
+pub fn disk_snapshot(disks: &Tracked<DiskReading>, detail: DiskDetail, filter: &DiskFilter) -> DiskSnapshot {
+    // Calculate total read/write across all disks
+    let mut read = 0u64;
+    let mut write = 0u64;
+    let mut total_read = 0u64;
+    let mut total_write = 0u64;
+
+    for disk in filter.devices(disks.current()) {
+        read += disks.delta(disk, |d| d.total_read);
+        write += disks.delta(disk, |d| d.total_written);
+        total_read += disk.total_read;
+        total_write += disk.total_written;
+    }
+
+    let details = match detail {
+        DiskDetail::Io => Vec::new(),
+        DiskDetail::Volumes => {
+            filter.apply(disks.current())
+                .into_iter()
+                .map(|disk| DiskDetails {
+                    name: disk.name.clone(),
+                    mount_point: disk.mount_point.clone(),
+                    total_space: disk.total_space,
+                    available_space: disk.available_space,
+                    used_space: disk.total_space.saturating_sub(disk.available_space),
+                    file_system: disk.file_system.clone(),
+                })
+                .collect()
+        }
+    };
+
+    DiskSnapshot {
+        read,
+        write,
+        read_rate: disks.per_second(read),
+        write_rate: disks.per_second(write),
+        total_read,
+        total_write,
+        disks: details,
+    }
+}
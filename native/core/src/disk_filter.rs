@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::source::DiskReading;
+
+// Which volumes are listed. Every disk API goes through the same filter, so
+// the volume list looks the same whichever call produced it.
+//
+// A volume is listed when it matches an include rule, or matches none of the
+// exclude rules. Shadowed mount points and duplicate devices are then dropped
+// whatever the include rules say.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiskFilter {
+    // File system types that are never listed (case-insensitive)
+    pub exclude_file_systems: Vec<String>,
+    // Mount points at or below these are not listed
+    pub exclude_mount_prefixes: Vec<String>,
+    // Hide removable media (ejectable drives, mounted disk images)
+    pub exclude_removable: bool,
+    // File system types listed even when an exclude rule matches
+    pub include_file_systems: Vec<String>,
+    // Mount points at or below these are listed even when an exclude rule matches
+    pub include_mount_prefixes: Vec<String>,
+    // Mount points hidden while another mount point is present
+    pub shadowed: Vec<Shadowed>,
+    // List a device mounted more than once (bind mounts, btrfs subvolumes)
+    // only at its shortest mount point, and count its I/O once
+    pub dedup_devices: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Shadowed {
+    pub mount_point: String,
+    pub shadowed_by: String,
+}
+
+impl Default for DiskFilter {
+    fn default() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::macos()
+        } else if cfg!(target_os = "linux") {
+            Self::linux()
+        } else {
+            Self::none()
+        }
+    }
+}
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+impl DiskFilter {
+    // List every volume
+    pub fn none() -> Self {
+        DiskFilter {
+            exclude_file_systems: Vec::new(),
+            exclude_mount_prefixes: Vec::new(),
+            exclude_removable: false,
+            include_file_systems: Vec::new(),
+            include_mount_prefixes: Vec::new(),
+            shadowed: Vec::new(),
+            dedup_devices: false,
+        }
+    }
+
+    // Hide virtual filesystems, mounted DMGs and the APFS system volumes. The
+    // sealed system volume at / shares its container with the data volume, so
+    // only the data volume is listed when it exists (Catalina and later).
+    pub fn macos() -> Self {
+        DiskFilter {
+            exclude_file_systems: strings(&["devfs", "autofs", "nullfs"]),
+            exclude_mount_prefixes: strings(&["/System/Volumes"]),
+            exclude_removable: true,
+            include_mount_prefixes: strings(&["/System/Volumes/Data"]),
+            shadowed: vec![Shadowed {
+                mount_point: "/".to_string(),
+                shadowed_by: "/System/Volumes/Data".to_string(),
+            }],
+            ..Self::none()
+        }
+    }
+
+    // Hide kernel and in-memory filesystems, container layers, snap packages
+    // and bind mounts. Removable media stays listed, including udisks mounts
+    // under /run/media.
+    pub fn linux() -> Self {
+        DiskFilter {
+            exclude_file_systems: strings(&[
+                "tmpfs", "devtmpfs", "ramfs", "overlay", "squashfs", "proc", "sysfs", "cgroup", "cgroup2",
+                "devpts", "mqueue", "debugfs", "tracefs", "securityfs", "pstore", "bpf", "configfs",
+                "fusectl", "hugetlbfs", "autofs", "binfmt_misc", "efivarfs", "nsfs", "rpc_pipefs",
+                "fuse.lxcfs", "fuse.snapfuse", "fuse.portal", "fuse.gvfsd-fuse",
+            ]),
+            exclude_mount_prefixes: strings(&[
+                "/proc", "/sys", "/dev", "/run", "/snap", "/var/snap", "/var/lib/docker", "/var/lib/containers",
+            ]),
+            include_mount_prefixes: strings(&["/run/media"]),
+            dedup_devices: true,
+            ..Self::none()
+        }
+    }
+
+    // The volumes this filter lists, in their original order
+    pub fn apply<'a>(&self, disks: &'a [DiskReading]) -> Vec<&'a DiskReading> {
+        let listed = disks.iter().filter(|disk| self.lists(disk, disks));
+        if self.dedup_devices {
+            unique_devices(listed)
+        } else {
+            listed.collect()
+        }
+    }
+
+    // The disks whose I/O counts towards the totals: all of them, but each
+    // device only once when deduplicating
+    pub fn devices<'a>(&self, disks: &'a [DiskReading]) -> Vec<&'a DiskReading> {
+        if self.dedup_devices {
+            unique_devices(disks.iter())
+        } else {
+            disks.iter().collect()
+        }
+    }
+
+    fn lists(&self, disk: &DiskReading, disks: &[DiskReading]) -> bool {
+        if !self.includes(disk) && self.excludes(disk) {
+            return false;
+        }
+        !self.shadowed.iter().any(|shadow| {
+            shadow.mount_point == disk.mount_point && disks.iter().any(|d| d.mount_point == shadow.shadowed_by)
+        })
+    }
+
+    fn includes(&self, disk: &DiskReading) -> bool {
+        has_file_system(&self.include_file_systems, disk)
+            || self.include_mount_prefixes.iter().any(|prefix| is_under(&disk.mount_point, prefix))
+    }
+
+    fn excludes(&self, disk: &DiskReading) -> bool {
+        (self.exclude_removable && disk.is_removable)
+            || has_file_system(&self.exclude_file_systems, disk)
+            || self.exclude_mount_prefixes.iter().any(|prefix| is_under(&disk.mount_point, prefix))
+    }
+}
+
+fn has_file_system(file_systems: &[String], disk: &DiskReading) -> bool {
+    file_systems.iter().any(|fs| fs.eq_ignore_ascii_case(&disk.file_system))
+}
+
+// Whether `mount_point` is `prefix` or below it, by whole path components:
+// "/dev" covers "/dev/shm" but not "/devel"
+fn is_under(mount_point: &str, prefix: &str) -> bool {
+    let prefix = prefix.trim_end_matches('/');
+    match mount_point.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/') || prefix.is_empty(),
+        None => false,
+    }
+}
+
+// Keep the shortest mount point of each device. Only devices named by path
+// are merged: virtual filesystems share names like "tmpfs" without sharing
+// storage, and Windows volumes are often unnamed.
+fn unique_devices<'a>(disks: impl Iterator<Item = &'a DiskReading>) -> Vec<&'a DiskReading> {
+    let disks: Vec<&DiskReading> = disks.collect();
+    let mut shortest: HashMap<&str, &DiskReading> = HashMap::new();
+    for disk in &disks {
+        if disk.name.starts_with('/') {
+            shortest.entry(&disk.name)
+                .and_modify(|kept| {
+                    if disk.mount_point.len() < kept.mount_point.len() {
+                        *kept = disk;
+                    }
+                })
+                .or_insert(disk);
+        }
+    }
+    disks.into_iter()
+        .filter(|disk| match shortest.get(disk.name.as_str()) {
+            Some(kept) => std::ptr::eq(*kept, *disk),
+            None => true,
+        })
+        .collect()
+}
+
+// Applied by collectors that haven't been given a filter of their own;
+// `None` until the user overrides the OS defaults
+static FILTER: RwLock<Option<DiskFilter>> = RwLock::new(None);
+
+// The filter in effect for this process
+pub fn disk_filter() -> DiskFilter {
+    FILTER.read().unwrap().clone().unwrap_or_default()
+}
+
+// Replace the filter for this process; `None` restores the OS defaults
+pub fn set_disk_filter(filter: Option<DiskFilter>) {
+    *FILTER.write().unwrap() = filter;
+}
@@ -0,0 +1,201 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::collector::Collector;
+use crate::plan::{ProcessDetail, RefreshPlan};
+use crate::prometheus;
+use crate::snapshot::{BatterySnapshot, ProcessRecord, SystemSnapshot};
+
+// Minimal HTTP listener that serves `GET /metrics` in Prometheus text format,
+// collecting fresh on every scrape. Scrapes are handled one at a time on a
+// single thread with its own collector, so rates are per scrape interval.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExporterOptions {
+    // How many processes, by CPU usage, are exported per scrape
+    pub top_processes: usize,
+}
+
+impl Default for ExporterOptions {
+    fn default() -> Self {
+        ExporterOptions { top_processes: 20 }
+    }
+}
+
+// Where the exporters get their data. A collector reads the live machine;
+// the native module feeds them a running replay instead, when there is one.
+pub trait MetricsFeed: Send {
+    fn system(&mut self) -> SystemSnapshot;
+    fn battery(&mut self) -> BatterySnapshot;
+    // Processes without threads
+    fn processes(&mut self) -> Vec<ProcessRecord>;
+}
+
+impl MetricsFeed for Collector {
+    fn system(&mut self) -> SystemSnapshot {
+        Collector::system(self)
+    }
+
+    fn battery(&mut self) -> BatterySnapshot {
+        Collector::battery(self)
+    }
+
+    // Exported processes are labelled by pid and name only
+    fn processes(&mut self) -> Vec<ProcessRecord> {
+        let detail = ProcessDetail { command: false, user: false, ..ProcessDetail::default() };
+        self.collect(&RefreshPlan::processes(detail)).processes.unwrap_or_default()
+    }
+}
+
+pub struct Exporter {
+    addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Exporter {
+    // Bind `addr` (port 0 picks a free port) and start serving in the background
+    pub fn start(addr: impl ToSocketAddrs, options: ExporterOptions) -> io::Result<Self> {
+        Self::start_with(addr, options, Collector::new())
+    }
+
+    // As `start`, scraping `feed` instead of a collector of its own
+    pub fn start_with(addr: impl ToSocketAddrs, options: ExporterOptions, feed: impl MetricsFeed + 'static) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::Builder::new()
+                .name("peep-exporter".to_string())
+                .spawn(move || serve(listener, options, feed, &stop))?
+        };
+
+        Ok(Exporter { addr, stop, thread: Some(thread) })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    // Stop accepting scrapes and wait for the listener thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Wake the blocking accept so the thread sees the flag. A wildcard
+        // address can't be connected to everywhere, so use loopback instead.
+        let mut wake = self.addr;
+        if wake.ip().is_unspecified() {
+            wake.set_ip(match wake.ip() {
+                IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+                IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+            });
+        }
+        let _ = TcpStream::connect_timeout(&wake, Duration::from_secs(1));
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+
+    // Block the calling thread until the exporter stops (for the CLI)
+    pub fn wait(mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for Exporter {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.shutdown();
+        }
+    }
+}
+
+fn serve(listener: TcpListener, options: ExporterOptions, mut feed: impl MetricsFeed, stop: &AtomicBool) {
+    // Process CPU usage is a delta, so the first scrape needs a previous refresh
+    feed.processes();
+
+    for stream in listener.incoming() {
+        if stop.load(Ordering::SeqCst) {
+            break;
+        }
+        if let Ok(stream) = stream {
+            if let Err(err) = handle(stream, &mut feed, options) {
+                eprintln!("peep exporter: {}", err);
+            }
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream, feed: &mut impl MetricsFeed, options: ExporterOptions) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Drain the headers; nothing in them changes the response
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim_end().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or("");
+    let path = parts.next().unwrap_or("");
+    let path = path.split('?').next().unwrap_or(path);
+
+    match (method, path) {
+        ("GET", "/metrics") => {
+            let body = scrape(feed, options);
+            respond(&mut stream, "200 OK", prometheus::CONTENT_TYPE, &body)
+        }
+        ("GET", "/") => respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><head><title>Peep Exporter</title></head><body><h1>Peep Exporter</h1><p><a href=\"/metrics\">Metrics</a></p></body></html>\n",
+        ),
+        ("GET", _) => respond(&mut stream, "404 Not Found", "text/plain", "Not found\n"),
+        _ => respond(&mut stream, "405 Method Not Allowed", "text/plain", "Method not allowed\n"),
+    }
+}
+
+fn scrape(feed: &mut impl MetricsFeed, options: ExporterOptions) -> String {
+    let started = Instant::now();
+
+    let system = feed.system();
+    let battery = feed.battery();
+    let mut processes = feed.processes();
+    processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    processes.truncate(options.top_processes);
+
+    let mut body = prometheus::render(&system, &battery, &processes);
+    body.push_str("# HELP peep_scrape_duration_seconds Time spent collecting this scrape.\n");
+    body.push_str("# TYPE peep_scrape_duration_seconds gauge\n");
+    body.push_str(&format!("peep_scrape_duration_seconds {}\n", started.elapsed().as_secs_f64()));
+    body
+}
+
+fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
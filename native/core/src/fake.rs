@@ -0,0 +1,152 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::control::{ControlError, ControlErrorCode};
+use crate::protection::protection_policy;
+use crate::plan::{ProcessDetail, RefreshPlan};
+use crate::snapshot::ProcessIdentity;
+use crate::source::{
+    BatteryReading, CpuReading, DiskReading, InterfaceReading, MemoryReading, MetricsSource, ProcessReading,
+};
+
+// A scripted machine for tests. The test keeps a clone of the source and
+// moves the machine from one known state to the next with `advance`, so the
+// collector's rates and shaping can be checked against exact values.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Machine {
+    pub cpu: CpuReading,
+    pub memory: MemoryReading,
+    pub disks: Vec<DiskReading>,
+    pub networks: Vec<InterfaceReading>,
+    pub processes: Vec<ProcessReading>,
+    pub battery: Option<BatteryReading>,
+}
+
+#[derive(Default)]
+struct State {
+    machine: Machine,
+    now: Duration,
+    killed: Vec<u32>,
+}
+
+// Clones share the same machine
+#[derive(Clone, Default)]
+pub struct FakeSource {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeSource {
+    pub fn new(machine: Machine) -> Self {
+        FakeSource {
+            state: Arc::new(Mutex::new(State { machine, ..State::default() })),
+        }
+    }
+
+    // Let `elapsed` pass and put the machine in its next state
+    pub fn advance(&self, elapsed: Duration, machine: Machine) {
+        let mut state = self.state.lock().unwrap();
+        state.now += elapsed;
+        state.machine = machine;
+    }
+
+    // Change the current state in place, without moving the clock
+    pub fn update(&self, change: impl FnOnce(&mut Machine)) {
+        change(&mut self.state.lock().unwrap().machine);
+    }
+
+    pub fn machine(&self) -> Machine {
+        self.state.lock().unwrap().machine.clone()
+    }
+
+    // PIDs killed through the source, in order
+    pub fn killed(&self) -> Vec<u32> {
+        self.state.lock().unwrap().killed.clone()
+    }
+}
+
+impl MetricsSource for FakeSource {
+    fn now(&self) -> Duration {
+        self.state.lock().unwrap().now
+    }
+
+    // The script decides when the machine changes, so there's nothing to do
+    fn refresh(&mut self, _plan: &RefreshPlan) {}
+
+    fn cpu(&self) -> CpuReading {
+        self.state.lock().unwrap().machine.cpu.clone()
+    }
+
+    fn memory(&self) -> MemoryReading {
+        self.state.lock().unwrap().machine.memory.clone()
+    }
+
+    fn disks(&self) -> Vec<DiskReading> {
+        self.state.lock().unwrap().machine.disks.clone()
+    }
+
+    fn networks(&self) -> Vec<InterfaceReading> {
+        self.state.lock().unwrap().machine.networks.clone()
+    }
+
+    fn processes(&self, detail: ProcessDetail) -> Vec<ProcessReading> {
+        let mut processes = self.state.lock().unwrap().machine.processes.clone();
+        // Like the real source, only fill in what was asked for
+        for process in &mut processes {
+            if !detail.command {
+                process.command.clear();
+                process.exe = None;
+            }
+            if !detail.user {
+                process.user.clear();
+            }
+            if !detail.network {
+                process.total_net_received = None;
+                process.total_net_transmitted = None;
+            }
+        }
+        processes
+    }
+
+    fn battery(&self) -> Option<BatteryReading> {
+        self.state.lock().unwrap().machine.battery.clone()
+    }
+
+    fn identity(&mut self, pid: u32) -> Option<ProcessIdentity> {
+        let state = self.state.lock().unwrap();
+        let process = state.machine.processes.iter().find(|process| process.pid == pid)?;
+        Some(ProcessIdentity { pid, start_time: process.start_time, exe: process.exe.clone() })
+    }
+
+    // Killed processes disappear from the machine
+    fn kill(&mut self, pid: u32, force: bool) -> Result<(), ControlError> {
+        let mut state = self.state.lock().unwrap();
+        let index = state.machine.processes.iter().position(|process| process.pid == pid).ok_or_else(ControlError::not_found)?;
+        let process = &state.machine.processes[index];
+        // Like the real source, except that fake processes are never kernel threads
+        if !force {
+            let descendant = started_by_peep(&state.machine.processes, process.parent);
+            if let Some(reason) = protection_policy().reason(pid, &process.name, process.exe.as_deref(), false, descendant) {
+                return Err(ControlError::new(ControlErrorCode::ProtectedProcess, reason));
+            }
+        }
+        state.machine.processes.remove(index);
+        state.killed.push(pid);
+        Ok(())
+    }
+}
+
+// Follows fake parents up to this process, the way the real source walks
+// live ones
+fn started_by_peep(processes: &[ProcessReading], mut parent: Option<u32>) -> bool {
+    let peep = std::process::id();
+    // A parent chain can't be longer than the process list without a loop
+    for _ in 0..=processes.len() {
+        match parent {
+            Some(pid) if pid == peep => return true,
+            Some(pid) => parent = processes.iter().find(|process| process.pid == pid).and_then(|process| process.parent),
+            None => return false,
+        }
+    }
+    false
+}
@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::snapshot::SystemSnapshot;
+
+// A single series that can be recorded and queried
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Metric {
+    // Overall CPU usage (percent)
+    Cpu,
+    // Usage of one core (percent)
+    CpuCore(usize),
+    // Memory and swap in use (percent of total)
+    Memory,
+    Swap,
+    // Bytes per second
+    DiskRead,
+    DiskWrite,
+    NetworkRx,
+    NetworkTx,
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Metric::Cpu => write!(f, "cpu"),
+            Metric::CpuCore(core) => write!(f, "cpu.{}", core),
+            Metric::Memory => write!(f, "memory"),
+            Metric::Swap => write!(f, "swap"),
+            Metric::DiskRead => write!(f, "disk.read"),
+            Metric::DiskWrite => write!(f, "disk.write"),
+            Metric::NetworkRx => write!(f, "network.rx"),
+            Metric::NetworkTx => write!(f, "network.tx"),
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cpu" => Ok(Metric::Cpu),
+            "memory" => Ok(Metric::Memory),
+            "swap" => Ok(Metric::Swap),
+            "disk.read" => Ok(Metric::DiskRead),
+            "disk.write" => Ok(Metric::DiskWrite),
+            "network.rx" => Ok(Metric::NetworkRx),
+            "network.tx" => Ok(Metric::NetworkTx),
+            _ => s.strip_prefix("cpu.")
+                .and_then(|core| core.parse().ok())
+                .map(Metric::CpuCore)
+                .ok_or_else(|| format!("Unknown metric: {}", s)),
+        }
+    }
+}
+
+// Bucket width and how many buckets to keep at that width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resolution {
+    pub step_secs: u64,
+    pub capacity: usize,
+}
+
+impl Resolution {
+    fn step_ms(&self) -> u64 {
+        self.step_secs * 1000
+    }
+
+    // How far back this resolution reaches
+    fn span_ms(&self) -> u64 {
+        self.step_ms() * self.capacity as u64
+    }
+}
+
+// 1s for 10 minutes, 10s for 6 hours, 1m for 7 days
+pub const DEFAULT_RESOLUTIONS: [Resolution; 3] = [
+    Resolution { step_secs: 1, capacity: 600 },
+    Resolution { step_secs: 10, capacity: 2_160 },
+    Resolution { step_secs: 60, capacity: 10_080 },
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start: u64,
+    min: f64,
+    max: f64,
+    sum: f64,
+    count: u32,
+}
+
+impl Bucket {
+    fn new(start: u64, value: f64) -> Self {
+        Bucket { start, min: value, max: value, sum: value, count: 1 }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+// One aggregated bucket as returned by queries
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPoint {
+    // Start of the bucket (ms since epoch)
+    pub timestamp: u64,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+// Fixed-size ring of buckets at one resolution
+struct Ring {
+    resolution: Resolution,
+    buckets: VecDeque<Bucket>,
+}
+
+impl Ring {
+    fn new(resolution: Resolution) -> Self {
+        Ring { resolution, buckets: VecDeque::new() }
+    }
+
+    fn record(&mut self, timestamp: u64, value: f64) {
+        let start = timestamp - timestamp % self.resolution.step_ms();
+
+        match self.buckets.back_mut() {
+            Some(last) if last.start == start => last.add(value),
+            Some(last) if last.start > start => {
+                // Late sample: fold it into its bucket, or slot a new one in
+                // place, unless it's already older than this ring reaches
+                if start < last.start.saturating_sub(self.resolution.span_ms()) {
+                    return;
+                }
+                match self.buckets.binary_search_by_key(&start, |b| b.start) {
+                    Ok(i) => self.buckets[i].add(value),
+                    Err(i) => self.buckets.insert(i, Bucket::new(start, value)),
+                }
+            }
+            _ => self.buckets.push_back(Bucket::new(start, value)),
+        }
+
+        // Drop anything that has aged out of this resolution's window
+        let newest = self.buckets.back().map_or(start, |b| b.start);
+        let oldest = newest.saturating_sub(self.resolution.span_ms());
+        while self.buckets.front().is_some_and(|b| b.start < oldest) || self.buckets.len() > self.resolution.capacity {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn query(&self, from: u64, to: u64) -> Vec<HistoryPoint> {
+        self.buckets.iter()
+            .filter(|b| b.start + self.resolution.step_ms() > from && b.start <= to)
+            .map(|b| HistoryPoint {
+                timestamp: b.start,
+                min: b.min,
+                avg: b.sum / b.count as f64,
+                max: b.max,
+            })
+            .collect()
+    }
+}
+
+// In-memory, multi-resolution metric history. Every recorded value is folded
+// into each resolution's current bucket, so coarse series are true min/avg/max
+// aggregates rather than point samples.
+pub struct History {
+    resolutions: Vec<Resolution>,
+    series: BTreeMap<Metric, Vec<Ring>>,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(&DEFAULT_RESOLUTIONS)
+    }
+}
+
+impl History {
+    // `resolutions` should be ordered from finest to coarsest
+    pub fn new(resolutions: &[Resolution]) -> Self {
+        History {
+            resolutions: resolutions.to_vec(),
+            series: BTreeMap::new(),
+        }
+    }
+
+    pub fn record_value(&mut self, metric: Metric, timestamp: u64, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        let resolutions = &self.resolutions;
+        let rings = self.series
+            .entry(metric)
+            .or_insert_with(|| resolutions.iter().copied().map(Ring::new).collect());
+        for ring in rings {
+            ring.record(timestamp, value);
+        }
+    }
+
+    // Record every tracked series from one system snapshot
+    pub fn record(&mut self, timestamp: u64, snapshot: &SystemSnapshot) {
+        self.record_value(Metric::Cpu, timestamp, snapshot.cpu.usage as f64);
+        for (core, usage) in snapshot.cpu.per_core.iter().enumerate() {
+            self.record_value(Metric::CpuCore(core), timestamp, *usage as f64);
+        }
+
+        let memory = &snapshot.memory;
+        if memory.total > 0 {
+            self.record_value(Metric::Memory, timestamp, memory.used as f64 / memory.total as f64 * 100.0);
+        }
+        if memory.total_swap > 0 {
+            self.record_value(Metric::Swap, timestamp, memory.used_swap as f64 / memory.total_swap as f64 * 100.0);
+        }
+
+        self.record_value(Metric::DiskRead, timestamp, snapshot.disk.read_rate);
+        self.record_value(Metric::DiskWrite, timestamp, snapshot.disk.write_rate);
+        self.record_value(Metric::NetworkRx, timestamp, snapshot.network.rx_rate);
+        self.record_value(Metric::NetworkTx, timestamp, snapshot.network.tx_rate);
+    }
+
+    // Buckets overlapping [from, to] (ms since epoch). With no explicit
+    // resolution, the finest one whose window covers the range is used.
+    pub fn query(&self, metric: Metric, from: u64, to: u64, step_secs: Option<u64>) -> Result<Vec<HistoryPoint>, String> {
+        let index = match step_secs {
+            Some(step) => self.resolutions.iter()
+                .position(|r| r.step_secs == step)
+                .ok_or_else(|| format!("Unsupported resolution: {}s", step))?,
+            None => self.auto_resolution(from, to),
+        };
+
+        Ok(self.series
+            .get(&metric)
+            .and_then(|rings| rings.get(index))
+            .map(|ring| ring.query(from, to))
+            .unwrap_or_default())
+    }
+
+    pub fn metrics(&self) -> Vec<Metric> {
+        self.series.keys().copied().collect()
+    }
+
+    // The finest resolution whose window spans the range, measured up to
+    // `to` or now, whichever is earlier, so the choice depends only on what
+    // was asked for and not on when the series was last fed
+    fn auto_resolution(&self, from: u64, to: u64) -> usize {
+        let range = to.min(crate::now_millis()).saturating_sub(from);
+        self.resolutions.iter()
+            .position(|resolution| range < resolution.span_ms())
+            .unwrap_or(self.resolutions.len().saturating_sub(1))
+    }
+}
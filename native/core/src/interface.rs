@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// What kind of link a network interface is
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InterfaceKind {
+    Ethernet,
+    Wireless,
+    // macOS names both en*, and nothing in the name tells them apart
+    EthernetOrWireless,
+    Loopback,
+    Bridge,
+    Bond,
+    Vlan,
+    Veth,
+    Tap,
+    Tunnel,
+    Wireguard,
+    Ppp,
+    PeerToPeer,
+    // Apple Wireless Direct Link, behind AirDrop and Sidecar
+    Awdl,
+    Firewire,
+    #[default]
+    Other,
+}
+
+impl InterfaceKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            InterfaceKind::Ethernet => "Ethernet",
+            InterfaceKind::Wireless => "Wi-Fi",
+            InterfaceKind::EthernetOrWireless => "Ethernet/Wi-Fi",
+            InterfaceKind::Loopback => "Loopback",
+            InterfaceKind::Bridge => "Bridge",
+            InterfaceKind::Bond => "Bond",
+            InterfaceKind::Vlan => "VLAN",
+            InterfaceKind::Veth => "Virtual Ethernet",
+            InterfaceKind::Tap => "TAP",
+            InterfaceKind::Tunnel => "VPN Tunnel",
+            InterfaceKind::Wireguard => "WireGuard",
+            InterfaceKind::Ppp => "PPP",
+            InterfaceKind::PeerToPeer => "Peer-to-Peer",
+            InterfaceKind::Awdl => "Apple Wireless Direct Link",
+            InterfaceKind::Firewire => "FireWire",
+            InterfaceKind::Other => "Other",
+        }
+    }
+}
+
+// An interface's kind, and whether it's backed by hardware rather than
+// created in software (bridges, tunnels, container links)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceClass {
+    pub kind: InterfaceKind,
+    pub physical: bool,
+}
+
+// Classify an interface of this machine: from sysfs on Linux, from its name
+// elsewhere
+pub fn classify(name: &str) -> InterfaceClass {
+    if cfg!(target_os = "linux") {
+        if let Some(class) = classify_sysfs(Path::new("/sys/class/net"), name) {
+            return class;
+        }
+    }
+    classify_name(name)
+}
+
+// Classify from `<root>/<name>` in a /sys/class/net layout; None when the
+// interface has no directory there
+pub fn classify_sysfs(root: &Path, name: &str) -> Option<InterfaceClass> {
+    let dir = root.join(name);
+    if !dir.is_dir() {
+        return None;
+    }
+
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|s| s.trim().to_string());
+    let has = |entry: &str| dir.join(entry).exists();
+    // ARPHRD_* link type from if_arp.h
+    let link_type: u32 = read("type").and_then(|t| t.parse().ok()).unwrap_or(0);
+    let dev_type = read("uevent").and_then(|uevent| {
+        uevent.lines().find_map(|line| line.strip_prefix("DEVTYPE=").map(str::to_string))
+    });
+    // Hardware interfaces link to their bus device; software ones live
+    // under /sys/devices/virtual/net and have no `device`
+    let physical = has("device");
+
+    let kind = if link_type == 772 {
+        InterfaceKind::Loopback
+    } else if has("wireless") || has("phy80211") || dev_type.as_deref() == Some("wlan") {
+        InterfaceKind::Wireless
+    } else if has("bridge") || dev_type.as_deref() == Some("bridge") {
+        InterfaceKind::Bridge
+    } else if has("bonding") || dev_type.as_deref() == Some("bond") {
+        InterfaceKind::Bond
+    } else if dev_type.as_deref() == Some("vlan") {
+        InterfaceKind::Vlan
+    } else if dev_type.as_deref() == Some("wireguard") {
+        InterfaceKind::Wireguard
+    } else if has("tun_flags") {
+        // TAP devices carry Ethernet frames, TUN devices bare IP packets
+        if link_type == 1 { InterfaceKind::Tap } else { InterfaceKind::Tunnel }
+    } else if link_type == 512 {
+        InterfaceKind::Ppp
+    } else if matches!(link_type, 768 | 769 | 776 | 778 | 823) {
+        // IPIP, IP6IP6, SIT, GRE and IP6GRE tunnels
+        InterfaceKind::Tunnel
+    } else if link_type == 1 && physical {
+        InterfaceKind::Ethernet
+    } else if link_type == 1 && read("iflink") != read("ifindex") {
+        // A software Ethernet link attached to a peer: veth pairs and macvlans
+        InterfaceKind::Veth
+    } else {
+        classify_name(name).kind
+    };
+
+    Some(InterfaceClass { kind, physical })
+}
+
+// Infer the kind from naming conventions (macOS, BSD and Linux)
+pub fn classify_name(name: &str) -> InterfaceClass {
+    let starts = |prefixes: &[&str]| prefixes.iter().any(|prefix| name.starts_with(prefix));
+
+    // ap1 and the like are the access point side of Personal Hotspot or
+    // hostapd: wireless, but created in software on top of the real adapter
+    let access_point = starts(&["ap"]);
+
+    let kind = if starts(&["lo"]) {
+        InterfaceKind::Loopback
+    } else if name.contains('.') {
+        // eth0.100
+        InterfaceKind::Vlan
+    } else if starts(&["eth"]) || (starts(&["en"]) && cfg!(target_os = "linux")) {
+        // enp3s0, eno1 and ens33 are predictable Ethernet names on Linux
+        InterfaceKind::Ethernet
+    } else if starts(&["en"]) {
+        // en0, en1 are typically built-in Ethernet/Wi-Fi
+        // en2+ are often USB/Thunderbolt adapters
+        InterfaceKind::EthernetOrWireless
+    } else if access_point || starts(&["wl", "ath"]) {
+        InterfaceKind::Wireless
+    } else if starts(&["fw"]) {
+        InterfaceKind::Firewire
+    } else if starts(&["awdl"]) {
+        InterfaceKind::Awdl
+    } else if starts(&["p2p", "llw"]) {
+        InterfaceKind::PeerToPeer
+    } else if starts(&["br", "docker", "virbr", "cni", "lxcbr"]) {
+        InterfaceKind::Bridge
+    } else if starts(&["bond"]) {
+        InterfaceKind::Bond
+    } else if starts(&["veth"]) {
+        InterfaceKind::Veth
+    } else if starts(&["tap"]) {
+        InterfaceKind::Tap
+    } else if starts(&["wg"]) {
+        InterfaceKind::Wireguard
+    } else if starts(&["ppp"]) {
+        InterfaceKind::Ppp
+    } else if starts(&["utun", "tun", "gif", "stf", "ipsec", "gre", "sit"]) {
+        InterfaceKind::Tunnel
+    } else {
+        InterfaceKind::Other
+    };
+
+    let physical = !access_point && matches!(
+        kind,
+        InterfaceKind::Ethernet | InterfaceKind::Wireless | InterfaceKind::EthernetOrWireless | InterfaceKind::Firewire
+    );
+    InterfaceClass { kind, physical }
+}
+
+// RFC 2863 operational state, as the kernel reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkState {
+    Up,
+    Down,
+    Dormant,
+    LowerLayerDown,
+    NotPresent,
+    Testing,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Duplex {
+    Full,
+    Half,
+}
+
+// Link-layer details the OS knows about an interface; all None where it
+// doesn't say (virtual interfaces have no speed, macOS has no sysfs)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LinkInfo {
+    pub state: Option<LinkState>,
+    // Negotiated speed in Mbit/s
+    pub speed_mbps: Option<u64>,
+    pub duplex: Option<Duplex>,
+}
+
+// Link details of an interface of this machine
+pub fn link_info(name: &str) -> LinkInfo {
+    if cfg!(target_os = "linux") {
+        link_info_sysfs(Path::new("/sys/class/net"), name)
+    } else {
+        LinkInfo::default()
+    }
+}
+
+// Read link details from `<root>/<name>` in a /sys/class/net layout
+pub fn link_info_sysfs(root: &Path, name: &str) -> LinkInfo {
+    let dir = root.join(name);
+    // `speed` and `duplex` fail to read while the link is down
+    let read = |file: &str| fs::read_to_string(dir.join(file)).ok().map(|s| s.trim().to_string());
+
+    let state = read("operstate").and_then(|state| match state.as_str() {
+        "up" => Some(LinkState::Up),
+        "down" => Some(LinkState::Down),
+        "dormant" => Some(LinkState::Dormant),
+        "lowerlayerdown" => Some(LinkState::LowerLayerDown),
+        "notpresent" => Some(LinkState::NotPresent),
+        "testing" => Some(LinkState::Testing),
+        // "unknown" is what tunnels and loopback report
+        _ => None,
+    });
+    // -1 (or 4294967295 from older kernels) means unknown
+    let speed_mbps = read("speed")
+        .and_then(|speed| speed.parse::<i64>().ok())
+        .filter(|speed| *speed > 0 && *speed < u32::MAX as i64)
+        .map(|speed| speed as u64);
+    let duplex = read("duplex").and_then(|duplex| match duplex.as_str() {
+        "full" => Some(Duplex::Full),
+        "half" => Some(Duplex::Half),
+        _ => None,
+    });
+
+    LinkInfo { state, speed_mbps, duplex }
+}
+
+// Infer network interface type, the same way snapshots label it
+pub fn get_interface_type(name: &str) -> &'static str {
+    classify(name).kind.label()
+}
+
+// Exclude loopback and virtual interfaces, keep physical adapters; agrees
+// with the `physical` flag snapshots carry
+pub fn is_physical_interface(name: &str) -> bool {
+    classify(name).physical
+}
@@ -0,0 +1,76 @@
+// Pure-Rust collection layer for Peep. Everything here returns plain,
+// serde-serializable snapshot types so it can be shared by the Neon module,
+// command-line tools and tests without a Node runtime. Collection reads from
+// a `MetricsSource`: the real machine by default, or a scripted fake.
+
+mod collector;
+mod connections;
+mod control;
+mod disk;
+mod disk_filter;
+mod exporter;
+mod fake;
+mod history;
+mod interface;
+mod network;
+mod os;
+mod otlp;
+mod plan;
+mod ports;
+mod protection;
+mod power;
+mod process;
+mod prometheus;
+mod rate;
+mod recording;
+mod snapshot;
+mod sock_diag;
+mod sockets;
+mod source;
+mod store;
+mod sysinfo_source;
+mod tree;
+
+pub use collector::Collector;
+pub use connections::{
+    connections, parse_table as parse_connection_table, Connection, ConnectionFilter, ConnectionState, Protocol,
+};
+pub use control::{
+    send_signal, signal_processes, terminate_gracefully, ControlError, ControlErrorCode, ProcessSignal, ProcessTarget, SignalOutcome,
+    SignalScope, TerminateResult, TerminationStep, TreeOrder,
+};
+pub use disk_filter::{disk_filter, set_disk_filter, DiskFilter, Shadowed};
+pub use exporter::{Exporter, ExporterOptions, MetricsFeed};
+pub use fake::{FakeSource, Machine};
+pub use history::{History, HistoryPoint, Metric, Resolution, DEFAULT_RESOLUTIONS};
+pub use interface::{
+    classify as classify_interface, classify_name as classify_interface_name, classify_sysfs as classify_interface_sysfs,
+    get_interface_type, is_physical_interface, link_info_sysfs as interface_link_info_sysfs, Duplex, InterfaceClass,
+    InterfaceKind, LinkInfo, LinkState,
+};
+pub use os::os_info;
+pub use otlp::{export as export_otlp, metrics_request as otlp_metrics_request, OtlpExporter, OtlpOptions};
+pub use plan::{parse_plan, DiskDetail, ProcessDetail, RefreshPlan};
+pub use ports::{find_process_by_port, is_listening, listeners, listening_ports, ListeningPort};
+pub use power::battery_snapshot;
+pub use prometheus::render as render_prometheus;
+pub use protection::{protection_policy, set_protection_policy, ProtectionPolicy};
+pub use recording::{Frame, Recorder, Recording, Replay, RECORDING_VERSION};
+pub use snapshot::*;
+pub use source::{
+    BatteryReading, CpuReading, DiskReading, InterfaceReading, MemoryReading, MetricsSource, ProcessReading,
+};
+pub use store::{Store, StoreOptions, StoreQuery, StoredSample};
+pub use sysinfo_source::SysinfoSource;
+pub use tree::{process_tree, ProcessNode, SubtreeTotal};
+
+// Wall-clock time in ms since the Unix epoch, as used for history timestamps
+pub fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// Refreshing CPU usage faster than this yields meaningless values
+pub use sysinfo::MINIMUM_CPU_UPDATE_INTERVAL;
@@ -0,0 +1,60 @@
+use crate::interface::InterfaceKind;
+use crate::rate::Tracked;
+use crate::snapshot::{InterfaceSnapshot, NetworkSnapshot};
+use crate::source::InterfaceReading;
+
+// Build a network snapshot from freshly updated readings
+pub fn network_snapshot(networks: &Tracked<InterfaceReading>) -> NetworkSnapshot {
+    let received = |data: &InterfaceReading| networks.delta(data, |d| d.total_received);
+    let transmitted = |data: &InterfaceReading| networks.delta(data, |d| d.total_transmitted);
+    let packets_received = |data: &InterfaceReading| networks.delta(data, |d| d.total_packets_received);
+    let packets_transmitted = |data: &InterfaceReading| networks.delta(data, |d| d.total_packets_transmitted);
+
+    // Loopback is never listed; physical adapters and software interfaces
+    // are listed separately
+    let (interfaces, virtual_interfaces): (Vec<InterfaceSnapshot>, Vec<InterfaceSnapshot>) = networks.current().iter()
+        .filter(|data| data.kind != InterfaceKind::Loopback)
+        .map(|data| InterfaceSnapshot {
+            name: data.name.clone(),
+            interface_type: data.kind.label().to_string(),
+            kind: data.kind,
+            physical: data.physical,
+            mac_address: data.mac_address.clone(),
+            addresses: data.addresses.clone(),
+            mtu: data.mtu,
+            state: data.link.state,
+            speed_mbps: data.link.speed_mbps,
+            duplex: data.link.duplex,
+            received: received(data),
+            transmitted: transmitted(data),
+            received_rate: networks.per_second(received(data)),
+            transmitted_rate: networks.per_second(transmitted(data)),
+            total_received: data.total_received,
+            total_transmitted: data.total_transmitted,
+            packets_received: data.total_packets_received,
+            packets_transmitted: data.total_packets_transmitted,
+            packets_received_rate: networks.per_second(packets_received(data)),
+            packets_transmitted_rate: networks.per_second(packets_transmitted(data)),
+        })
+        .partition(|interface| interface.physical);
+
+    // Calculate totals from all interfaces (not just filtered ones)
+    let all = networks.current();
+    let rx = all.iter().map(received).sum();
+    let tx = all.iter().map(transmitted).sum();
+    let rx_packets = all.iter().map(packets_received).sum();
+    let tx_packets = all.iter().map(packets_transmitted).sum();
+
+    NetworkSnapshot {
+        rx,
+        tx,
+        rx_rate: networks.per_second(rx),
+        tx_rate: networks.per_second(tx),
+        rx_packets_rate: networks.per_second(rx_packets),
+        tx_packets_rate: networks.per_second(tx_packets),
+        total_rx: all.iter().map(|data| data.total_received).sum(),
+        total_tx: all.iter().map(|data| data.total_transmitted).sum(),
+        interfaces,
+        virtual_interfaces,
+    }
+}
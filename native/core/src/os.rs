@@ -0,0 +1,103 @@
+use sysinfo::System;
+
+use crate::snapshot::OsInfo;
+
+pub fn os_info() -> OsInfo {
+    let version = System::os_version().unwrap_or_else(|| "Unknown".to_string());
+
+    OsInfo {
+        name: System::name().unwrap_or_else(|| "Unknown".to_string()),
+        marketing_name: marketing_name(&version).map(str::to_string),
+        version,
+        kernel_version: System::kernel_version().unwrap_or_else(|| "Unknown".to_string()),
+        hostname: System::host_name().unwrap_or_else(|| "Unknown".to_string()),
+        uptime: System::uptime(),
+        model: machine_model(),
+    }
+}
+
+// macOS marketing name based on version
+#[cfg(target_os = "macos")]
+fn marketing_name(os_version: &str) -> Option<&'static str> {
+    let name = match os_version.split('.').next()? {
+        "15" => "Sequoia",
+        "14" => "Sonoma",
+        "13" => "Ventura",
+        "12" => "Monterey",
+        "11" => "Big Sur",
+        // For macOS 10.x, check the minor version
+        "10" => match os_version.split('.').nth(1)? {
+            "15" => "Catalina",
+            "14" => "Mojave",
+            "13" => "High Sierra",
+            "12" => "Sierra",
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(not(target_os = "macos"))]
+fn marketing_name(_os_version: &str) -> Option<&'static str> {
+    None
+}
+
+// Get machine model (macOS specific)
+#[cfg(target_os = "macos")]
+fn machine_model() -> Option<String> {
+    use std::process::Command;
+
+    let output = Command::new("sysctl")
+        .arg("-n")
+        .arg("hw.model")
+        .output()
+        .ok()?;
+    let model = String::from_utf8(output.stdout).ok()?;
+    let model_id = model.trim();
+
+    // Map model identifiers to friendly names
+    let friendly_name = match model_id {
+        // MacBook Pro 16-inch models
+        "MacBookPro18,1" | "MacBookPro18,2" => "MacBook Pro (16-inch, 2021)",
+        "MacBookPro18,3" | "MacBookPro18,4" => "MacBook Pro (14-inch, 2021)",
+        "Mac14,5" | "Mac14,6" => "MacBook Pro (16-inch, 2023)",
+        "Mac14,7" | "Mac14,9" => "MacBook Pro (14-inch, 2023)",
+        "Mac15,3" | "Mac15,6" | "Mac15,7" | "Mac15,8" | "Mac15,9" | "Mac15,10" | "Mac15,11" => "MacBook Pro (14-inch, 2024)",
+
+        // MacBook Air models
+        "MacBookAir10,1" => "MacBook Air (M1, 2020)",
+        "Mac14,2" => "MacBook Air (M2, 2022)",
+        "Mac14,15" => "MacBook Air (15-inch, M2, 2023)",
+        "Mac15,12" | "Mac15,13" => "MacBook Air (13-inch, M3, 2024)",
+
+        // Mac Studio
+        "Mac13,1" => "Mac Studio (2022)",
+        "Mac14,13" | "Mac14,14" => "Mac Studio (2023)",
+
+        // Mac mini
+        "Macmini9,1" => "Mac mini (M1, 2020)",
+        "Mac14,3" => "Mac mini (M2, 2023)",
+        "Mac15,14" => "Mac mini (M4, 2024)",
+
+        // iMac
+        "iMac21,1" | "iMac21,2" => "iMac (24-inch, M1, 2021)",
+        "Mac15,4" | "Mac15,5" => "iMac (24-inch, M4, 2024)",
+
+        // Mac Pro
+        "Mac14,8" => "Mac Pro (2023)",
+
+        // Older Intel models (common ones)
+        "MacBookPro16,1" => "MacBook Pro (16-inch, 2019)",
+        "MacBookPro15,1" | "MacBookPro15,3" => "MacBook Pro (15-inch, 2018-2019)",
+        "MacBookPro14,3" => "MacBook Pro (15-inch, 2017)",
+
+        _ => model_id
+    };
+    Some(friendly_name.to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn machine_model() -> Option<String> {
+    None
+}
@@ -0,0 +1,460 @@
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use sysinfo::System;
+
+use crate::collector::Collector;
+use crate::exporter::MetricsFeed;
+use crate::snapshot::{BatterySnapshot, ProcessRecord, SystemSnapshot};
+
+// OpenTelemetry metrics export over OTLP/HTTP with JSON encoding. Metric and
+// attribute names follow the OTel semantic conventions for system, process
+// and hardware (battery) metrics.
+//
+// Only plain `http://` endpoints are supported; point it at a local
+// collector and let that handle TLS and auth to any upstream.
+
+const DEFAULT_PORT: u16 = 4318;
+const DEFAULT_PATH: &str = "/v1/metrics";
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OtlpOptions {
+    // e.g. "http://localhost:4318"; "/v1/metrics" is used when there's no path
+    pub endpoint: String,
+    pub interval: Duration,
+    // How many processes, by CPU usage, are exported per push
+    pub top_processes: usize,
+    // Extra request headers, e.g. for a collector that expects an API key
+    pub headers: Vec<(String, String)>,
+}
+
+impl Default for OtlpOptions {
+    fn default() -> Self {
+        OtlpOptions {
+            endpoint: "http://localhost:4318".to_string(),
+            interval: Duration::from_secs(10),
+            top_processes: 20,
+            headers: Vec::new(),
+        }
+    }
+}
+
+// Background pusher: collects and exports every `interval` until stopped
+pub struct OtlpExporter {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl OtlpExporter {
+    pub fn start(options: OtlpOptions) -> io::Result<Self> {
+        Self::start_with(options, Collector::new())
+    }
+
+    // As `start`, pushing what `feed` reports instead of a collector of its own
+    pub fn start_with(options: OtlpOptions, feed: impl MetricsFeed + 'static) -> io::Result<Self> {
+        // Fail fast on an endpoint or headers we can never push with
+        Endpoint::parse(&options.endpoint)?;
+        check_headers(&options.headers)?;
+
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread = {
+            let stop = Arc::clone(&stop);
+            thread::Builder::new()
+                .name("peep-otlp".to_string())
+                .spawn(move || run(options, feed, &stop))?
+        };
+        Ok(OtlpExporter { stop, thread: Some(thread) })
+    }
+
+    // Stop pushing and wait for the background thread to exit
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        let (stopped, wakeup) = &*self.stop;
+        *stopped.lock().unwrap() = true;
+        wakeup.notify_all();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for OtlpExporter {
+    fn drop(&mut self) {
+        if self.thread.is_some() {
+            self.shutdown();
+        }
+    }
+}
+
+fn run(options: OtlpOptions, mut feed: impl MetricsFeed, stop: &(Mutex<bool>, Condvar)) {
+    // Process CPU usage is a delta, so the first push needs a previous refresh
+    feed.processes();
+
+    let (stopped, wakeup) = stop;
+    let mut guard = stopped.lock().unwrap();
+    loop {
+        guard = wakeup.wait_timeout_while(guard, options.interval, |stopped| !*stopped).unwrap().0;
+        if *guard {
+            return;
+        }
+        drop(guard);
+
+        if let Err(err) = export(&mut feed, &options) {
+            eprintln!("peep otlp: {}", err);
+        }
+        guard = stopped.lock().unwrap();
+    }
+}
+
+// Collect once and push the result to the collector
+pub fn export(feed: &mut impl MetricsFeed, options: &OtlpOptions) -> io::Result<()> {
+    let system = feed.system();
+    let battery = feed.battery();
+    let mut processes = feed.processes();
+    processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    processes.truncate(options.top_processes);
+
+    let request = metrics_request(&system, &battery, &processes, crate::now_millis() * 1_000_000);
+    let body = serde_json::to_vec(&request).map_err(io::Error::other)?;
+    check_headers(&options.headers)?;
+    post(&Endpoint::parse(&options.endpoint)?, &options.headers, &body)
+}
+
+// Header names must be tokens, and neither names nor values may break out
+// of their line and inject headers or a body of their own
+fn check_headers(headers: &[(String, String)]) -> io::Result<()> {
+    for (name, value) in headers {
+        let valid_name = !name.is_empty()
+            && name.bytes().all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+        if !valid_name {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid header name: {:?}", name)));
+        }
+        if value.contains(['\r', '\n']) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid value for header {}", name)));
+        }
+    }
+    Ok(())
+}
+
+// Build an `ExportMetricsServiceRequest` in OTLP/JSON form
+pub fn metrics_request(
+    system: &SystemSnapshot,
+    battery: &BatterySnapshot,
+    processes: &[ProcessRecord],
+    time_nanos: u64,
+) -> Value {
+    let boot_nanos = System::boot_time() * 1_000_000_000;
+    let mut metrics = Vec::new();
+
+    // CPU
+    let mut cpu_points = vec![point(time_nanos, None, double(system.cpu.usage as f64 / 100.0), vec![])];
+    for (core, usage) in system.cpu.per_core.iter().enumerate() {
+        let attributes = vec![attribute("cpu.logical_number", json!({ "intValue": core.to_string() }))];
+        cpu_points.push(point(time_nanos, None, double(*usage as f64 / 100.0), attributes));
+    }
+    metrics.push(gauge("system.cpu.utilization", "1", "CPU usage; per core when cpu.logical_number is set.", cpu_points));
+    metrics.push(sum(
+        "system.cpu.logical.count",
+        "{cpu}",
+        "Number of logical CPUs.",
+        false,
+        vec![point(time_nanos, Some(boot_nanos), int(system.cpu.cores as u64), vec![])],
+    ));
+
+    // Memory and swap
+    let memory = &system.memory;
+    metrics.push(sum(
+        "system.memory.usage",
+        "By",
+        "Physical memory in use and free.",
+        false,
+        vec![
+            point(time_nanos, Some(boot_nanos), int(memory.used), vec![string_attribute("system.memory.state", "used")]),
+            point(time_nanos, Some(boot_nanos), int(memory.free), vec![string_attribute("system.memory.state", "free")]),
+        ],
+    ));
+    if memory.total > 0 {
+        metrics.push(gauge(
+            "system.memory.utilization",
+            "1",
+            "Fraction of physical memory in use.",
+            vec![point(time_nanos, None, double(memory.used as f64 / memory.total as f64), vec![string_attribute("system.memory.state", "used")])],
+        ));
+    }
+    metrics.push(sum(
+        "system.paging.usage",
+        "By",
+        "Swap space in use and free.",
+        false,
+        vec![
+            point(time_nanos, Some(boot_nanos), int(memory.used_swap), vec![string_attribute("system.paging.state", "used")]),
+            point(time_nanos, Some(boot_nanos), int(memory.free_swap), vec![string_attribute("system.paging.state", "free")]),
+        ],
+    ));
+
+    // Disk I/O and filesystems
+    let disk = &system.disk;
+    metrics.push(sum(
+        "system.disk.io",
+        "By",
+        "Bytes read from and written to all disks since boot.",
+        true,
+        vec![
+            point(time_nanos, Some(boot_nanos), int(disk.total_read), vec![string_attribute("disk.io.direction", "read")]),
+            point(time_nanos, Some(boot_nanos), int(disk.total_write), vec![string_attribute("disk.io.direction", "write")]),
+        ],
+    ));
+    let mut filesystem_points = Vec::new();
+    for volume in &disk.disks {
+        for (state, value) in [("used", volume.used_space), ("free", volume.available_space)] {
+            filesystem_points.push(point(time_nanos, Some(boot_nanos), int(value), vec![
+                string_attribute("system.device", &volume.name),
+                string_attribute("system.filesystem.mountpoint", &volume.mount_point),
+                string_attribute("system.filesystem.type", &volume.file_system),
+                string_attribute("system.filesystem.state", state),
+            ]));
+        }
+    }
+    metrics.push(sum("system.filesystem.usage", "By", "Filesystem space used and free.", false, filesystem_points));
+
+    // Network
+    let mut io_points = Vec::new();
+    let mut packet_points = Vec::new();
+    for interface in system.network.interfaces.iter().chain(&system.network.virtual_interfaces) {
+        let attributes = |direction: &str| vec![
+            string_attribute("network.interface.name", &interface.name),
+            string_attribute("network.io.direction", direction),
+        ];
+        io_points.push(point(time_nanos, Some(boot_nanos), int(interface.total_received), attributes("receive")));
+        io_points.push(point(time_nanos, Some(boot_nanos), int(interface.total_transmitted), attributes("transmit")));
+        packet_points.push(point(time_nanos, Some(boot_nanos), int(interface.packets_received), attributes("receive")));
+        packet_points.push(point(time_nanos, Some(boot_nanos), int(interface.packets_transmitted), attributes("transmit")));
+    }
+    metrics.push(sum("system.network.io", "By", "Bytes received and transmitted since boot.", true, io_points));
+    metrics.push(sum("system.network.packet.count", "{packet}", "Packets received and transmitted since boot.", true, packet_points));
+
+    // Battery
+    if battery.available {
+        let state = battery.state.as_deref().map(|s| vec![string_attribute("hw.battery.state", s)]).unwrap_or_default();
+        if let Some(percentage) = battery.percentage {
+            metrics.push(gauge("hw.battery.charge", "1", "Remaining battery charge.", vec![point(time_nanos, None, double(percentage / 100.0), state.clone())]));
+        }
+        if let Some(minutes) = battery.time_to_empty.or(battery.time_to_full) {
+            metrics.push(gauge("hw.battery.time_left", "s", "Time until empty when discharging, or full when charging.", vec![point(time_nanos, None, double(minutes * 60.0), state.clone())]));
+        }
+        if let Some(health) = battery.health {
+            metrics.push(gauge("peep.battery.health", "1", "Full capacity relative to design capacity.", vec![point(time_nanos, None, double(health / 100.0), vec![])]));
+        }
+    }
+
+    // Top processes
+    let process_attributes = |p: &ProcessRecord| vec![
+        attribute("process.pid", json!({ "intValue": p.pid.to_string() })),
+        string_attribute("process.executable.name", &p.name),
+    ];
+    let process_start = |p: &ProcessRecord| match p.start_time {
+        0 => time_nanos.saturating_sub(p.run_time * 1_000_000_000),
+        start => start * 1_000_000_000,
+    };
+    metrics.push(gauge(
+        "process.cpu.utilization",
+        "1",
+        "CPU usage of the process, relative to one core.",
+        processes.iter().map(|p| point(time_nanos, None, double(p.cpu as f64 / 100.0), process_attributes(p))).collect(),
+    ));
+    metrics.push(sum(
+        "process.cpu.time",
+        "s",
+        "CPU time consumed by the process.",
+        true,
+        processes.iter().map(|p| point(time_nanos, Some(process_start(p)), double(p.cpu_time as f64), process_attributes(p))).collect(),
+    ));
+    metrics.push(sum(
+        "process.memory.usage",
+        "By",
+        "Resident memory of the process.",
+        false,
+        processes.iter().map(|p| point(time_nanos, Some(process_start(p)), int(p.memory), process_attributes(p))).collect(),
+    ));
+
+    let mut resource = vec![string_attribute("service.name", "peep")];
+    if let Some(host) = System::host_name() {
+        resource.push(string_attribute("host.name", &host));
+    }
+    resource.push(string_attribute("os.type", std::env::consts::OS));
+
+    json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": resource },
+            "scopeMetrics": [{
+                "scope": { "name": "peep", "version": env!("CARGO_PKG_VERSION") },
+                "metrics": metrics,
+            }],
+        }],
+    })
+}
+
+fn attribute(key: &str, value: Value) -> Value {
+    json!({ "key": key, "value": value })
+}
+
+fn string_attribute(key: &str, value: &str) -> Value {
+    attribute(key, json!({ "stringValue": value }))
+}
+
+enum Number {
+    Int(u64),
+    Double(f64),
+}
+
+fn int(value: u64) -> Number {
+    Number::Int(value)
+}
+
+fn double(value: f64) -> Number {
+    Number::Double(value)
+}
+
+// 64-bit integers are strings in OTLP/JSON
+fn point(time_nanos: u64, start_nanos: Option<u64>, value: Number, attributes: Vec<Value>) -> Value {
+    let mut point = json!({ "timeUnixNano": time_nanos.to_string(), "attributes": attributes });
+    if let Some(start) = start_nanos {
+        point["startTimeUnixNano"] = start.to_string().into();
+    }
+    match value {
+        Number::Int(value) => point["asInt"] = value.to_string().into(),
+        Number::Double(value) => point["asDouble"] = value.into(),
+    }
+    point
+}
+
+fn gauge(name: &str, unit: &str, description: &str, points: Vec<Value>) -> Value {
+    json!({ "name": name, "unit": unit, "description": description, "gauge": { "dataPoints": points } })
+}
+
+// Cumulative sum; non-monotonic sums are OTel UpDownCounters
+fn sum(name: &str, unit: &str, description: &str, monotonic: bool, points: Vec<Value>) -> Value {
+    json!({
+        "name": name,
+        "unit": unit,
+        "description": description,
+        "sum": {
+            "dataPoints": points,
+            // AGGREGATION_TEMPORALITY_CUMULATIVE
+            "aggregationTemporality": 2,
+            "isMonotonic": monotonic,
+        },
+    })
+}
+
+struct Endpoint {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl Endpoint {
+    fn parse(endpoint: &str) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", message, endpoint));
+
+        let rest = match endpoint.split_once("://") {
+            Some(("http", rest)) => rest,
+            Some(("https", _)) => return Err(invalid("https endpoints are not supported, use a local collector")),
+            Some(_) => return Err(invalid("Unsupported endpoint scheme")),
+            None => endpoint,
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => rest.split_at(i),
+            None => (rest, ""),
+        };
+        // IPv6 hosts are bracketed: [::1]:4318
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(bracketed) => {
+                let (host, after) = bracketed.split_once(']').ok_or_else(|| invalid("Invalid endpoint host"))?;
+                (host, after.strip_prefix(':'))
+            }
+            None => match authority.split_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("Invalid endpoint port"))?,
+            None => DEFAULT_PORT,
+        };
+        if host.is_empty() {
+            return Err(invalid("Missing endpoint host"));
+        }
+
+        Ok(Endpoint {
+            host: host.to_string(),
+            port,
+            path: if path.is_empty() || path == "/" { DEFAULT_PATH.to_string() } else { path.to_string() },
+        })
+    }
+}
+
+// Try each resolved address in turn, so an unreachable one can't hang a push
+fn connect(endpoint: &Endpoint) -> io::Result<TcpStream> {
+    let mut last_error = None;
+    for addr in (endpoint.host.as_str(), endpoint.port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, format!("No addresses found for {}", endpoint.host))
+    }))
+}
+
+fn post(endpoint: &Endpoint, headers: &[(String, String)], body: &[u8]) -> io::Result<()> {
+    let mut stream = connect(endpoint)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    // IPv6 literals keep their brackets in the Host header
+    let host = if endpoint.host.contains(':') {
+        format!("[{}]", endpoint.host)
+    } else {
+        endpoint.host.clone()
+    };
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+        endpoint.path, host, endpoint.port, body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let mut status_line = String::new();
+    reader.read_line(&mut status_line)?;
+    let status: u16 = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Malformed HTTP response"))?;
+
+    if (200..300).contains(&status) {
+        Ok(())
+    } else {
+        // Include what the collector said, it usually explains the rejection
+        let mut response = String::new();
+        let _ = reader.take(4096).read_to_string(&mut response);
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or("").trim();
+        Err(io::Error::other(format!("Collector responded {}: {}", status, body)))
+    }
+}
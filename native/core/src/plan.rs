@@ -0,0 +1,132 @@
+use serde_json::Value;
+
+// Which subsystems a `Collector::collect` call refreshes and reports, and in
+// how much detail. Anything left out is neither refreshed nor returned, so
+// collection cost scales with what the caller actually shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RefreshPlan {
+    pub cpu: bool,
+    pub memory: bool,
+    pub disks: Option<DiskDetail>,
+    pub network: bool,
+    pub processes: Option<ProcessDetail>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskDetail {
+    // I/O counters and rates only; volume space is not queried
+    Io,
+    // I/O plus the list of volumes with their space usage
+    Volumes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessDetail {
+    // Include threads (Linux tasks) as separate entries
+    pub threads: bool,
+    // Read each process's command line
+    pub command: bool,
+    // Resolve each process's user name
+    pub user: bool,
+    // Attribute TCP traffic to processes (Linux only). Reading every
+    // socket is costly, so it's off unless a traffic column is shown.
+    pub network: bool,
+}
+
+impl Default for ProcessDetail {
+    fn default() -> Self {
+        ProcessDetail { threads: false, command: true, user: true, network: false }
+    }
+}
+
+impl RefreshPlan {
+    // Everything `SystemSnapshot` reports: CPU, memory, disks with volumes and network
+    pub fn system() -> Self {
+        RefreshPlan {
+            cpu: true,
+            memory: true,
+            disks: Some(DiskDetail::Volumes),
+            network: true,
+            processes: None,
+        }
+    }
+
+    pub fn cpu() -> Self {
+        RefreshPlan { cpu: true, ..Self::default() }
+    }
+
+    pub fn memory() -> Self {
+        RefreshPlan { memory: true, ..Self::default() }
+    }
+
+    pub fn disks(detail: DiskDetail) -> Self {
+        RefreshPlan { disks: Some(detail), ..Self::default() }
+    }
+
+    pub fn network() -> Self {
+        RefreshPlan { network: true, ..Self::default() }
+    }
+
+    pub fn processes(detail: ProcessDetail) -> Self {
+        RefreshPlan { processes: Some(detail), ..Self::default() }
+    }
+}
+
+// Read a refresh plan from its JSON form:
+//
+//     { cpu?: boolean, memory?: boolean, network?: boolean,
+//       disks?: boolean | 'io' | 'volumes',
+//       processes?: boolean | { threads?: boolean, command?: boolean, user?: boolean,
+//                               network?: boolean } }
+//
+// Omitted (or null) subsystems are skipped. `disks: true` means 'volumes',
+// and `processes: true` means the default detail: command and user, without
+// threads or traffic. Anything but an object is no plan at all.
+pub fn parse_plan(value: &Value) -> Result<Option<RefreshPlan>, String> {
+    let Some(plan) = value.as_object() else {
+        return Ok(None);
+    };
+
+    let disks = match plan.get("disks") {
+        None | Some(Value::Null) => None,
+        Some(Value::Bool(enabled)) => enabled.then_some(DiskDetail::Volumes),
+        Some(Value::String(detail)) => match detail.as_str() {
+            "io" => Some(DiskDetail::Io),
+            "volumes" => Some(DiskDetail::Volumes),
+            other => return Err(format!("Unknown disk detail: {}", other)),
+        },
+        Some(_) => return Err("disks must be a boolean, 'io' or 'volumes'".to_string()),
+    };
+
+    let processes = match plan.get("processes") {
+        None | Some(Value::Null) => None,
+        Some(Value::Bool(enabled)) => enabled.then(ProcessDetail::default),
+        Some(Value::Object(detail)) => {
+            let defaults = ProcessDetail::default();
+            let or_default = |key: &str, default: bool| flag(detail, key).map(|v| v.unwrap_or(default));
+            Some(ProcessDetail {
+                threads: or_default("threads", defaults.threads)?,
+                command: or_default("command", defaults.command)?,
+                user: or_default("user", defaults.user)?,
+                network: or_default("network", defaults.network)?,
+            })
+        }
+        Some(_) => return Err("processes must be a boolean or an object".to_string()),
+    };
+
+    Ok(Some(RefreshPlan {
+        cpu: flag(plan, "cpu")?.unwrap_or(false),
+        memory: flag(plan, "memory")?.unwrap_or(false),
+        disks,
+        network: flag(plan, "network")?.unwrap_or(false),
+        processes,
+    }))
+}
+
+fn flag(obj: &serde_json::Map<String, Value>, key: &str) -> Result<Option<bool>, String> {
+    match obj.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::Bool(value)) => Ok(Some(*value)),
+        Some(_) => Err(format!("{} must be a boolean", key)),
+    }
+}
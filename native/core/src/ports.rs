@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::IpAddr;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users};
+
+use crate::connections::{connections, Connection, ConnectionFilter, ConnectionState, Protocol};
+use crate::sysinfo_source::user_name;
+
+// A socket accepting traffic on a local port, with its owner
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListeningPort {
+    pub protocol: Protocol,
+    pub address: IpAddr,
+    pub port: u16,
+    // The owner, when its descriptors could be read: other users' sockets
+    // only have one when running as root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub process_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+}
+
+// TCP sockets in LISTEN, and UDP sockets bound to a port without a peer
+pub fn is_listening(connection: &Connection) -> bool {
+    if connection.protocol.is_tcp() {
+        connection.state == ConnectionState::Listen
+    } else {
+        connection.state == ConnectionState::Close && connection.remote_port == 0
+    }
+}
+
+// The listening sockets among `connections`, ordered by port. Commands and
+// users are left unresolved.
+pub fn listeners(connections: &[Connection]) -> Vec<ListeningPort> {
+    let mut listeners: Vec<ListeningPort> = connections.iter()
+        .filter(|connection| is_listening(connection))
+        .map(|connection| ListeningPort {
+            protocol: connection.protocol,
+            address: connection.local_address,
+            port: connection.local_port,
+            pid: connection.pid,
+            process_name: connection.process_name.clone(),
+            command: Vec::new(),
+            user: None,
+        })
+        .collect();
+    listeners.sort_by_key(|l| (l.port, l.protocol.name(), l.pid));
+    listeners
+}
+
+// Every listening TCP and UDP port with its owning process (Linux only;
+// empty elsewhere)
+pub fn listening_ports() -> io::Result<Vec<ListeningPort>> {
+    let filter = ConnectionFilter {
+        states: vec![ConnectionState::Listen, ConnectionState::Close],
+        ..ConnectionFilter::default()
+    };
+    let mut listeners = listeners(&connections(&filter)?);
+    describe_owners(&mut listeners);
+    Ok(listeners)
+}
+
+// What is listening on `port`. The address family doesn't matter: `Tcp`
+// also finds a tcp6 listener, which is how dual-stack servers show up. When
+// several sockets share the port, one with a known owner is preferred.
+pub fn find_process_by_port(port: u16, protocol: Protocol) -> io::Result<Option<ListeningPort>> {
+    let filter = ConnectionFilter {
+        protocols: Protocol::ALL.into_iter().filter(|p| p.is_tcp() == protocol.is_tcp()).collect(),
+        states: vec![if protocol.is_tcp() { ConnectionState::Listen } else { ConnectionState::Close }],
+        pid: None,
+    };
+    let mut found: Vec<ListeningPort> = listeners(&connections(&filter)?)
+        .into_iter()
+        .filter(|l| l.port == port)
+        .collect();
+    found.sort_by_key(|l| l.pid.is_none());
+    found.truncate(1);
+    describe_owners(&mut found);
+    Ok(found.pop())
+}
+
+// Fill in the owners' command lines and user names, refreshing only those processes
+fn describe_owners(listeners: &mut [ListeningPort]) {
+    let mut pids: Vec<Pid> = listeners.iter().filter_map(|l| l.pid).map(Pid::from_u32).collect();
+    pids.sort();
+    pids.dedup();
+    if pids.is_empty() {
+        return;
+    }
+
+    let mut system = System::new();
+    let kind = ProcessRefreshKind::nothing().with_cmd(UpdateKind::Always).with_user(UpdateKind::Always);
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), true, kind);
+    let users = Users::new_with_refreshed_list();
+
+    for listener in listeners {
+        let Some(process) = listener.pid.and_then(|pid| system.process(Pid::from_u32(pid))) else {
+            continue;
+        };
+        listener.command = process.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect();
+        listener.user = Some(user_name(process, &users));
+    }
+}
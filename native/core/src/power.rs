@@ -0,0 +1,64 @@
+use battery::units::energy::watt_hour;
+use battery::units::thermodynamic_temperature::degree_celsius;
+use battery::units::time::second;
+use battery::Manager;
+use std::time::Duration;
+
+use crate::snapshot::BatterySnapshot;
+use crate::source::BatteryReading;
+
+// Get battery information for the first battery found
+pub fn battery_snapshot() -> BatterySnapshot {
+    battery_from(read_battery())
+}
+
+// Raw readings of the first battery found, if any
+pub fn read_battery() -> Option<BatteryReading> {
+    let battery = Manager::new()
+        .ok()
+        .and_then(|manager| manager.batteries().ok())
+        .and_then(|mut batteries| batteries.next())
+        .and_then(Result::ok)?;
+
+    Some(BatteryReading {
+        // State of charge (0.0 - 1.0)
+        charge: battery.state_of_charge().value as f64,
+        // State (charging, discharging, full, etc.)
+        state: format!("{:?}", battery.state()),
+        energy: battery.energy().get::<watt_hour>() as f64,
+        energy_full: battery.energy_full().get::<watt_hour>() as f64,
+        energy_full_design: battery.energy_full_design().get::<watt_hour>() as f64,
+        time_to_full: battery.time_to_full().and_then(|time| Duration::try_from_secs_f64(time.get::<second>() as f64).ok()),
+        time_to_empty: battery.time_to_empty().and_then(|time| Duration::try_from_secs_f64(time.get::<second>() as f64).ok()),
+        temperature: battery.temperature().map(|temp| temp.get::<degree_celsius>() as f64),
+    })
+}
+
+pub fn battery_from(reading: Option<BatteryReading>) -> BatterySnapshot {
+    let Some(battery) = reading else {
+        // No battery found, or battery manager not available
+        return BatterySnapshot::default();
+    };
+
+    // State of health - calculate manually as (energy_full / energy_full_design) * 100
+    // This gives us the maximum capacity as a percentage of original design capacity
+    let health = if battery.energy_full_design > 0.0 {
+        battery.energy_full / battery.energy_full_design * 100.0
+    } else {
+        100.0
+    };
+    let minutes = |time: Duration| time.as_secs_f64() / 60.0;
+
+    BatterySnapshot {
+        available: true,
+        percentage: Some(battery.charge * 100.0),
+        state: Some(battery.state),
+        health: Some(health),
+        energy_full_design: Some(battery.energy_full_design),
+        time_to_full: battery.time_to_full.map(minutes),
+        time_to_empty: battery.time_to_empty.map(minutes),
+        energy: Some(battery.energy),
+        energy_full: Some(battery.energy_full),
+        temperature: battery.temperature,
+    }
+}
@@ -0,0 +1,37 @@
+use crate::plan::ProcessDetail;
+use crate::rate::Tracked;
+use crate::snapshot::ProcessRecord;
+use crate::source::ProcessReading;
+
+// Command line and user are left empty unless `detail` asks for them
+pub fn process_record(process: &ProcessReading, processes: &Tracked<ProcessReading>, detail: ProcessDetail) -> ProcessRecord {
+    // Get process command line
+    let command = if detail.command {
+        process.command.join(" ")
+    } else {
+        String::new()
+    };
+
+    ProcessRecord {
+        pid: process.pid,
+        name: process.name.clone(),
+        ppid: process.parent.unwrap_or(0),
+        is_thread: process.is_thread,
+        cpu: process.cpu,
+        memory: process.memory,
+        run_time: process.run_time,
+        cpu_time: process.cpu_time,
+        status: process.status.clone(),
+        user: if detail.user { process.user.clone() } else { String::new() },
+        command,
+        // Disk I/O since the previous process refresh
+        disk_read: processes.delta(process, |p| p.total_disk_read),
+        disk_write: processes.delta(process, |p| p.total_disk_written),
+        net_rx: process.total_net_received
+            .map(|_| processes.per_second(processes.delta_if_read(process, |p| p.total_net_received))),
+        net_tx: process.total_net_transmitted
+            .map(|_| processes.per_second(processes.delta_if_read(process, |p| p.total_net_transmitted))),
+        start_time: process.start_time,
+        exe: process.exe.clone(),
+    }
+}
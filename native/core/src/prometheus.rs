@@ -0,0 +1,185 @@
+use std::fmt::Write;
+
+use crate::snapshot::{BatterySnapshot, DiskDetails, InterfaceSnapshot, ProcessRecord, SystemSnapshot};
+
+// Prometheus text exposition (format 0.0.4) of everything Peep collects.
+//
+// Names follow Prometheus conventions: a `peep_` prefix, base units (bytes,
+// seconds, ratios in 0..1) and `_total` on counters. Labels are shared across
+// families: `core`, `device`/`mount_point`/`fs_type` for volumes,
+// `interface`/`type` for network interfaces and `pid`/`name` for processes.
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub fn render(system: &SystemSnapshot, battery: &BatterySnapshot, processes: &[ProcessRecord]) -> String {
+    let mut out = Exposition::default();
+    render_system(&mut out, system);
+    render_battery(&mut out, battery);
+    render_processes(&mut out, processes);
+    out.text
+}
+
+fn render_system(out: &mut Exposition, system: &SystemSnapshot) {
+    let cpu = &system.cpu;
+    out.family("peep_cpu_cores", "gauge", "Number of logical CPU cores.");
+    out.sample("peep_cpu_cores", &[], cpu.cores as f64);
+    out.family("peep_cpu_usage_ratio", "gauge", "Overall CPU usage.");
+    out.sample("peep_cpu_usage_ratio", &[], cpu.usage as f64 / 100.0);
+    out.family("peep_cpu_core_usage_ratio", "gauge", "CPU usage of each core.");
+    for (core, usage) in cpu.per_core.iter().enumerate() {
+        out.sample("peep_cpu_core_usage_ratio", &[("core", &core.to_string())], *usage as f64 / 100.0);
+    }
+
+    let memory = &system.memory;
+    for (name, help, value) in [
+        ("peep_memory_total_bytes", "Total physical memory.", memory.total),
+        ("peep_memory_used_bytes", "Physical memory in use.", memory.used),
+        ("peep_memory_free_bytes", "Unused physical memory.", memory.free),
+        ("peep_swap_total_bytes", "Total swap space.", memory.total_swap),
+        ("peep_swap_used_bytes", "Swap space in use.", memory.used_swap),
+        ("peep_swap_free_bytes", "Unused swap space.", memory.free_swap),
+    ] {
+        out.family(name, "gauge", help);
+        out.sample(name, &[], value as f64);
+    }
+
+    let disk = &system.disk;
+    out.family("peep_disk_read_bytes_total", "counter", "Bytes read from all disks since boot.");
+    out.sample("peep_disk_read_bytes_total", &[], disk.total_read as f64);
+    out.family("peep_disk_written_bytes_total", "counter", "Bytes written to all disks since boot.");
+    out.sample("peep_disk_written_bytes_total", &[], disk.total_write as f64);
+
+    let volume_labels = |v: &DiskDetails| [
+        ("device", v.name.clone()),
+        ("mount_point", v.mount_point.clone()),
+        ("fs_type", v.file_system.clone()),
+    ];
+    out.family("peep_filesystem_size_bytes", "gauge", "Volume size.");
+    for volume in &disk.disks {
+        out.sample_owned("peep_filesystem_size_bytes", &volume_labels(volume), volume.total_space as f64);
+    }
+    out.family("peep_filesystem_used_bytes", "gauge", "Space used on the volume.");
+    for volume in &disk.disks {
+        out.sample_owned("peep_filesystem_used_bytes", &volume_labels(volume), volume.used_space as f64);
+    }
+    out.family("peep_filesystem_avail_bytes", "gauge", "Space available on the volume.");
+    for volume in &disk.disks {
+        out.sample_owned("peep_filesystem_avail_bytes", &volume_labels(volume), volume.available_space as f64);
+    }
+
+    let network = &system.network;
+    let interfaces = || network.interfaces.iter().chain(&network.virtual_interfaces);
+    let interface_labels = |i: &InterfaceSnapshot| [
+        ("interface", i.name.clone()),
+        ("type", i.interface_type.clone()),
+    ];
+    out.family("peep_network_receive_bytes_total", "counter", "Bytes received since boot.");
+    for interface in interfaces() {
+        out.sample_owned("peep_network_receive_bytes_total", &interface_labels(interface), interface.total_received as f64);
+    }
+    out.family("peep_network_transmit_bytes_total", "counter", "Bytes transmitted since boot.");
+    for interface in interfaces() {
+        out.sample_owned("peep_network_transmit_bytes_total", &interface_labels(interface), interface.total_transmitted as f64);
+    }
+    out.family("peep_network_receive_packets_total", "counter", "Packets received since boot.");
+    for interface in interfaces() {
+        out.sample_owned("peep_network_receive_packets_total", &interface_labels(interface), interface.packets_received as f64);
+    }
+    out.family("peep_network_transmit_packets_total", "counter", "Packets transmitted since boot.");
+    for interface in interfaces() {
+        out.sample_owned("peep_network_transmit_packets_total", &interface_labels(interface), interface.packets_transmitted as f64);
+    }
+}
+
+fn render_battery(out: &mut Exposition, battery: &BatterySnapshot) {
+    out.family("peep_battery_available", "gauge", "Whether a battery was found (1) or not (0).");
+    out.sample("peep_battery_available", &[], if battery.available { 1.0 } else { 0.0 });
+    if !battery.available {
+        return;
+    }
+
+    if let Some(state) = &battery.state {
+        out.family("peep_battery_state", "gauge", "Current battery state, as a label.");
+        out.sample("peep_battery_state", &[("state", state)], 1.0);
+    }
+
+    let minutes = |m: f64| m * 60.0;
+    for (name, help, value) in [
+        ("peep_battery_charge_ratio", "State of charge.", battery.percentage.map(|p| p / 100.0)),
+        ("peep_battery_health_ratio", "Full capacity relative to design capacity.", battery.health.map(|h| h / 100.0)),
+        ("peep_battery_energy_watt_hours", "Energy currently stored.", battery.energy),
+        ("peep_battery_energy_full_watt_hours", "Energy stored when full.", battery.energy_full),
+        ("peep_battery_energy_full_design_watt_hours", "Design capacity.", battery.energy_full_design),
+        ("peep_battery_time_to_full_seconds", "Estimated time until fully charged.", battery.time_to_full.map(minutes)),
+        ("peep_battery_time_to_empty_seconds", "Estimated time until empty.", battery.time_to_empty.map(minutes)),
+        ("peep_battery_temperature_celsius", "Battery temperature.", battery.temperature),
+    ] {
+        if let Some(value) = value {
+            out.family(name, "gauge", help);
+            out.sample(name, &[], value);
+        }
+    }
+}
+
+fn render_processes(out: &mut Exposition, processes: &[ProcessRecord]) {
+    let labels = |p: &ProcessRecord| [("pid", p.pid.to_string()), ("name", p.name.clone())];
+
+    out.family("peep_process_cpu_usage_ratio", "gauge", "CPU usage of the process, relative to one core.");
+    for process in processes {
+        out.sample_owned("peep_process_cpu_usage_ratio", &labels(process), process.cpu as f64 / 100.0);
+    }
+    out.family("peep_process_cpu_seconds_total", "counter", "CPU time consumed by the process.");
+    for process in processes {
+        out.sample_owned("peep_process_cpu_seconds_total", &labels(process), process.cpu_time as f64);
+    }
+    out.family("peep_process_memory_bytes", "gauge", "Resident memory of the process.");
+    for process in processes {
+        out.sample_owned("peep_process_memory_bytes", &labels(process), process.memory as f64);
+    }
+}
+
+#[derive(Default)]
+struct Exposition {
+    text: String,
+}
+
+impl Exposition {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        writeln!(self.text, "# HELP {} {}", name, help).unwrap();
+        writeln!(self.text, "# TYPE {} {}", name, kind).unwrap();
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.text.push_str(name);
+        if !labels.is_empty() {
+            self.text.push('{');
+            for (i, (key, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.text.push(',');
+                }
+                write!(self.text, "{}=\"{}\"", key, escape_label(value)).unwrap();
+            }
+            self.text.push('}');
+        }
+        writeln!(self.text, " {}", format_value(value)).unwrap();
+    }
+
+    fn sample_owned(&mut self, name: &str, labels: &[(&str, String)], value: f64) {
+        let labels: Vec<(&str, &str)> = labels.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        self.sample(name, &labels, value);
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+// Which processes kill and signal calls refuse unless forced. Some are always
+// protected: pids 0 and 1 (the kernel's idle task and init/launchd), Peep
+// itself, the process that started it (the parent Electron process when Peep
+// runs in a helper, or the shell for the CLI), every process Peep started
+// (e.g. Electron's renderer and GPU helpers), and kernel threads. Patterns
+// add to those; forcing overrides everything except Peep itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ProtectionPolicy {
+    // Case-insensitive, with `*` matching anything. A pattern containing a
+    // path separator matches the executable path, otherwise the name.
+    pub patterns: Vec<String>,
+}
+
+impl Default for ProtectionPolicy {
+    fn default() -> Self {
+        let patterns: &[&str] = if cfg!(target_os = "macos") {
+            &["WindowServer", "loginwindow", "kernel_task", "opendirectoryd"]
+        } else if cfg!(target_os = "linux") {
+            &["Xorg", "Xwayland", "gnome-shell", "kwin_x11", "kwin_wayland", "systemd-logind", "dbus-daemon"]
+        } else if cfg!(windows) {
+            &["csrss.exe", "smss.exe", "wininit.exe", "winlogon.exe", "services.exe", "lsass.exe", "dwm.exe"]
+        } else {
+            &[]
+        };
+        ProtectionPolicy { patterns: patterns.iter().map(|pattern| pattern.to_string()).collect() }
+    }
+}
+
+impl ProtectionPolicy {
+    // Only the built-in rules
+    pub fn none() -> Self {
+        ProtectionPolicy { patterns: Vec::new() }
+    }
+
+    // Why the process may not be signalled, or None when it may be.
+    // `descendant` is whether Peep started it, directly or not.
+    pub fn reason(&self, pid: u32, name: &str, exe: Option<&str>, kernel_thread: bool, descendant: bool) -> Option<String> {
+        let why = if pid == std::process::id() {
+            "this is Peep".to_string()
+        } else if pid == 0 {
+            "it's part of the kernel".to_string()
+        } else if pid == 1 {
+            "it's the init process".to_string()
+        } else if parent_pid() == Some(pid) {
+            "it started Peep".to_string()
+        } else if descendant {
+            "Peep started it".to_string()
+        } else if kernel_thread {
+            "it's a kernel thread".to_string()
+        } else {
+            let pattern = self.patterns.iter().find(|pattern| {
+                if pattern.contains('/') || pattern.contains('\\') {
+                    exe.is_some_and(|exe| matches(pattern, exe))
+                } else {
+                    matches(pattern, name)
+                }
+            })?;
+            format!("it matches \"{}\"", pattern)
+        };
+        Some(format!("{} ({}) is protected: {}", name, pid, why))
+    }
+}
+
+#[cfg(unix)]
+fn parent_pid() -> Option<u32> {
+    Some(std::os::unix::process::parent_id())
+}
+
+#[cfg(not(unix))]
+fn parent_pid() -> Option<u32> {
+    None
+}
+
+// A case-insensitive glob where `*` matches any run of characters
+fn matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was, and how much of the text it has swallowed
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+static POLICY: RwLock<Option<ProtectionPolicy>> = RwLock::new(None);
+
+// The policy in effect for this process
+pub fn protection_policy() -> ProtectionPolicy {
+    POLICY.read().unwrap().clone().unwrap_or_default()
+}
+
+// Replace the policy for this process; `None` restores the OS defaults
+pub fn set_protection_policy(policy: Option<ProtectionPolicy>) {
+    *POLICY.write().unwrap() = policy;
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+use std::time::Duration;
+
+use crate::source::{DiskReading, InterfaceReading, ProcessReading};
+
+// The latest readings of a set of cumulative counters (per disk, interface or
+// process) together with the previous ones and the time between them.
+//
+// Sources report counters as totals; deltas and rates are always taken
+// between two refreshes of the same tracker, so they stay correct no matter
+// how often each subsystem is refreshed.
+pub struct Tracked<T: Keyed> {
+    current: Vec<T>,
+    previous: HashMap<T::Key, T>,
+    refreshed_at: Option<Duration>,
+    elapsed: Duration,
+}
+
+// What identifies the same counter across refreshes
+pub trait Keyed {
+    type Key: Eq + Hash;
+    fn key(&self) -> Self::Key;
+}
+
+impl Keyed for DiskReading {
+    type Key = String;
+    fn key(&self) -> String {
+        self.mount_point.clone()
+    }
+}
+
+impl Keyed for InterfaceReading {
+    type Key = String;
+    fn key(&self) -> String {
+        self.name.clone()
+    }
+}
+
+// A reused pid is a different process, and its counters start over
+impl Keyed for ProcessReading {
+    type Key = (u32, u64);
+    fn key(&self) -> (u32, u64) {
+        (self.pid, self.start_time)
+    }
+}
+
+impl<T: Keyed> Tracked<T> {
+    // `readings` taken at `now` are the baseline for the first deltas
+    pub fn new(now: Duration, readings: Vec<T>) -> Self {
+        Tracked {
+            current: readings,
+            previous: HashMap::new(),
+            refreshed_at: Some(now),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn update(&mut self, now: Duration, readings: Vec<T>) {
+        let previous = mem::replace(&mut self.current, readings);
+        self.previous = previous.into_iter().map(|reading| (reading.key(), reading)).collect();
+        self.elapsed = self.refreshed_at.map_or(Duration::ZERO, |at| now.saturating_sub(at));
+        self.refreshed_at = Some(now);
+    }
+
+    pub fn current(&self) -> &[T] {
+        &self.current
+    }
+
+    // How much `counter` grew since the previous refresh; zero for counters
+    // that are new or went backwards (e.g. an interface that was re-created)
+    pub fn delta(&self, reading: &T, counter: impl Fn(&T) -> u64) -> u64 {
+        match self.previous.get(&reading.key()) {
+            Some(previous) => counter(reading).saturating_sub(counter(previous)),
+            None => 0,
+        }
+    }
+
+    // Like `delta`, for a counter that isn't always read; zero when either
+    // refresh went without it, rather than counting its whole total at once
+    pub fn delta_if_read(&self, reading: &T, counter: impl Fn(&T) -> Option<u64>) -> u64 {
+        match (counter(reading), self.previous.get(&reading.key()).and_then(&counter)) {
+            (Some(current), Some(previous)) => current.saturating_sub(previous),
+            _ => 0,
+        }
+    }
+
+    // Convert a delta into a per-second rate over the previous refresh interval
+    pub fn per_second(&self, delta: u64) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            delta as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::plan::{DiskDetail, RefreshPlan};
+use crate::snapshot::{BatterySnapshot, OsInfo, PartialSnapshot, ProcessRecord, SystemSnapshot};
+
+// Session recordings: the raw snapshot stream written as JSON lines so a
+// reported glitch can be replayed exactly as Peep saw it.
+//
+// The first line is a header naming the format and its version; every line
+// after that is a frame holding whichever snapshots were collected at that
+// moment, stamped with ms since the recording started. A torn last line, from
+// a crash mid-write, is ignored when reading.
+
+const FORMAT: &str = "peep-recording";
+pub const RECORDING_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Header {
+    format: String,
+    version: u32,
+    // ms since the Unix epoch
+    started_at: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    os: Option<OsInfo>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Frame {
+    // ms since the recording started
+    pub offset: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system: Option<SystemSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processes: Option<Vec<ProcessRecord>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub battery: Option<BatterySnapshot>,
+}
+
+// Borrowed form of `Frame` so recording doesn't clone what it writes
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FrameRef<'a> {
+    offset: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a SystemSnapshot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    processes: Option<&'a [ProcessRecord]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    battery: Option<&'a BatterySnapshot>,
+}
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+    started_at: u64,
+    frames: usize,
+}
+
+impl Recorder {
+    // Create (or truncate) `path` and write the header. `started_at` is the
+    // wall-clock time frames are measured from.
+    pub fn create(path: impl AsRef<Path>, started_at: u64, os: Option<&OsInfo>) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let header = Header {
+            format: FORMAT.to_string(),
+            version: RECORDING_VERSION,
+            started_at,
+            os: os.cloned(),
+        };
+        serde_json::to_writer(&mut writer, &header)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        Ok(Recorder { writer, started_at, frames: 0 })
+    }
+
+    pub fn record_system(&mut self, timestamp: u64, system: &SystemSnapshot) -> io::Result<()> {
+        self.write(FrameRef { offset: self.offset(timestamp), system: Some(system), processes: None, battery: None })
+    }
+
+    pub fn record_processes(&mut self, timestamp: u64, processes: &[ProcessRecord]) -> io::Result<()> {
+        self.write(FrameRef { offset: self.offset(timestamp), system: None, processes: Some(processes), battery: None })
+    }
+
+    pub fn record_battery(&mut self, timestamp: u64, battery: &BatterySnapshot) -> io::Result<()> {
+        self.write(FrameRef { offset: self.offset(timestamp), system: None, processes: None, battery: Some(battery) })
+    }
+
+    // Number of frames written so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    fn offset(&self, timestamp: u64) -> u64 {
+        timestamp.saturating_sub(self.started_at)
+    }
+
+    // Flushed per frame so a crash loses at most the frame being written
+    fn write(&mut self, frame: FrameRef) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, &frame)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    pub started_at: u64,
+    pub os: Option<OsInfo>,
+    // In offset order
+    pub frames: Vec<Frame>,
+}
+
+impl Recording {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read(reader: impl BufRead) -> io::Result<Self> {
+        let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut lines = reader.lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?).map_err(|_| invalid("Not a Peep recording".to_string()))?,
+            None => return Err(invalid("Empty recording".to_string())),
+        };
+        if header.format != FORMAT {
+            return Err(invalid("Not a Peep recording".to_string()));
+        }
+        if header.version > RECORDING_VERSION {
+            return Err(invalid(format!(
+                "Recording version {} is newer than the supported version {}",
+                header.version, RECORDING_VERSION
+            )));
+        }
+
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut torn = None;
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // Only the last line may be torn
+            if let Some(line_number) = torn {
+                return Err(invalid(format!("Malformed frame on line {}", line_number)));
+            }
+            match serde_json::from_str(&line) {
+                Ok(frame) => frames.push(frame),
+                Err(_) => torn = Some(i + 2),
+            }
+        }
+        frames.sort_by_key(|frame| frame.offset);
+
+        Ok(Recording { started_at: header.started_at, os: header.os, frames })
+    }
+
+    // Offset of the last frame, in ms
+    pub fn duration(&self) -> u64 {
+        self.frames.last().map_or(0, |frame| frame.offset)
+    }
+
+    // The newest snapshot of each kind at `offset`. Before the first one of a
+    // kind is recorded, that first one stands in so replay always has data.
+    pub fn system_at(&self, offset: u64) -> Option<&SystemSnapshot> {
+        self.latest(offset, |frame| frame.system.as_ref())
+    }
+
+    pub fn processes_at(&self, offset: u64) -> Option<&[ProcessRecord]> {
+        self.latest(offset, |frame| frame.processes.as_deref())
+    }
+
+    pub fn battery_at(&self, offset: u64) -> Option<&BatterySnapshot> {
+        self.latest(offset, |frame| frame.battery.as_ref())
+    }
+
+    fn latest<'a, T: ?Sized>(&'a self, offset: u64, get: impl Fn(&'a Frame) -> Option<&'a T>) -> Option<&'a T> {
+        let end = self.frames.partition_point(|frame| frame.offset <= offset);
+        self.frames[..end]
+            .iter()
+            .rev()
+            .find_map(&get)
+            .or_else(|| self.frames[end..].iter().find_map(&get))
+    }
+}
+
+// Plays a recording back against the wall clock, `speed` times faster than
+// it was recorded
+pub struct Replay {
+    recording: Recording,
+    speed: f64,
+    looped: bool,
+    started: Instant,
+}
+
+impl Replay {
+    pub fn new(recording: Recording, speed: f64, looped: bool) -> Self {
+        Replay {
+            recording,
+            speed: if speed > 0.0 { speed } else { 1.0 },
+            looped,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn recording(&self) -> &Recording {
+        &self.recording
+    }
+
+    // Current position in recording time, in ms
+    pub fn offset(&self) -> u64 {
+        self.offset_after(self.started.elapsed())
+    }
+
+    // Position after `elapsed` wall-clock time, wrapping around when looped
+    pub fn offset_after(&self, elapsed: Duration) -> u64 {
+        let offset = (elapsed.as_secs_f64() * 1000.0 * self.speed) as u64;
+        let duration = self.recording.duration();
+        if self.looped && duration > 0 {
+            offset % (duration + 1)
+        } else {
+            offset.min(duration)
+        }
+    }
+
+    // Whether a non-looping replay has played its last frame
+    pub fn finished(&self) -> bool {
+        !self.looped && self.started.elapsed().as_secs_f64() * 1000.0 * self.speed >= self.recording.duration() as f64
+    }
+
+    pub fn system(&self) -> Option<&SystemSnapshot> {
+        self.recording.system_at(self.offset())
+    }
+
+    // What `Collector::collect` would have returned for `plan` at this point
+    pub fn collect(&self, plan: &RefreshPlan) -> PartialSnapshot {
+        let offset = self.offset();
+        let system = self.recording.system_at(offset);
+        PartialSnapshot {
+            cpu: system.filter(|_| plan.cpu).map(|s| s.cpu.clone()),
+            memory: system.filter(|_| plan.memory).map(|s| s.memory.clone()),
+            disk: plan.disks.and_then(|detail| {
+                let mut disk = system?.disk.clone();
+                if detail == DiskDetail::Io {
+                    disk.disks.clear();
+                }
+                Some(disk)
+            }),
+            network: system.filter(|_| plan.network).map(|s| s.network.clone()),
+            processes: plan.processes.and_then(|detail| {
+                let processes = self.recording.processes_at(offset)?;
+                Some(processes.iter().filter(|p| detail.threads || !p.is_thread).cloned().collect())
+            }),
+        }
+    }
+
+    pub fn processes(&self, show_threads: bool) -> Option<Vec<ProcessRecord>> {
+        let processes = self.recording.processes_at(self.offset())?;
+        Some(processes.iter().filter(|p| show_threads || !p.is_thread).cloned().collect())
+    }
+
+    pub fn battery(&self) -> Option<&BatterySnapshot> {
+        self.recording.battery_at(self.offset())
+    }
+}
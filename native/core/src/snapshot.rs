@@ -0,0 +1,289 @@
+use serde::{Deserialize, Serialize};
+
+use crate::control::ControlErrorCode;
+use crate::interface::{Duplex, InterfaceKind, LinkState};
+use std::net::IpAddr;
+
+// Plain, serializable copies of everything Peep reports. Field names serialize
+// in camelCase so the JSON shape matches what the renderer already consumes.
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemSnapshot {
+    pub cpu: CpuSnapshot,
+    pub memory: MemorySnapshot,
+    pub disk: DiskSnapshot,
+    pub network: NetworkSnapshot,
+}
+
+// Result of collecting with a `RefreshPlan`: only the planned parts are
+// present. With `RefreshPlan::system()` it serializes exactly like
+// `SystemSnapshot`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialSnapshot {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu: Option<CpuSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory: Option<MemorySnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<DiskSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkSnapshot>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub processes: Option<Vec<ProcessRecord>>,
+}
+
+impl From<SystemSnapshot> for PartialSnapshot {
+    fn from(system: SystemSnapshot) -> Self {
+        PartialSnapshot {
+            cpu: Some(system.cpu),
+            memory: Some(system.memory),
+            disk: Some(system.disk),
+            network: Some(system.network),
+            processes: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuSnapshot {
+    pub usage: f32,
+    pub cores: usize,
+    // CPU brand/name (e.g., "Apple M1 Pro")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub brand: Option<String>,
+    pub per_core: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemorySnapshot {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    pub free_swap: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskSnapshot {
+    // Bytes since the previous refresh of the handle
+    pub read: u64,
+    pub write: u64,
+    // Bytes per second over the previous refresh interval
+    pub read_rate: f64,
+    pub write_rate: f64,
+    // Cumulative bytes since boot
+    pub total_read: u64,
+    pub total_write: u64,
+    pub disks: Vec<DiskDetails>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskDetails {
+    pub name: String,
+    pub mount_point: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub used_space: u64,
+    pub file_system: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSnapshot {
+    // Bytes since the previous refresh of the handle
+    pub rx: u64,
+    pub tx: u64,
+    // Bytes and packets per second over the previous refresh interval
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    pub rx_packets_rate: f64,
+    pub tx_packets_rate: f64,
+    // Cumulative bytes since boot
+    pub total_rx: u64,
+    pub total_tx: u64,
+    // Physical adapters
+    pub interfaces: Vec<InterfaceSnapshot>,
+    // Bridges, tunnels, container links and other software interfaces,
+    // listed apart so they don't crowd out the adapters
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub virtual_interfaces: Vec<InterfaceSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceSnapshot {
+    pub name: String,
+    // Display label for `kind`
+    #[serde(rename = "type")]
+    pub interface_type: String,
+    #[serde(default)]
+    pub kind: InterfaceKind,
+    // False for bridges, tunnels, container links and other software interfaces
+    #[serde(default)]
+    pub physical: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<InterfaceAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<LinkState>,
+    // Negotiated link speed in Mbit/s
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_mbps: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplex: Option<Duplex>,
+    pub received: u64,
+    pub transmitted: u64,
+    pub received_rate: f64,
+    pub transmitted_rate: f64,
+    pub total_received: u64,
+    pub total_transmitted: u64,
+    // Cumulative packet counts since boot
+    pub packets_received: u64,
+    pub packets_transmitted: u64,
+    pub packets_received_rate: f64,
+    pub packets_transmitted_rate: f64,
+}
+
+// An IPv4 or IPv6 address with its prefix length, e.g. 192.168.1.20/24
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    pub prefix: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessRecord {
+    pub pid: u32,
+    pub name: String,
+    pub ppid: u32,
+    pub is_thread: bool,
+    pub cpu: f32,
+    pub memory: u64,
+    // Seconds since the process started
+    pub run_time: u64,
+    // Seconds the process has spent on CPU
+    pub cpu_time: u64,
+    pub status: String,
+    pub user: String,
+    pub command: String,
+    pub disk_read: u64,
+    pub disk_write: u64,
+    // TCP bytes per second over the previous process refresh (Linux only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_rx: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_tx: Option<f64>,
+    // Seconds since the Unix epoch; with the pid, what `identity` is made of
+    #[serde(default)]
+    pub start_time: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+}
+
+impl ProcessRecord {
+    pub fn identity(&self) -> ProcessIdentity {
+        ProcessIdentity { pid: self.pid, start_time: self.start_time, exe: self.exe.clone() }
+    }
+}
+
+// Which process a pid referred to when it was listed. By the time the user
+// acts on it the pid may have been reused, so control APIs that are given
+// an identity check it first.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessIdentity {
+    pub pid: u32,
+    // Seconds since the Unix epoch
+    pub start_time: u64,
+    // Executable path, where the OS lets us read it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe: Option<String>,
+}
+
+impl ProcessIdentity {
+    // Whether `current`, read just now, is still this process. Executables
+    // are only compared when both are known: other users' often aren't.
+    pub fn matches(&self, current: &ProcessIdentity) -> bool {
+        self.pid == current.pid
+            && self.start_time == current.start_time
+            && match (&self.exe, &current.exe) {
+                (Some(expected), Some(exe)) => expected == exe,
+                _ => true,
+            }
+    }
+}
+
+// Everything except `available` is only present when a battery was found
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatterySnapshot {
+    pub available: bool,
+    // State of charge as a percentage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    // Maximum capacity as a percentage of the original design capacity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_full_design: Option<f64>,
+    // Minutes until full (charging) or empty (discharging)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_to_full: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_to_empty: Option<f64>,
+    // Current and full capacity in watt-hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_full: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OsInfo {
+    pub name: String,
+    pub version: String,
+    // macOS marketing name (e.g. "Sequoia")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marketing_name: Option<String>,
+    pub kernel_version: String,
+    pub hostname: String,
+    pub uptime: u64,
+    // Friendly machine model (macOS only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillResult {
+    pub success: bool,
+    pub message: String,
+    // Only on failure
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<ControlErrorCode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub errno: Option<i32>,
+}
+
+impl KillResult {
+    pub fn ok(message: impl Into<String>) -> Self {
+        KillResult { success: true, message: message.into(), code: None, errno: None }
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+use std::io;
+
+// Byte counters of one TCP socket, from the kernel's tcp_info
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TcpCounters {
+    pub received: u64,
+    // Sent and acknowledged by the peer, so retransmissions aren't counted twice
+    pub acked: u64,
+}
+
+// The byte counters of every TCP socket in this network namespace, by socket
+// inode. Sockets without an inode (TIME_WAIT, orphaned) are left out.
+#[cfg(target_os = "linux")]
+pub fn tcp_counters() -> io::Result<HashMap<u64, TcpCounters>> {
+    let mut counters = HashMap::new();
+    linux::dump(libc::AF_INET as u8, &mut counters)?;
+    linux::dump(libc::AF_INET6 as u8, &mut counters)?;
+    Ok(counters)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn tcp_counters() -> io::Result<HashMap<u64, TcpCounters>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Per-socket counters are only available on Linux"))
+}
+
+// A NETLINK_SOCK_DIAG dump of inet_diag_msg records with their tcp_info
+// attribute (see linux/inet_diag.h and linux/tcp.h)
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::io;
+    use std::mem;
+    use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+
+    use super::TcpCounters;
+
+    const NETLINK_SOCK_DIAG: libc::c_int = 4;
+    const SOCK_DIAG_BY_FAMILY: u16 = 20;
+    const NLM_F_REQUEST: u16 = 0x1;
+    const NLM_F_DUMP: u16 = 0x300;
+    const NLMSG_ERROR: u16 = 2;
+    const NLMSG_DONE: u16 = 3;
+    const INET_DIAG_INFO: u16 = 2;
+
+    const NLMSG_HEADER_LEN: usize = 16;
+    // inet_diag_req_v2: family, protocol, ext, pad, states, then a 48-byte inet_diag_sockid
+    const REQUEST_LEN: usize = 56;
+    // inet_diag_msg: family, state, timer, retrans, sockid, expires, rqueue, wqueue, uid, inode
+    const DIAG_MSG_LEN: usize = 72;
+    const INODE_OFFSET: usize = 68;
+    // Offsets of tcpi_bytes_acked and tcpi_bytes_received in struct tcp_info
+    // (Linux 4.1 and later; older kernels send a shorter struct)
+    const BYTES_ACKED_OFFSET: usize = 120;
+    const BYTES_RECEIVED_OFFSET: usize = 128;
+
+    pub fn dump(family: u8, counters: &mut HashMap<u64, TcpCounters>) -> io::Result<()> {
+        // SAFETY: plain socket(2) call; the result is checked before use
+        let fd = unsafe {
+            libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, NETLINK_SOCK_DIAG)
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: `fd` is a freshly opened descriptor nothing else owns
+        let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let mut request = Vec::with_capacity(NLMSG_HEADER_LEN + REQUEST_LEN);
+        request.extend(((NLMSG_HEADER_LEN + REQUEST_LEN) as u32).to_ne_bytes());
+        request.extend(SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        request.extend((NLM_F_REQUEST | NLM_F_DUMP).to_ne_bytes());
+        request.extend(1u32.to_ne_bytes()); // sequence number
+        request.extend(0u32.to_ne_bytes()); // port ID: the kernel
+        request.extend([family, libc::IPPROTO_TCP as u8, 1 << (INET_DIAG_INFO - 1), 0]);
+        request.extend(u32::MAX.to_ne_bytes()); // every TCP state
+        request.extend([0u8; 48]); // any address and port
+
+        // SAFETY: sockaddr_nl is plain data, and all-zero is a valid value
+        let mut kernel: libc::sockaddr_nl = unsafe { mem::zeroed() };
+        kernel.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        // SAFETY: the request buffer and address outlive the call, and the
+        // lengths passed match them
+        let sent = unsafe {
+            libc::sendto(
+                socket.as_raw_fd(),
+                request.as_ptr().cast(),
+                request.len(),
+                0,
+                (&kernel as *const libc::sockaddr_nl).cast(),
+                mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+            )
+        };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            // SAFETY: the kernel writes at most `buffer.len()` bytes into `buffer`
+            let received = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr().cast(), buffer.len(), 0) };
+            if received < 0 {
+                let err = io::Error::last_os_error();
+                if err.kind() == io::ErrorKind::Interrupted {
+                    continue;
+                }
+                return Err(err);
+            }
+            if parse_messages(&buffer[..received as usize], counters)? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_ne_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_ne_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+        u64::from_ne_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    // Netlink messages and attributes are padded to 4 bytes
+    fn aligned(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    // Parse one datagram of the dump; true once the dump is complete
+    fn parse_messages(mut bytes: &[u8], counters: &mut HashMap<u64, TcpCounters>) -> io::Result<bool> {
+        while bytes.len() >= NLMSG_HEADER_LEN {
+            let len = u32_at(bytes, 0) as usize;
+            if len < NLMSG_HEADER_LEN || len > bytes.len() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "Truncated netlink message"));
+            }
+            match u16_at(bytes, 4) {
+                NLMSG_DONE => return Ok(true),
+                NLMSG_ERROR => {
+                    let errno = if len >= NLMSG_HEADER_LEN + 4 { -(u32_at(bytes, NLMSG_HEADER_LEN) as i32) } else { 0 };
+                    return if errno == 0 { Ok(true) } else { Err(io::Error::from_raw_os_error(errno)) };
+                }
+                SOCK_DIAG_BY_FAMILY => parse_socket(&bytes[NLMSG_HEADER_LEN..len], counters),
+                _ => {}
+            }
+            bytes = &bytes[aligned(len).min(bytes.len())..];
+        }
+        Ok(false)
+    }
+
+    fn parse_socket(message: &[u8], counters: &mut HashMap<u64, TcpCounters>) {
+        if message.len() < DIAG_MSG_LEN {
+            return;
+        }
+        let inode = u32_at(message, INODE_OFFSET) as u64;
+        if inode == 0 {
+            return;
+        }
+
+        let mut attributes = &message[DIAG_MSG_LEN..];
+        while attributes.len() >= 4 {
+            let len = u16_at(attributes, 0) as usize;
+            if len < 4 || len > attributes.len() {
+                return;
+            }
+            let info = &attributes[4..len];
+            if u16_at(attributes, 2) == INET_DIAG_INFO && info.len() >= BYTES_RECEIVED_OFFSET + 8 {
+                counters.insert(inode, TcpCounters {
+                    received: u64_at(info, BYTES_RECEIVED_OFFSET),
+                    acked: u64_at(info, BYTES_ACKED_OFFSET),
+                });
+                return;
+            }
+            attributes = &attributes[aligned(len).min(attributes.len())..];
+        }
+    }
+}
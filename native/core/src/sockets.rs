@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::sock_diag::{tcp_counters, TcpCounters};
+
+// Which process owns each socket, by inode, from the `socket:[N]` links in
+// `<proc_root>/<pid>/fd`. A socket shared between processes (inherited
+// across fork) goes to the lowest PID. Processes whose descriptors can't be
+// read (other users', unless running as root) own nothing.
+pub fn socket_owners(proc_root: &Path) -> HashMap<u64, u32> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|name| name.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let inode = target.to_str()
+                .and_then(|target| target.strip_prefix("socket:["))
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|inode| inode.parse::<u64>().ok());
+            if let Some(inode) = inode {
+                owners.entry(inode)
+                    .and_modify(|owner: &mut u32| *owner = (*owner).min(pid))
+                    .or_insert(pid);
+            }
+        }
+    }
+    owners
+}
+
+// Cumulative TCP bytes per process, built up from per-socket counters.
+//
+// Socket counters vanish when the socket closes, so a process's traffic
+// can't just be the sum over its open sockets: each refresh instead adds
+// what every socket moved since the previous one. Sockets opened and closed
+// between two refreshes are missed, as are sockets in other network
+// namespaces. UDP has no per-socket byte counters and isn't counted.
+#[derive(Default)]
+pub struct ProcessTraffic {
+    sockets: HashMap<u64, TcpCounters>,
+    totals: HashMap<u32, TcpCounters>,
+    refreshed: bool,
+}
+
+impl ProcessTraffic {
+    pub fn refresh(&mut self) {
+        let Ok(sockets) = tcp_counters() else {
+            return;
+        };
+        let owners = socket_owners(Path::new("/proc"));
+
+        for (inode, counters) in &sockets {
+            let moved = match self.sockets.get(inode) {
+                Some(previous) => TcpCounters {
+                    received: counters.received.saturating_sub(previous.received),
+                    acked: counters.acked.saturating_sub(previous.acked),
+                },
+                // Opened since the previous refresh; on the first refresh
+                // every socket's history predates us and is the baseline
+                None if self.refreshed => *counters,
+                None => TcpCounters::default(),
+            };
+            if let Some(pid) = owners.get(inode) {
+                let total = self.totals.entry(*pid).or_default();
+                total.received += moved.received;
+                total.acked += moved.acked;
+            }
+        }
+
+        // Forget processes that have exited, so a reused PID starts over
+        self.totals.retain(|pid, _| Path::new("/proc").join(pid.to_string()).exists());
+        self.sockets = sockets;
+        self.refreshed = true;
+    }
+
+    // TCP bytes received and sent by `pid` since the first refresh; None
+    // until a refresh succeeded (never, where sock_diag isn't available)
+    pub fn totals(&self, pid: u32) -> Option<TcpCounters> {
+        self.refreshed.then(|| self.totals.get(&pid).copied().unwrap_or_default())
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Duration;
+
+use crate::control::ControlError;
+use crate::interface::{InterfaceKind, LinkInfo};
+use crate::snapshot::{InterfaceAddress, ProcessIdentity};
+use crate::plan::{ProcessDetail, RefreshPlan};
+
+// Where `Collector` gets its numbers from. A source only reports raw
+// readings: cumulative counters, current levels and whatever the OS already
+// computes (CPU usage). Filtering, deltas, rates and shaping into snapshots
+// all happen in the collector, so they behave the same for every source.
+//
+// `SysinfoSource` is the real machine; `FakeSource` is a scripted one for
+// tests. A source must be readable as soon as it's constructed, since the
+// collector takes its I/O baseline from the first readings.
+pub trait MetricsSource: Send {
+    // Monotonic time, used to turn counter deltas into rates
+    fn now(&self) -> Duration;
+
+    // Bring the parts of the source that `plan` names up to date
+    fn refresh(&mut self, plan: &RefreshPlan);
+
+    fn cpu(&self) -> CpuReading;
+    fn memory(&self) -> MemoryReading;
+    // Every disk the OS knows about, unfiltered
+    fn disks(&self) -> Vec<DiskReading>;
+    // Every network interface, unfiltered
+    fn networks(&self) -> Vec<InterfaceReading>;
+    // Every process, threads included when the source tracks them; the
+    // collector drops threads unless asked for. Command lines and users only
+    // need to be filled in when `detail` asks for them.
+    fn processes(&self, detail: ProcessDetail) -> Vec<ProcessReading>;
+    fn battery(&self) -> Option<BatteryReading>;
+
+    // Who `pid` is right now, read fresh rather than from the last refresh;
+    // None when there's no such process
+    fn identity(&mut self, pid: u32) -> Option<ProcessIdentity>;
+
+    // SIGKILL the process, or say why it couldn't be. Unless `force`,
+    // processes the protection policy covers are refused.
+    fn kill(&mut self, pid: u32, force: bool) -> Result<(), ControlError>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuReading {
+    pub brand: Option<String>,
+    // Percentages over the interval between the last two CPU refreshes
+    pub usage: f32,
+    pub per_core: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryReading {
+    pub total: u64,
+    pub used: u64,
+    pub free: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+    pub free_swap: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskReading {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub is_removable: bool,
+    pub total_space: u64,
+    pub available_space: u64,
+    // Cumulative bytes since boot
+    pub total_read: u64,
+    pub total_written: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterfaceReading {
+    pub name: String,
+    pub kind: InterfaceKind,
+    // Backed by hardware rather than created in software
+    pub physical: bool,
+    pub mac_address: Option<String>,
+    pub addresses: Vec<InterfaceAddress>,
+    pub mtu: Option<u64>,
+    pub link: LinkInfo,
+    // Cumulative counts since boot
+    pub total_received: u64,
+    pub total_transmitted: u64,
+    pub total_packets_received: u64,
+    pub total_packets_transmitted: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProcessReading {
+    pub pid: u32,
+    pub parent: Option<u32>,
+    pub name: String,
+    pub is_thread: bool,
+    // Percentage of one core over the interval between process refreshes
+    pub cpu: f32,
+    pub memory: u64,
+    // Seconds since the process started
+    pub run_time: u64,
+    // Seconds the process has spent on CPU
+    pub cpu_time: u64,
+    pub status: String,
+    pub user: String,
+    pub command: Vec<String>,
+    // Cumulative bytes since the process started
+    pub total_disk_read: u64,
+    pub total_disk_written: u64,
+    // Cumulative TCP bytes; None where the source can't attribute traffic
+    // to processes, or `detail` didn't ask for it
+    pub total_net_received: Option<u64>,
+    pub total_net_transmitted: Option<u64>,
+    // Seconds since the Unix epoch
+    pub start_time: u64,
+    pub exe: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BatteryReading {
+    // State of charge, 0 to 1
+    pub charge: f64,
+    pub state: String,
+    // Watt-hours
+    pub energy: f64,
+    pub energy_full: f64,
+    pub energy_full_design: f64,
+    pub time_to_full: Option<Duration>,
+    pub time_to_empty: Option<Duration>,
+    pub temperature: Option<f64>,
+}
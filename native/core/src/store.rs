@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::snapshot::SystemSnapshot;
+
+// Append-only, segment-based store for sampled system snapshots.
+//
+// The store is a directory of segment files named after the timestamp of
+// their first record (`<ms>.seg`). Each record is framed as
+//
+//     [payload length: u32 LE][crc32 of payload: u32 LE][payload: JSON]
+//
+// so a crash mid-write leaves at most one torn record at the tail of the
+// newest segment, which is detected and truncated away on open. Older
+// segments are sealed and never rewritten on open; a damaged one is read up
+// to its first bad record.
+
+const SEGMENT_EXTENSION: &str = "seg";
+const HEADER_LEN: usize = 8;
+// Guards against reading a garbage length as a huge allocation
+const MAX_RECORD_LEN: u32 = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreOptions {
+    // Samples closer together than this are dropped on append
+    pub min_interval: Duration,
+    // Start a new segment once the current one covers this much time...
+    pub segment_span: Duration,
+    // ...or grows past this many bytes
+    pub max_segment_bytes: u64,
+    // Segments whose newest sample is older than this are deleted
+    pub retention: Duration,
+    // Segments older than this are thinned to one sample per `compact_step`
+    pub compact_after: Duration,
+    pub compact_step: Duration,
+}
+
+impl Default for StoreOptions {
+    fn default() -> Self {
+        StoreOptions {
+            min_interval: Duration::from_secs(5),
+            segment_span: Duration::from_secs(60 * 60),
+            max_segment_bytes: 8 * 1024 * 1024,
+            retention: Duration::from_secs(7 * 24 * 60 * 60),
+            compact_after: Duration::from_secs(6 * 60 * 60),
+            compact_step: Duration::from_secs(60),
+        }
+    }
+}
+
+// A stored snapshot; serializes as the `get_system_info` shape plus `timestamp`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredSample {
+    // ms since epoch
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub snapshot: SystemSnapshot,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    path: PathBuf,
+    first: u64,
+    last: u64,
+    len: u64,
+    compacted: bool,
+}
+
+// A query over the segments that were current when it was prepared
+#[derive(Debug, Clone)]
+pub struct StoreQuery {
+    paths: Vec<PathBuf>,
+    from: u64,
+    to: u64,
+}
+
+impl StoreQuery {
+    pub fn run(self) -> io::Result<Vec<StoredSample>> {
+        let mut samples = Vec::new();
+        for path in &self.paths {
+            // Retention may have removed the segment since; compaction
+            // renames over it, so it's always either the old or new version
+            let records = match read_segment(path) {
+                Ok((records, _)) => records,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err),
+            };
+            samples.extend(records.into_iter().filter(|r| r.timestamp >= self.from && r.timestamp <= self.to));
+        }
+        Ok(samples)
+    }
+}
+
+pub struct Store {
+    dir: PathBuf,
+    options: StoreOptions,
+    // Ordered oldest to newest; only the last one is appended to
+    segments: Vec<Segment>,
+    active: Option<File>,
+}
+
+impl Store {
+    // Open (or create) a store in `dir`, recovering from any torn tail write
+    pub fn open(dir: impl AsRef<Path>, options: StoreOptions) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == SEGMENT_EXTENSION) && segment_start(&path).is_some() {
+                paths.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "tmp") {
+                // Left behind by a compaction that never got renamed into place
+                fs::remove_file(&path)?;
+            }
+        }
+        paths.sort_by_key(|path| segment_start(path));
+
+        let mut segments = Vec::new();
+        let newest = paths.len().saturating_sub(1);
+        for (i, path) in paths.into_iter().enumerate() {
+            let (records, valid_len) = read_segment(&path)?;
+            // Only the newest segment can have been mid-write, so only its
+            // tail is cut back to the last intact record
+            if i == newest && valid_len < fs::metadata(&path)?.len() {
+                OpenOptions::new().write(true).open(&path)?.set_len(valid_len)?;
+            }
+            match (records.first(), records.last()) {
+                (Some(first), Some(last)) => segments.push(Segment {
+                    path,
+                    first: first.timestamp,
+                    last: last.timestamp,
+                    len: valid_len,
+                    compacted: false,
+                }),
+                // A torn first write: nothing survived, so there's nothing worth keeping
+                _ if i == newest => fs::remove_file(&path)?,
+                // Left on disk untouched rather than deleting what can't be read
+                _ => {}
+            }
+        }
+
+        Ok(Store { dir, options, segments, active: None })
+    }
+
+    pub fn options(&self) -> &StoreOptions {
+        &self.options
+    }
+
+    // Append one sample. Samples older than the newest stored one, or closer
+    // to it than `min_interval`, are ignored. Returns whether it was written.
+    pub fn append(&mut self, timestamp: u64, snapshot: &SystemSnapshot) -> io::Result<bool> {
+        if let Some(last) = self.segments.last().map(|s| s.last) {
+            if timestamp < last + self.options.min_interval.as_millis() as u64 {
+                return Ok(false);
+            }
+        }
+
+        let payload = serde_json::to_vec(&StoredSample { timestamp, snapshot: snapshot.clone() })
+            .map_err(io::Error::other)?;
+        let record = encode_record(&payload);
+
+        if self.needs_new_segment(timestamp, record.len() as u64) {
+            self.start_segment(timestamp)?;
+            self.maintain(timestamp)?;
+        }
+
+        if self.active.is_none() {
+            let segment = self.segments.last().expect("active segment");
+            self.active = Some(OpenOptions::new().append(true).open(&segment.path)?);
+        }
+
+        // One write per record keeps a crash from interleaving partial records
+        self.active.as_mut().unwrap().write_all(&record)?;
+
+        let segment = self.segments.last_mut().unwrap();
+        segment.last = timestamp;
+        segment.len += record.len() as u64;
+        Ok(true)
+    }
+
+    // Samples with `from <= timestamp <= to`, oldest first
+    pub fn query(&self, from: u64, to: u64) -> io::Result<Vec<StoredSample>> {
+        self.prepare_query(from, to).run()
+    }
+
+    // Pick the segments a query needs without reading them, so the reading
+    // can happen without holding on to the store
+    pub fn prepare_query(&self, from: u64, to: u64) -> StoreQuery {
+        let paths = self.segments.iter()
+            .filter(|s| s.last >= from && s.first <= to)
+            .map(|s| s.path.clone())
+            .collect();
+        StoreQuery { paths, from, to }
+    }
+
+    // Timestamps of the oldest and newest stored samples
+    pub fn range(&self) -> Option<(u64, u64)> {
+        Some((self.segments.first()?.first, self.segments.last()?.last))
+    }
+
+    // Apply retention and compaction relative to `now` (ms since epoch).
+    // Called automatically whenever a new segment is started.
+    pub fn maintain(&mut self, now: u64) -> io::Result<()> {
+        let retention = self.options.retention.as_millis() as u64;
+        let compact_after = self.options.compact_after.as_millis() as u64;
+        let step = (self.options.compact_step.as_millis() as u64).max(1);
+
+        // Never touch the segment currently being appended to
+        let sealed = self.segments.len().saturating_sub(1);
+
+        let mut expired = 0;
+        while expired < sealed && self.segments[expired].last < now.saturating_sub(retention) {
+            fs::remove_file(&self.segments[expired].path)?;
+            expired += 1;
+        }
+        self.segments.drain(..expired);
+        let sealed = sealed - expired;
+
+        for segment in &mut self.segments[..sealed] {
+            if !segment.compacted && segment.last < now.saturating_sub(compact_after) {
+                compact_segment(segment, step)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_new_segment(&self, timestamp: u64, record_len: u64) -> bool {
+        match self.segments.last() {
+            None => true,
+            Some(segment) => {
+                timestamp >= segment.first + self.options.segment_span.as_millis() as u64
+                    || segment.len + record_len > self.options.max_segment_bytes
+            }
+        }
+    }
+
+    fn start_segment(&mut self, timestamp: u64) -> io::Result<()> {
+        // Make sure the previous segment is durable before moving on from it
+        if let Some(file) = self.active.take() {
+            file.sync_data()?;
+        }
+
+        let path = self.dir.join(format!("{:013}.{}", timestamp, SEGMENT_EXTENSION));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        self.segments.push(Segment {
+            path,
+            first: timestamp,
+            last: timestamp,
+            len: 0,
+            compacted: false,
+        });
+        self.active = Some(file);
+        Ok(())
+    }
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        if let Some(file) = self.active.take() {
+            let _ = file.sync_data();
+        }
+    }
+}
+
+fn segment_start(path: &Path) -> Option<u64> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+fn encode_record(payload: &[u8]) -> Vec<u8> {
+    let mut record = Vec::with_capacity(HEADER_LEN + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32fast::hash(payload).to_le_bytes());
+    record.extend_from_slice(payload);
+    record
+}
+
+// Decode every intact record in a segment. Also returns the byte length of
+// the valid prefix; anything past it is a torn or corrupt write.
+fn read_segment(path: &Path) -> io::Result<(Vec<StoredSample>, u64)> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+
+    let mut records = Vec::new();
+    let mut offset = 0;
+    while bytes.len() - offset >= HEADER_LEN {
+        let len = u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let crc = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().unwrap());
+        if len > MAX_RECORD_LEN {
+            break;
+        }
+
+        let start = offset + HEADER_LEN;
+        let end = start + len as usize;
+        if end > bytes.len() || crc32fast::hash(&bytes[start..end]) != crc {
+            break;
+        }
+        match serde_json::from_slice::<StoredSample>(&bytes[start..end]) {
+            Ok(record) => records.push(record),
+            Err(_) => break,
+        }
+        offset = end;
+    }
+
+    Ok((records, offset as u64))
+}
+
+// Keep the first sample of every `step` window, rewriting the segment through
+// a temporary file so a crash leaves either the old or the new version
+fn compact_segment(segment: &mut Segment, step: u64) -> io::Result<()> {
+    let (records, _) = read_segment(&segment.path)?;
+
+    let records_len = records.len();
+    let mut kept: Vec<StoredSample> = Vec::new();
+    for record in records {
+        let window = record.timestamp / step;
+        if kept.last().is_none_or(|k| k.timestamp / step != window) {
+            kept.push(record);
+        }
+    }
+
+    if kept.len() == records_len {
+        segment.compacted = true;
+        return Ok(());
+    }
+
+    let tmp = segment.path.with_extension("tmp");
+    {
+        let mut file = File::create(&tmp)?;
+        let mut len = 0;
+        for record in &kept {
+            let payload = serde_json::to_vec(record).map_err(io::Error::other)?;
+            let record = encode_record(&payload);
+            file.write_all(&record)?;
+            len += record.len() as u64;
+        }
+        file.sync_all()?;
+        segment.len = len;
+    }
+    fs::rename(&tmp, &segment.path)?;
+    // The rename itself only survives a crash once the directory is synced
+    if let Some(dir) = segment.path.parent() {
+        sync_dir(dir)?;
+    }
+
+    segment.compacted = true;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_dir(dir: &Path) -> io::Result<()> {
+    File::open(dir)?.sync_all()
+}
+
+// Directories can't be opened as files here; renames are durable once the
+// file itself is synced
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Path) -> io::Result<()> {
+    Ok(())
+}
@@ -0,0 +1,270 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{
+    System, Pid, Process, ProcessStatus, ProcessesToUpdate, Networks, Users, Disks,
+    RefreshKind, CpuRefreshKind, MemoryRefreshKind, DiskRefreshKind, ProcessRefreshKind, UpdateKind,
+};
+
+use crate::control::{check_protection, signal_process, ControlError, ProcessSignal};
+use crate::interface::{classify, link_info};
+use crate::plan::{DiskDetail, ProcessDetail, RefreshPlan};
+use crate::power::read_battery;
+use crate::snapshot::{InterfaceAddress, ProcessIdentity};
+use crate::sockets::ProcessTraffic;
+use crate::source::{
+    BatteryReading, CpuReading, DiskReading, InterfaceReading, MemoryReading, MetricsSource, ProcessReading,
+};
+
+// The real machine, through sysinfo (and the battery crate for batteries).
+// Owns the sysinfo handles so CPU usage deltas are kept between refreshes.
+pub struct SysinfoSource {
+    system: System,
+    networks: Networks,
+    disks: Disks,
+    users: Users,
+    traffic: ProcessTraffic,
+    started: Instant,
+}
+
+impl Default for SysinfoSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SysinfoSource {
+    pub fn new() -> Self {
+        SysinfoSource {
+            system: System::new_with_specifics(
+                RefreshKind::nothing()
+                    .with_cpu(CpuRefreshKind::everything())
+                    .with_memory(MemoryRefreshKind::everything()),
+            ),
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list(),
+            users: Users::new_with_refreshed_list(),
+            traffic: ProcessTraffic::default(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl MetricsSource for SysinfoSource {
+    fn now(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    fn refresh(&mut self, plan: &RefreshPlan) {
+        if plan.cpu {
+            self.system.refresh_cpu_usage();
+        }
+        if plan.memory {
+            self.system.refresh_memory();
+        }
+        if let Some(detail) = plan.disks {
+            let kind = match detail {
+                DiskDetail::Io => DiskRefreshKind::nothing().with_io_usage(),
+                DiskDetail::Volumes => DiskRefreshKind::nothing().with_io_usage().with_storage(),
+            };
+            self.disks.refresh_specifics(true, kind);
+        }
+        if plan.network {
+            self.networks.refresh(true);
+        }
+        if let Some(detail) = plan.processes {
+            let mut kind = ProcessRefreshKind::nothing().with_cpu().with_memory().with_disk_usage();
+            // An exec keeps the pid but replaces the command line and
+            // executable, so those are re-read every time. The owner is read
+            // once per process.
+            if detail.command {
+                kind = kind.with_cmd(UpdateKind::Always).with_exe(UpdateKind::Always);
+            }
+            if detail.user {
+                kind = kind.with_user(UpdateKind::OnlyIfNotSet);
+            }
+            // `nothing()` still lists tasks, so they have to be turned off explicitly
+            if !detail.threads {
+                kind = kind.without_tasks();
+            }
+            self.system.refresh_processes_specifics(ProcessesToUpdate::All, true, kind);
+            if detail.network {
+                self.traffic.refresh();
+            }
+        }
+    }
+
+    fn cpu(&self) -> CpuReading {
+        let cpus = self.system.cpus();
+        CpuReading {
+            brand: cpus.first().map(|cpu| cpu.brand().to_string()),
+            usage: self.system.global_cpu_usage(),
+            per_core: cpus.iter().map(|cpu| cpu.cpu_usage()).collect(),
+        }
+    }
+
+    fn memory(&self) -> MemoryReading {
+        MemoryReading {
+            total: self.system.total_memory(),
+            used: self.system.used_memory(),
+            free: self.system.free_memory(),
+            total_swap: self.system.total_swap(),
+            used_swap: self.system.used_swap(),
+            free_swap: self.system.free_swap(),
+        }
+    }
+
+    fn disks(&self) -> Vec<DiskReading> {
+        self.disks.list().iter()
+            .map(|disk| {
+                let usage = disk.usage();
+                DiskReading {
+                    name: disk.name().to_string_lossy().into_owned(),
+                    mount_point: disk.mount_point().to_string_lossy().into_owned(),
+                    file_system: disk.file_system().to_string_lossy().into_owned(),
+                    is_removable: disk.is_removable(),
+                    total_space: disk.total_space(),
+                    available_space: disk.available_space(),
+                    total_read: usage.total_read_bytes,
+                    total_written: usage.total_written_bytes,
+                }
+            })
+            .collect()
+    }
+
+    fn networks(&self) -> Vec<InterfaceReading> {
+        self.networks.iter()
+            .map(|(name, data)| {
+                let class = classify(name);
+                // IPv4 first, for a stable order between refreshes
+                let mut addresses: Vec<InterfaceAddress> = data.ip_networks().iter()
+                    .map(|network| InterfaceAddress { address: network.addr, prefix: network.prefix })
+                    .collect();
+                addresses.sort_by_key(|a| (a.address.is_ipv6(), a.address));
+                InterfaceReading {
+                    name: name.clone(),
+                    kind: class.kind,
+                    physical: class.physical,
+                    mac_address: Some(data.mac_address())
+                        .filter(|mac| !mac.is_unspecified())
+                        .map(|mac| mac.to_string()),
+                    addresses,
+                    mtu: Some(data.mtu()).filter(|mtu| *mtu > 0),
+                    link: link_info(name),
+                    total_received: data.total_received(),
+                    total_transmitted: data.total_transmitted(),
+                    total_packets_received: data.total_packets_received(),
+                    total_packets_transmitted: data.total_packets_transmitted(),
+                }
+            })
+            .collect()
+    }
+
+    fn processes(&self, detail: ProcessDetail) -> Vec<ProcessReading> {
+        // Get process age (uptime in seconds) - current time minus start time
+        let current_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        self.system.processes()
+            .values()
+            .map(|process| {
+                let disk_usage = process.disk_usage();
+                let traffic = if detail.network { self.traffic.totals(process.pid().as_u32()) } else { None };
+                ProcessReading {
+                    pid: process.pid().as_u32(),
+                    parent: process.parent().map(|p| p.as_u32()),
+                    name: process.name().to_string_lossy().into_owned(),
+                    is_thread: process.thread_kind().is_some(),
+                    cpu: process.cpu_usage(),
+                    memory: process.memory(),
+                    run_time: current_time.saturating_sub(process.start_time()),
+                    // Milliseconds of user plus system time
+                    cpu_time: process.accumulated_cpu_time() / 1000,
+                    status: status_label(process.status()).to_string(),
+                    user: if detail.user { user_name(process, &self.users) } else { String::new() },
+                    command: if detail.command {
+                        process.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect()
+                    } else {
+                        Vec::new()
+                    },
+                    total_disk_read: disk_usage.total_read_bytes,
+                    total_disk_written: disk_usage.total_written_bytes,
+                    total_net_received: traffic.map(|t| t.received),
+                    total_net_transmitted: traffic.map(|t| t.acked),
+                    start_time: process.start_time(),
+                    exe: if detail.command { exe_path(process) } else { None },
+                }
+            })
+            .collect()
+    }
+
+    fn battery(&self) -> Option<BatteryReading> {
+        read_battery()
+    }
+
+    fn identity(&mut self, pid: u32) -> Option<ProcessIdentity> {
+        let pid = Pid::from_u32(pid);
+        let kind = ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet);
+        self.system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, kind);
+        self.system.process(pid).filter(|process| is_alive(process)).map(identity_of)
+    }
+
+    fn kill(&mut self, pid: u32, force: bool) -> Result<(), ControlError> {
+        let pid = Pid::from_u32(pid);
+        // Processes started since the last refresh aren't known yet, and the
+        // protection policy may need the executable path
+        let kind = ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet);
+        self.system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), false, kind);
+        match self.system.process(pid) {
+            Some(process) => {
+                check_protection(process, force)?;
+                signal_process(process, ProcessSignal::Kill)
+            }
+            None => Err(ControlError::not_found()),
+        }
+    }
+}
+
+// Format process status nicely
+fn status_label(status: ProcessStatus) -> &'static str {
+    match status {
+        ProcessStatus::Run => "Running",
+        ProcessStatus::Sleep => "Sleep",
+        ProcessStatus::Idle => "Idle",
+        ProcessStatus::Zombie => "Zombie",
+        ProcessStatus::Stop => "Stopped",
+        ProcessStatus::Dead => "Dead",
+        ProcessStatus::Tracing => "Tracing",
+        ProcessStatus::Wakekill => "Wakekill",
+        ProcessStatus::Waking => "Waking",
+        ProcessStatus::Parked => "Parked",
+        ProcessStatus::LockBlocked => "Blocked",
+        ProcessStatus::UninterruptibleDiskSleep => "DiskSleep",
+        _ => "Unknown",
+    }
+}
+
+pub(crate) fn exe_path(process: &Process) -> Option<String> {
+    process.exe().map(|exe| exe.to_string_lossy().into_owned())
+}
+
+pub(crate) fn identity_of(process: &Process) -> ProcessIdentity {
+    ProcessIdentity { pid: process.pid().as_u32(), start_time: process.start_time(), exe: exe_path(process) }
+}
+
+// Zombies only wait for their parent to collect the exit status
+pub(crate) fn is_alive(process: &Process) -> bool {
+    !matches!(process.status(), ProcessStatus::Zombie | ProcessStatus::Dead)
+}
+
+// Get user name from user ID
+pub(crate) fn user_name(process: &Process, users: &Users) -> String {
+    if let Some(uid) = process.user_id() {
+        users.iter()
+            .find(|u| u.id() == uid)
+            .map(|u| u.name().to_string())
+            .unwrap_or_else(|| uid.to_string())
+    } else {
+        "unknown".to_string()
+    }
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::snapshot::ProcessRecord;
+
+// One process with its children and the totals of the subtree it roots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub process: ProcessRecord,
+    pub children: Vec<ProcessNode>,
+    pub total: SubtreeTotal,
+}
+
+// Sums over a process and all its descendants. Memory adds up resident
+// sizes, so pages shared between processes are counted once per process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtreeTotal {
+    pub processes: u32,
+    pub cpu: f32,
+    pub memory: u64,
+    pub disk_read: u64,
+    pub disk_write: u64,
+}
+
+impl SubtreeTotal {
+    fn of(process: &ProcessRecord) -> Self {
+        SubtreeTotal {
+            processes: 1,
+            cpu: process.cpu,
+            memory: process.memory,
+            disk_read: process.disk_read,
+            disk_write: process.disk_write,
+        }
+    }
+
+    fn add(&mut self, other: &SubtreeTotal) {
+        self.processes += other.processes;
+        self.cpu += other.cpu;
+        self.memory += other.memory;
+        self.disk_read += other.disk_read;
+        self.disk_write += other.disk_write;
+    }
+}
+
+// Nest a flat process list by parent, ordered by pid at every level. Threads
+// are left out, since they share their process's memory.
+//
+// A process is a root when it has no parent (ppid 0, which is also how pid 1
+// and kernel threads report), when its parent isn't in the list, or when its
+// parent started after it did: the parent exited and its pid was reused
+// before the kernel's reparenting showed up in this refresh. Parent loops,
+// which the same races can produce, are broken at their lowest pid.
+pub fn process_tree(processes: &[ProcessRecord]) -> Vec<ProcessNode> {
+    let mut processes: Vec<&ProcessRecord> = processes.iter().filter(|p| !p.is_thread).collect();
+    processes.sort_by_key(|p| p.pid);
+    let by_pid: HashMap<u32, &ProcessRecord> = processes.iter().map(|p| (p.pid, *p)).collect();
+
+    let mut roots = Vec::new();
+    let mut children: HashMap<u32, Vec<&ProcessRecord>> = HashMap::new();
+    for &process in &processes {
+        match by_pid.get(&process.ppid) {
+            Some(parent) if process.ppid != 0 && process.ppid != process.pid && parent.run_time >= process.run_time => {
+                children.entry(process.ppid).or_default().push(process);
+            }
+            _ => roots.push(process),
+        }
+    }
+
+    let mut placed = HashSet::new();
+    let mut tree: Vec<ProcessNode> = roots.into_iter().map(|root| node(root, &children, &mut placed)).collect();
+    // Whatever is left only descends from a loop
+    for process in processes {
+        if !placed.contains(&process.pid) {
+            tree.push(node(process, &children, &mut placed));
+        }
+    }
+    tree.sort_by_key(|n| n.process.pid);
+    tree
+}
+
+fn node(process: &ProcessRecord, children: &HashMap<u32, Vec<&ProcessRecord>>, placed: &mut HashSet<u32>) -> ProcessNode {
+    placed.insert(process.pid);
+    let mut total = SubtreeTotal::of(process);
+    let mut nodes = Vec::new();
+    for &child in children.get(&process.pid).into_iter().flatten() {
+        if placed.contains(&child.pid) {
+            continue;
+        }
+        let child = node(child, children, placed);
+        total.add(&child.total);
+        nodes.push(child);
+    }
+    ProcessNode { process: process.clone(), children: nodes, total }
+}
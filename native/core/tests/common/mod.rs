@@ -0,0 +1,45 @@
+use peep_core::{CpuSnapshot, DiskDetails, DiskSnapshot, MemorySnapshot, NetworkSnapshot, SystemSnapshot};
+
+// A fixed system snapshot with `usage` as its CPU load, one volume and no
+// network interfaces
+pub fn snapshot(usage: f32) -> SystemSnapshot {
+    SystemSnapshot {
+        cpu: CpuSnapshot { usage, cores: 2, brand: None, per_core: vec![usage, usage] },
+        memory: MemorySnapshot {
+            total: 1024,
+            used: 512,
+            free: 512,
+            total_swap: 0,
+            used_swap: 0,
+            free_swap: 0,
+        },
+        disk: DiskSnapshot {
+            read: 10,
+            write: 20,
+            read_rate: 10.0,
+            write_rate: 20.0,
+            total_read: 100,
+            total_write: 200,
+            disks: vec![DiskDetails {
+                name: "/dev/sda1".to_string(),
+                mount_point: "/".to_string(),
+                file_system: "ext4".to_string(),
+                total_space: 1000,
+                available_space: 400,
+                used_space: 600,
+            }],
+        },
+        network: NetworkSnapshot {
+            rx: 1,
+            tx: 2,
+            rx_rate: 1.0,
+            tx_rate: 2.0,
+            rx_packets_rate: 0.0,
+            tx_packets_rate: 0.0,
+            total_rx: 10,
+            total_tx: 20,
+            interfaces: vec![],
+            virtual_interfaces: vec![],
+        },
+    }
+}
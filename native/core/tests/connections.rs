@@ -0,0 +1,67 @@
+use peep_core::{connections, parse_connection_table, ConnectionFilter, ConnectionState, Protocol};
+use std::net::IpAddr;
+
+// Captured on a little-endian machine, where the kernel's native-endian
+// words reverse each group of four address bytes
+#[cfg(target_endian = "little")]
+#[test]
+fn parses_proc_net_tables() {
+    let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0CEA 00000000:0000 0A 00000000:00000003 00:00000000 00000000   999        0 41325 1 0000000000000000 100 0 0 10 0
+   1: 0201A8C0:D431 22D8BA8E:01BB 01 00000124:00000000 02:00000B2C 00000000  1000        0 52211 2 0000000000000000 20 4 30 10 -1
+   2: garbage
+";
+    let connections = parse_connection_table(tcp, Protocol::Tcp);
+    assert_eq!(connections.len(), 2);
+
+    let listener = &connections[0];
+    assert_eq!(listener.local_address, "127.0.0.1".parse::<IpAddr>().unwrap());
+    assert_eq!(listener.local_port, 3306);
+    assert_eq!(listener.remote_address, "0.0.0.0".parse::<IpAddr>().unwrap());
+    assert_eq!(listener.state, ConnectionState::Listen);
+    assert_eq!(listener.receive_queue, 3);
+    assert_eq!((listener.uid, listener.inode), (999, 41325));
+
+    let https = &connections[1];
+    assert_eq!(https.local_address, "192.168.1.2".parse::<IpAddr>().unwrap());
+    assert_eq!(https.local_port, 54321);
+    assert_eq!(https.remote_address, "142.186.216.34".parse::<IpAddr>().unwrap());
+    assert_eq!(https.remote_port, 443);
+    assert_eq!(https.state, ConnectionState::Established);
+    assert_eq!(https.send_queue, 0x124);
+
+    let udp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  12: 00000000000000000000000001000000:14E9 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 20786 2 0000000000000000 0
+";
+    let connections = parse_connection_table(udp6, Protocol::Udp6);
+    assert_eq!(connections[0].local_address, "::1".parse::<IpAddr>().unwrap());
+    assert_eq!(connections[0].local_port, 5353);
+    assert_eq!(connections[0].state, ConnectionState::Close);
+    assert_eq!(connections[0].pid, None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn finds_this_process_listening() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let filter = ConnectionFilter {
+        protocols: vec![Protocol::Tcp],
+        states: vec![ConnectionState::Listen],
+        pid: Some(std::process::id()),
+    };
+    let found = connections(&filter).unwrap();
+    let ours = found.iter().find(|c| c.local_port == port).expect("listener not found");
+    assert_eq!(ours.pid, Some(std::process::id()));
+    assert!(ours.process_name.is_some());
+    assert!(found.iter().all(|c| c.state == ConnectionState::Listen && c.protocol == Protocol::Tcp));
+}
+
+// Polled by the app, so unsupported platforms answer rather than fail
+#[cfg(not(target_os = "linux"))]
+#[test]
+fn other_platforms_have_no_connections() {
+    assert!(connections(&ConnectionFilter::default()).unwrap().is_empty());
+    assert!(peep_core::listening_ports().unwrap().is_empty());
+}
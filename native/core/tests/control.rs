@@ -0,0 +1,146 @@
+use peep_core::{
+    send_signal, signal_processes, terminate_gracefully, ControlErrorCode, ProcessIdentity, ProcessSignal, SignalScope,
+    TerminationStep, TreeOrder,
+};
+use std::time::Duration;
+
+#[cfg(unix)]
+mod orphan;
+
+// A shell with two sleeping children, once both have started
+#[cfg(unix)]
+const SHELL_WITH_CHILDREN: &str = "sleep 30 & sleep 30 & echo ready; wait";
+
+#[test]
+fn parses_signal_names() {
+    assert_eq!("TERM".parse::<ProcessSignal>(), Ok(ProcessSignal::Term));
+    assert_eq!("sighup".parse::<ProcessSignal>(), Ok(ProcessSignal::Hup));
+    assert_eq!("SIGUSR2".parse::<ProcessSignal>(), Ok(ProcessSignal::Usr2));
+    assert!("SIGNOPE".parse::<ProcessSignal>().is_err());
+
+    assert_eq!(ProcessSignal::Usr1.to_string(), "SIGUSR1");
+    assert_eq!(serde_json::to_value(ProcessSignal::Usr1).unwrap(), "USR1");
+    assert!(ProcessSignal::Kill.is_supported());
+}
+
+#[cfg(unix)]
+#[test]
+fn stops_and_continues_a_process() {
+    let pid = orphan::sleep();
+
+    let stopped = send_signal(pid, ProcessSignal::Stop, false);
+    assert!(stopped.success, "{}", stopped.message);
+    assert_eq!(stopped.message, "Sent SIGSTOP");
+    assert!(send_signal(pid, ProcessSignal::Cont, false).success);
+
+    assert!(send_signal(pid, ProcessSignal::Kill, false).success);
+    orphan::wait_for_exit(pid);
+    // Exited, so there's nothing to signal any more
+    assert_eq!(send_signal(pid, ProcessSignal::Term, false).message, "Process not found");
+}
+
+#[cfg(unix)]
+#[test]
+fn term_ends_a_cooperative_process() {
+    let pid = orphan::sleep();
+
+    let result = terminate_gracefully(pid, Duration::from_secs(5), false);
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.ended_by, Some(TerminationStep::Term));
+    assert!(!orphan::is_running(pid));
+}
+
+#[cfg(unix)]
+#[test]
+fn kill_ends_a_process_ignoring_term() {
+    // Only signalled once the trap is in place
+    let pid = orphan::script("trap '' TERM; echo ready; while :; do sleep 0.1; done");
+
+    let result = terminate_gracefully(pid, Duration::from_millis(300), false);
+    assert!(result.success, "{}", result.message);
+    assert_eq!(result.ended_by, Some(TerminationStep::Kill));
+    assert_eq!(result.message, "Process killed after ignoring SIGTERM for 300 ms");
+    assert!(!orphan::is_running(pid));
+}
+
+#[cfg(unix)]
+#[test]
+fn signals_a_tree_in_either_order() {
+    for order in [TreeOrder::BottomUp, TreeOrder::TopDown] {
+        let shell = orphan::script(SHELL_WITH_CHILDREN);
+
+        let outcomes = signal_processes(shell, ProcessSignal::Kill, SignalScope::Tree(order), false);
+        let names: Vec<&str> = outcomes.iter().map(|o| o.name.as_str()).collect();
+        assert!(outcomes.iter().all(|o| o.success), "{:?}", outcomes);
+        match order {
+            TreeOrder::BottomUp => assert_eq!(names, ["sleep", "sleep", "sh"]),
+            TreeOrder::TopDown => assert_eq!(names, ["sh", "sleep", "sleep"]),
+        }
+        assert_eq!(outcomes.iter().filter(|o| o.pid == shell).count(), 1);
+        orphan::wait_for_exit(shell);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn signals_a_process_group() {
+    let shell = orphan::script(SHELL_WITH_CHILDREN);
+
+    let outcomes = signal_processes(shell, ProcessSignal::Term, SignalScope::Group, false);
+    assert_eq!(outcomes.len(), 3, "{:?}", outcomes);
+    assert!(outcomes.iter().all(|o| o.success && o.message == "Sent SIGTERM"));
+    assert!(outcomes.iter().any(|o| o.pid == shell));
+    orphan::wait_for_exit(shell);
+
+    let missing = signal_processes(shell, ProcessSignal::Term, SignalScope::Group, false);
+    assert_eq!(missing.len(), 1);
+    assert_eq!((missing[0].success, missing[0].message.as_str()), (false, "Process not found"));
+}
+
+#[cfg(unix)]
+#[test]
+fn refuses_a_pid_that_was_reused() {
+    let pid = orphan::sleep();
+    let listed = peep_core::Collector::new()
+        .processes(false)
+        .into_iter()
+        .find(|p| p.pid == pid)
+        .expect("child listed")
+        .identity();
+
+    // As if the listed process had exited and its pid been reused
+    let stale = ProcessIdentity { start_time: listed.start_time.saturating_sub(60), ..listed.clone() };
+    let message = format!("PID {} now belongs to a different process", pid);
+    assert_eq!(send_signal(stale.clone(), ProcessSignal::Kill, false).message, message);
+    assert_eq!(terminate_gracefully(stale.clone(), Duration::from_secs(1), false).message, message);
+    let outcomes = signal_processes(stale, ProcessSignal::Kill, SignalScope::Tree(TreeOrder::BottomUp), false);
+    assert_eq!((outcomes.len(), outcomes[0].success), (1, false));
+    assert_eq!(outcomes[0].code, Some(ControlErrorCode::IdentityMismatch));
+    assert!(orphan::is_running(pid));
+
+    let result = terminate_gracefully(listed, Duration::from_secs(5), false);
+    assert!(result.success, "{}", result.message);
+}
+
+#[cfg(unix)]
+#[test]
+fn failures_carry_a_code_and_errno() {
+    let pid = orphan::sleep();
+    let sent = send_signal(pid, ProcessSignal::Cont, false);
+    assert_eq!((sent.code, sent.errno), (None, None));
+    send_signal(pid, ProcessSignal::Kill, false);
+    orphan::wait_for_exit(pid);
+
+    let missing = send_signal(pid, ProcessSignal::Term, false);
+    assert_eq!(
+        serde_json::to_value(&missing).unwrap(),
+        serde_json::json!({ "success": false, "message": "Process not found", "code": "noSuchProcess", "errno": 3 })
+    );
+    let terminated = terminate_gracefully(pid, Duration::from_secs(1), false);
+    assert_eq!((terminated.code, terminated.errno), (Some(ControlErrorCode::NoSuchProcess), Some(3)));
+
+    // Parents come first top-down, and Peep won't signal itself
+    let outcomes = signal_processes(std::process::id(), ProcessSignal::Cont, SignalScope::Tree(TreeOrder::TopDown), false);
+    assert_eq!(outcomes[0].pid, std::process::id());
+    assert_eq!((outcomes[0].code, outcomes[0].errno), (Some(ControlErrorCode::ProtectedProcess), Some(1)));
+}
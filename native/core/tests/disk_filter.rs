@@ -0,0 +1,107 @@
+use peep_core::{Collector, DiskFilter, DiskReading, FakeSource, Machine, Shadowed};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+fn disk(name: &str, mount_point: &str, file_system: &str, removable: bool) -> DiskReading {
+    DiskReading {
+        name: name.to_string(),
+        mount_point: mount_point.to_string(),
+        file_system: file_system.to_string(),
+        is_removable: removable,
+        total_space: 100 * GB,
+        available_space: 40 * GB,
+        total_read: 1000,
+        total_written: 2000,
+    }
+}
+
+fn linux_disks() -> Vec<DiskReading> {
+    vec![
+        disk("/dev/nvme0n1p2", "/", "ext4", false),
+        disk("/dev/nvme0n1p1", "/boot/efi", "vfat", false),
+        disk("tmpfs", "/tmp", "tmpfs", false),
+        disk("tmpfs", "/run/user/1000", "tmpfs", false),
+        disk("udev", "/dev", "devtmpfs", false),
+        disk("overlay", "/var/lib/docker/overlay2/abc/merged", "overlay", false),
+        disk("/dev/loop3", "/snap/core22/1380", "squashfs", false),
+        // A bind mount of the root device
+        disk("/dev/nvme0n1p2", "/home/alice/shared", "ext4", false),
+        disk("/dev/sdb1", "/run/media/alice/USB", "exfat", true),
+    ]
+}
+
+fn mount_points(filter: &DiskFilter, disks: &[DiskReading]) -> Vec<String> {
+    filter.apply(disks).iter().map(|disk| disk.mount_point.clone()).collect()
+}
+
+#[test]
+fn linux_defaults_hide_virtual_and_duplicate_mounts() {
+    assert_eq!(
+        mount_points(&DiskFilter::linux(), &linux_disks()),
+        ["/", "/boot/efi", "/run/media/alice/USB"]
+    );
+}
+
+#[test]
+fn macos_defaults_list_the_data_volume() {
+    let disks = vec![
+        disk("Macintosh HD", "/", "apfs", false),
+        disk("Macintosh HD", "/System/Volumes/Data", "apfs", false),
+        disk("Preboot", "/System/Volumes/Preboot", "apfs", false),
+        disk("devfs", "/dev", "devfs", false),
+        disk("Installer", "/Volumes/Installer", "hfs", true),
+        disk("Backup", "/Volumes/Backup", "apfs", false),
+    ];
+    assert_eq!(mount_points(&DiskFilter::macos(), &disks), ["/System/Volumes/Data", "/Volumes/Backup"]);
+
+    // Before Catalina there is no data volume, so / is listed
+    assert_eq!(mount_points(&DiskFilter::macos(), &disks[..1]), ["/"]);
+}
+
+#[test]
+fn prefixes_match_whole_path_components() {
+    let filter = DiskFilter {
+        exclude_mount_prefixes: vec!["/dev/".to_string()],
+        ..DiskFilter::none()
+    };
+    let disks = vec![disk("a", "/dev", "ext4", false), disk("b", "/dev/shm", "ext4", false), disk("c", "/devel", "ext4", false)];
+    assert_eq!(mount_points(&filter, &disks), ["/devel"]);
+}
+
+#[test]
+fn include_rules_override_excludes_but_not_shadows() {
+    let filter = DiskFilter {
+        include_file_systems: vec!["TMPFS".to_string()],
+        shadowed: vec![Shadowed { mount_point: "/tmp".to_string(), shadowed_by: "/".to_string() }],
+        ..DiskFilter::linux()
+    };
+    // Both tmpfs mounts are let through, but /tmp is shadowed by /
+    assert_eq!(
+        mount_points(&filter, &linux_disks()),
+        ["/", "/boot/efi", "/run/user/1000", "/run/media/alice/USB"]
+    );
+    assert_eq!(mount_points(&DiskFilter::none(), &linux_disks()).len(), linux_disks().len());
+}
+
+#[test]
+fn overrides_fill_in_missing_fields_from_the_defaults() {
+    let filter: DiskFilter = serde_json::from_str(r#"{ "excludeRemovable": true }"#).unwrap();
+    assert!(filter.exclude_removable);
+    assert_eq!(filter.exclude_file_systems, DiskFilter::default().exclude_file_systems);
+}
+
+#[test]
+fn collectors_count_each_device_once() {
+    let source = FakeSource::new(Machine { disks: linux_disks(), ..Machine::default() });
+    let mut deduplicated = Collector::with_source(source.clone()).with_disk_filter(DiskFilter::linux());
+    let mut everything = Collector::with_source(source).with_disk_filter(DiskFilter::none());
+
+    let disk = deduplicated.disks();
+    // The bind mount's device is only counted once
+    assert_eq!(disk.total_read, 8 * 1000);
+    assert_eq!(disk.disks.len(), 3);
+
+    let disk = everything.disks();
+    assert_eq!(disk.total_read, 9 * 1000);
+    assert_eq!(disk.disks.len(), 9);
+}
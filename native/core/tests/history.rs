@@ -0,0 +1,95 @@
+use peep_core::{History, HistoryPoint, Metric, Resolution, DEFAULT_RESOLUTIONS};
+
+const SECOND: u64 = 1000;
+const MINUTE: u64 = 60 * SECOND;
+// A fixed point safely in the past, aligned to every default resolution
+const T0: u64 = 1_700_000_040_000;
+
+fn point(timestamp: u64, min: f64, avg: f64, max: f64) -> HistoryPoint {
+    HistoryPoint { timestamp, min, avg, max }
+}
+
+#[test]
+fn values_are_bucketed_into_every_resolution() {
+    let mut history = History::default();
+    for (offset, value) in [(0, 10.0), (500, 30.0), (1500, 20.0), (12 * SECOND, 50.0), (61 * SECOND, 70.0)] {
+        history.record_value(Metric::Cpu, T0 + offset, value);
+    }
+    let query = |step| history.query(Metric::Cpu, T0, T0 + 2 * MINUTE, Some(step)).unwrap();
+
+    assert_eq!(query(1), vec![
+        point(T0, 10.0, 20.0, 30.0),
+        point(T0 + SECOND, 20.0, 20.0, 20.0),
+        point(T0 + 12 * SECOND, 50.0, 50.0, 50.0),
+        point(T0 + MINUTE + SECOND, 70.0, 70.0, 70.0),
+    ]);
+    assert_eq!(query(10), vec![
+        point(T0, 10.0, 20.0, 30.0),
+        point(T0 + 10 * SECOND, 50.0, 50.0, 50.0),
+        point(T0 + MINUTE, 70.0, 70.0, 70.0),
+    ]);
+    assert_eq!(query(60), vec![
+        point(T0, 10.0, 27.5, 50.0),
+        point(T0 + MINUTE, 70.0, 70.0, 70.0),
+    ]);
+    assert!(history.query(Metric::Cpu, T0, T0 + MINUTE, Some(5)).is_err());
+    assert_eq!(history.query(Metric::Memory, T0, T0 + MINUTE, Some(1)).unwrap(), vec![]);
+}
+
+#[test]
+fn old_buckets_are_evicted() {
+    let mut history = History::new(&[
+        Resolution { step_secs: 1, capacity: 3 },
+        Resolution { step_secs: 10, capacity: 3 },
+    ]);
+    for second in 0..40 {
+        history.record_value(Metric::Cpu, T0 + second * SECOND, second as f64);
+    }
+    let timestamps = |step| -> Vec<u64> {
+        history.query(Metric::Cpu, 0, T0 + MINUTE, Some(step)).unwrap()
+            .iter()
+            .map(|p| (p.timestamp - T0) / SECOND)
+            .collect()
+    };
+
+    // Each ring keeps its newest bucket plus whatever else fits in its span
+    assert_eq!(timestamps(1), vec![37, 38, 39]);
+    assert_eq!(timestamps(10), vec![10, 20, 30]);
+}
+
+#[test]
+fn resolution_is_picked_from_the_requested_range() {
+    let mut history = History::default();
+    history.record_value(Metric::Cpu, T0, 1.0);
+    let auto = |from, to| history.query(Metric::Cpu, from, to, None).unwrap();
+
+    assert_eq!(auto(T0, T0 + 5 * MINUTE), history.query(Metric::Cpu, T0, T0 + 5 * MINUTE, Some(1)).unwrap());
+    // A range longer than the 1s ring's ten minutes falls back to 10s buckets,
+    // even though the newest sample is recent enough for the finer ring
+    let hour = auto(T0, T0 + 60 * MINUTE);
+    assert_eq!(hour, history.query(Metric::Cpu, T0, T0 + 60 * MINUTE, Some(10)).unwrap());
+    assert_eq!(auto(T0 - 24 * 60 * MINUTE, T0 + MINUTE), history.query(Metric::Cpu, 0, T0 + MINUTE, Some(60)).unwrap());
+
+    // Ranges running into the future are measured up to now
+    let now = peep_core::now_millis();
+    let mut live = History::default();
+    live.record_value(Metric::Cpu, now - now % 60_000, 1.0);
+    let points = live.query(Metric::Cpu, now - MINUTE, u64::MAX, None).unwrap();
+    assert_eq!(points, live.query(Metric::Cpu, now - MINUTE, u64::MAX, Some(DEFAULT_RESOLUTIONS[0].step_secs)).unwrap());
+}
+
+#[test]
+fn late_samples_are_merged_in_order() {
+    let mut history = History::new(&[Resolution { step_secs: 1, capacity: 10 }]);
+    history.record_value(Metric::Cpu, T0 + 5 * SECOND, 50.0);
+    history.record_value(Metric::Cpu, T0 + 2 * SECOND, 20.0);
+    history.record_value(Metric::Cpu, T0 + 5 * SECOND + 300, 60.0);
+    history.record_value(Metric::Cpu, T0 + 2 * SECOND + 500, 40.0);
+    // Older than the ring reaches, so dropped rather than evicting newer data
+    history.record_value(Metric::Cpu, T0 - 20 * SECOND, 99.0);
+
+    assert_eq!(history.query(Metric::Cpu, 0, T0 + MINUTE, Some(1)).unwrap(), vec![
+        point(T0 + 2 * SECOND, 20.0, 30.0, 40.0),
+        point(T0 + 5 * SECOND, 50.0, 55.0, 60.0),
+    ]);
+}
@@ -0,0 +1,151 @@
+use peep_core::{
+    classify_interface, classify_interface_name, classify_interface_sysfs, get_interface_type, interface_link_info_sysfs,
+    is_physical_interface, Collector, Duplex, FakeSource, InterfaceAddress, InterfaceClass, InterfaceKind, InterfaceReading,
+    LinkInfo, LinkState, Machine,
+};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+// Lay out /sys/class/net/<name> with the given files; a `device` entry
+// stands in for the link to the bus device
+fn interface(root: &Path, name: &str, files: &[(&str, &str)]) {
+    let dir = root.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    for (file, contents) in files {
+        if contents.is_empty() {
+            fs::create_dir_all(dir.join(file)).unwrap();
+        } else {
+            fs::write(dir.join(file), contents).unwrap();
+        }
+    }
+}
+
+fn class(kind: InterfaceKind, physical: bool) -> Option<InterfaceClass> {
+    Some(InterfaceClass { kind, physical })
+}
+
+#[test]
+fn classifies_from_sysfs() {
+    let root = tempfile::tempdir().unwrap();
+    let root = root.path();
+    interface(root, "lo", &[("type", "772\n"), ("ifindex", "1\n"), ("iflink", "1\n")]);
+    interface(root, "enp3s0", &[("type", "1\n"), ("device", ""), ("ifindex", "2\n"), ("iflink", "2\n")]);
+    interface(root, "wlp2s0", &[("type", "1\n"), ("device", ""), ("wireless", ""), ("uevent", "DEVTYPE=wlan\nINTERFACE=wlp2s0\n")]);
+    interface(root, "docker0", &[("type", "1\n"), ("bridge", ""), ("uevent", "DEVTYPE=bridge\n")]);
+    interface(root, "vethab12cd", &[("type", "1\n"), ("ifindex", "7\n"), ("iflink", "6\n")]);
+    interface(root, "wg0", &[("type", "65534\n"), ("uevent", "DEVTYPE=wireguard\nINTERFACE=wg0\n")]);
+    interface(root, "tun0", &[("type", "65534\n"), ("tun_flags", "0x1001\n")]);
+    interface(root, "tap0", &[("type", "1\n"), ("tun_flags", "0x1002\n")]);
+    interface(root, "enp3s0.100", &[("type", "1\n"), ("uevent", "DEVTYPE=vlan\n"), ("ifindex", "9\n"), ("iflink", "2\n")]);
+    interface(root, "bond0", &[("type", "1\n"), ("bonding", "")]);
+    interface(root, "sit0", &[("type", "776\n")]);
+    interface(root, "dummy0", &[("type", "1\n"), ("ifindex", "4\n"), ("iflink", "4\n")]);
+
+    assert_eq!(classify_interface_sysfs(root, "lo"), class(InterfaceKind::Loopback, false));
+    assert_eq!(classify_interface_sysfs(root, "enp3s0"), class(InterfaceKind::Ethernet, true));
+    assert_eq!(classify_interface_sysfs(root, "wlp2s0"), class(InterfaceKind::Wireless, true));
+    assert_eq!(classify_interface_sysfs(root, "docker0"), class(InterfaceKind::Bridge, false));
+    assert_eq!(classify_interface_sysfs(root, "vethab12cd"), class(InterfaceKind::Veth, false));
+    assert_eq!(classify_interface_sysfs(root, "wg0"), class(InterfaceKind::Wireguard, false));
+    assert_eq!(classify_interface_sysfs(root, "tun0"), class(InterfaceKind::Tunnel, false));
+    assert_eq!(classify_interface_sysfs(root, "tap0"), class(InterfaceKind::Tap, false));
+    assert_eq!(classify_interface_sysfs(root, "enp3s0.100"), class(InterfaceKind::Vlan, false));
+    assert_eq!(classify_interface_sysfs(root, "bond0"), class(InterfaceKind::Bond, false));
+    assert_eq!(classify_interface_sysfs(root, "sit0"), class(InterfaceKind::Tunnel, false));
+    // Nothing in sysfs says what it is, so the name decides
+    assert_eq!(classify_interface_sysfs(root, "dummy0"), class(InterfaceKind::Other, false));
+    assert_eq!(classify_interface_sysfs(root, "eth9"), None);
+}
+
+#[test]
+fn falls_back_to_names() {
+    let kind = |name| classify_interface_name(name).kind;
+    assert_eq!(kind("lo0"), InterfaceKind::Loopback);
+    assert_eq!(kind("eth0"), InterfaceKind::Ethernet);
+    assert_eq!(kind("eth0.100"), InterfaceKind::Vlan);
+    assert_eq!(kind("wlan0"), InterfaceKind::Wireless);
+    assert_eq!(kind("utun3"), InterfaceKind::Tunnel);
+    assert_eq!(kind("awdl0"), InterfaceKind::Awdl);
+    assert_eq!(InterfaceKind::Awdl.label(), "Apple Wireless Direct Link");
+    assert_eq!(kind("p2p0"), InterfaceKind::PeerToPeer);
+    assert_eq!(kind("ap1"), InterfaceKind::Wireless);
+    assert_eq!(kind("br-1a2b3c"), InterfaceKind::Bridge);
+    assert_eq!(kind("wg0"), InterfaceKind::Wireguard);
+    assert_eq!(kind("veth1"), InterfaceKind::Veth);
+
+    assert!(classify_interface_name("eth0").physical);
+    assert!(classify_interface_name("wlan0").physical);
+    assert!(!classify_interface_name("docker0").physical);
+    assert!(!classify_interface_name("utun3").physical);
+    assert!(!classify_interface_name("awdl0").physical);
+    // Personal Hotspot's access point rides on the real Wi-Fi adapter
+    assert!(!classify_interface_name("ap1").physical);
+}
+
+#[test]
+fn name_helpers_agree_with_the_snapshot_classification() {
+    let mut names = vec!["lo".to_string(), "wg0".to_string(), "eth0".to_string()];
+    if let Ok(entries) = fs::read_dir("/sys/class/net") {
+        names.extend(entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()));
+    }
+    for name in &names {
+        let class = classify_interface(name);
+        assert_eq!(is_physical_interface(name), class.physical, "{}", name);
+        assert_eq!(get_interface_type(name), class.kind.label(), "{}", name);
+    }
+}
+
+#[test]
+fn reads_link_details_from_sysfs() {
+    let root = tempfile::tempdir().unwrap();
+    let root = root.path();
+    interface(root, "enp3s0", &[("operstate", "up\n"), ("speed", "1000\n"), ("duplex", "full\n")]);
+    // Unplugged: speed and duplex can't be read
+    interface(root, "enp4s0", &[("operstate", "down\n")]);
+    interface(root, "wg0", &[("operstate", "unknown\n"), ("speed", "-1\n")]);
+
+    assert_eq!(
+        interface_link_info_sysfs(root, "enp3s0"),
+        LinkInfo { state: Some(LinkState::Up), speed_mbps: Some(1000), duplex: Some(Duplex::Full) }
+    );
+    assert_eq!(
+        interface_link_info_sysfs(root, "enp4s0"),
+        LinkInfo { state: Some(LinkState::Down), ..LinkInfo::default() }
+    );
+    assert_eq!(interface_link_info_sysfs(root, "wg0"), LinkInfo::default());
+    assert_eq!(interface_link_info_sysfs(root, "missing0"), LinkInfo::default());
+}
+
+#[test]
+fn interface_details_reach_the_snapshot() {
+    let wired = InterfaceReading {
+        name: "enp3s0".to_string(),
+        kind: InterfaceKind::Ethernet,
+        physical: true,
+        mac_address: Some("3c:7c:3f:1e:22:9a".to_string()),
+        addresses: vec![
+            InterfaceAddress { address: "192.168.1.20".parse().unwrap(), prefix: 24 },
+            InterfaceAddress { address: "fe80::3e7c:3fff:fe1e:229a".parse().unwrap(), prefix: 64 },
+        ],
+        mtu: Some(1500),
+        link: LinkInfo { state: Some(LinkState::Up), speed_mbps: Some(2500), duplex: Some(Duplex::Full) },
+        ..InterfaceReading::default()
+    };
+    let source = FakeSource::new(Machine { networks: vec![wired], ..Machine::default() });
+    let mut collector = Collector::with_source(source);
+
+    let interface = serde_json::to_value(&collector.network().interfaces[0]).unwrap();
+    assert_eq!(interface["macAddress"], "3c:7c:3f:1e:22:9a");
+    assert_eq!(
+        interface["addresses"],
+        json!([
+            { "address": "192.168.1.20", "prefix": 24 },
+            { "address": "fe80::3e7c:3fff:fe1e:229a", "prefix": 64 },
+        ])
+    );
+    assert_eq!(interface["mtu"], 1500);
+    assert_eq!(interface["state"], "up");
+    assert_eq!(interface["speedMbps"], 2500);
+    assert_eq!(interface["duplex"], "full");
+}
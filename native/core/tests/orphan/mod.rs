@@ -0,0 +1,61 @@
+use std::io::{BufRead, BufReader};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System};
+
+// Processes for the signal tests to act on. The test process stands in for
+// Peep, which won't signal what it started, so these are started by a shell
+// that exits straight away and leaves them to init. Each gets a process
+// group of its own, the shell's.
+
+// Run `script` in the background and return its pid once it has printed a
+// line; its output goes to the shell's stderr so the pid can go to stdout
+pub fn script(script: &str) -> u32 {
+    let mut shell = Command::new("sh")
+        .args(["-c", "sh -c \"$1\" >&2 & echo $!", "sh", script])
+        .process_group(0)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut pid = String::new();
+    BufReader::new(shell.stdout.take().unwrap()).read_line(&mut pid).unwrap();
+    let mut ready = String::new();
+    BufReader::new(shell.stderr.take().unwrap()).read_line(&mut ready).unwrap();
+    shell.wait().unwrap();
+    pid.trim().parse().unwrap()
+}
+
+// A `sleep 30`, once the shell running it has become sleep
+pub fn sleep() -> u32 {
+    let pid = script("echo ready; exec sleep 30");
+    wait_until(|| lookup(pid, |process| process.name() == "sleep"));
+    pid
+}
+
+// Zombies count as gone: nothing may be waiting to collect them
+pub fn is_running(pid: u32) -> bool {
+    lookup(pid, |process| !matches!(process.status(), ProcessStatus::Zombie | ProcessStatus::Dead))
+}
+
+pub fn wait_for_exit(pid: u32) {
+    wait_until(|| !is_running(pid));
+}
+
+// A fresh look every time: sysinfo keeps the name it first saw
+fn lookup(pid: u32, check: impl FnOnce(&Process) -> bool) -> bool {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::Some(&[pid]), true, ProcessRefreshKind::nothing());
+    system.process(pid).is_some_and(check)
+}
+
+fn wait_until(mut done: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !done() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(20));
+    }
+}
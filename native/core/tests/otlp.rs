@@ -0,0 +1,225 @@
+use peep_core::{
+    Collector, FakeSource, InterfaceKind, InterfaceReading, Machine, OtlpExporter, OtlpOptions, ProcessReading,
+};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+struct Request {
+    request_line: String,
+    headers: Vec<(String, String)>,
+    body: Value,
+}
+
+// Stand-in collector: accepts one request per entry in `statuses`, answers
+// each with that status and hands the parsed request back
+fn collector(statuses: Vec<&'static str>) -> (String, mpsc::Receiver<Request>) {
+    collector_on(TcpListener::bind("127.0.0.1:0").unwrap(), statuses)
+}
+
+fn collector_on(listener: TcpListener, statuses: Vec<&'static str>) -> (String, mpsc::Receiver<Request>) {
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for status in statuses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(": ") {
+                    Some((name, value)) => headers.push((name.to_lowercase(), value.to_string())),
+                    None => break,
+                }
+            }
+            let length: usize = headers.iter().find(|(name, _)| name == "content-length").unwrap().1.parse().unwrap();
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response_body = if status.starts_with('2') { "{}" } else { "bad payload" };
+            // One write: the exporter hangs up as soon as it has the status line
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                response_body.len(),
+                response_body
+            );
+            let _ = (&stream).write_all(response.as_bytes());
+
+            let _ = sender.send(Request {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: serde_json::from_slice(&body).unwrap(),
+            });
+        }
+    });
+
+    (endpoint, receiver)
+}
+
+fn header<'a>(request: &'a Request, name: &str) -> Option<&'a str> {
+    request.headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+fn metric<'a>(body: &'a Value, name: &str) -> Option<&'a Value> {
+    body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == name)
+}
+
+#[test]
+fn export_posts_semantic_convention_metrics() {
+    let (endpoint, requests) = collector(vec!["200 OK"]);
+    let options = OtlpOptions {
+        endpoint,
+        headers: vec![("X-Api-Key".to_string(), "secret".to_string())],
+        ..OtlpOptions::default()
+    };
+
+    peep_core::export_otlp(&mut Collector::new(), &options).unwrap();
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(request.request_line, "POST /v1/metrics HTTP/1.1");
+    assert_eq!(header(&request, "content-type"), Some("application/json"));
+    assert_eq!(header(&request, "x-api-key"), Some("secret"));
+
+    let resource = &request.body["resourceMetrics"][0]["resource"]["attributes"];
+    assert!(resource
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|a| a["key"] == "service.name" && a["value"]["stringValue"] == "peep"));
+
+    let cpu = metric(&request.body, "system.cpu.utilization").expect("system.cpu.utilization");
+    assert_eq!(cpu["unit"], "1");
+    assert!(!cpu["gauge"]["dataPoints"].as_array().unwrap().is_empty());
+
+    let network = metric(&request.body, "system.network.io").expect("system.network.io");
+    assert_eq!(network["sum"]["isMonotonic"], true);
+    assert_eq!(network["sum"]["aggregationTemporality"], 2);
+
+    let memory = metric(&request.body, "process.memory.usage").expect("process.memory.usage");
+    let points = memory["sum"]["dataPoints"].as_array().unwrap();
+    assert!(!points.is_empty());
+    // 64-bit integers travel as strings
+    assert!(points[0]["asInt"].is_string());
+
+    for name in ["system.memory.usage", "system.disk.io", "system.filesystem.usage", "process.cpu.utilization"] {
+        assert!(metric(&request.body, name).is_some(), "missing {}", name);
+    }
+}
+
+#[test]
+fn export_uses_endpoint_path() {
+    let (endpoint, requests) = collector(vec!["202 Accepted"]);
+    let options = OtlpOptions { endpoint: format!("{}/otlp/v1/metrics", endpoint), ..OtlpOptions::default() };
+
+    peep_core::export_otlp(&mut Collector::new(), &options).unwrap();
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(request.request_line, "POST /otlp/v1/metrics HTTP/1.1");
+}
+
+#[test]
+fn export_reports_rejected_requests() {
+    let (endpoint, _requests) = collector(vec!["400 Bad Request"]);
+    let options = OtlpOptions { endpoint, ..OtlpOptions::default() };
+
+    let err = peep_core::export_otlp(&mut Collector::new(), &options).unwrap_err();
+    let message = err.to_string();
+    assert!(message.contains("400"), "{}", message);
+    assert!(message.contains("bad payload"), "{}", message);
+}
+
+#[test]
+fn exporter_pushes_on_interval_until_stopped() {
+    let (endpoint, requests) = collector(vec!["200 OK", "200 OK"]);
+    let options = OtlpOptions { endpoint, interval: Duration::from_millis(200), ..OtlpOptions::default() };
+
+    let exporter = OtlpExporter::start(options).unwrap();
+    for _ in 0..2 {
+        let request = requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(metric(&request.body, "system.cpu.utilization").is_some());
+    }
+    exporter.stop();
+}
+
+#[test]
+fn exporter_rejects_unsupported_endpoints() {
+    for endpoint in ["https://collector.example.com", "grpc://localhost:4317", "http://:4318", "http://localhost:port"] {
+        let options = OtlpOptions { endpoint: endpoint.to_string(), ..OtlpOptions::default() };
+        assert!(OtlpExporter::start(options).is_err(), "{}", endpoint);
+    }
+}
+
+#[test]
+fn ipv6_hosts_keep_their_brackets() {
+    // Not every sandbox has IPv6 loopback
+    let Ok(listener) = TcpListener::bind("[::1]:0") else {
+        return;
+    };
+    let port = listener.local_addr().unwrap().port();
+    let (endpoint, requests) = collector_on(listener, vec!["200 OK"]);
+    assert_eq!(endpoint, format!("http://[::1]:{}", port));
+
+    peep_core::export_otlp(&mut Collector::new(), &OtlpOptions { endpoint, ..OtlpOptions::default() }).unwrap();
+    let request = requests.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(header(&request, "host"), Some(format!("[::1]:{}", port).as_str()));
+}
+
+#[test]
+fn header_injection_is_rejected() {
+    let (endpoint, requests) = collector(vec!["200 OK"]);
+    for headers in [
+        vec![("X-Api-Key".to_string(), "secret\r\nX-Injected: 1".to_string())],
+        vec![("X-Api-Key".to_string(), "secret\n".to_string())],
+        vec![("X-Api-Key\r\nX-Injected".to_string(), "1".to_string())],
+        vec![("X Api Key".to_string(), "1".to_string())],
+        vec![(String::new(), "1".to_string())],
+    ] {
+        let options = OtlpOptions { endpoint: endpoint.clone(), headers, ..OtlpOptions::default() };
+        let err = peep_core::export_otlp(&mut Collector::new(), &options).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(OtlpExporter::start(options).is_err());
+    }
+    assert!(requests.recv_timeout(Duration::from_millis(200)).is_err());
+}
+
+#[test]
+fn process_cpu_time_is_cumulative_since_start() {
+    let source = FakeSource::new(Machine {
+        networks: vec![InterfaceReading { name: "tun0".to_string(), kind: InterfaceKind::Tunnel, ..InterfaceReading::default() }],
+        processes: vec![ProcessReading {
+            pid: 7,
+            name: "worker".to_string(),
+            run_time: 600,
+            cpu_time: 42,
+            start_time: 1_700_000_000,
+            ..ProcessReading::default()
+        }],
+        ..Machine::default()
+    });
+    let mut collector = Collector::with_source(source);
+    let (system, battery, processes) = (collector.system(), collector.battery(), collector.processes(false));
+    let request = peep_core::otlp_metrics_request(&system, &battery, &processes, 1_700_000_600_000_000_000);
+
+    let cpu_time = metric(&request, "process.cpu.time").expect("process.cpu.time");
+    assert_eq!(cpu_time["unit"], "s");
+    let point = &cpu_time["sum"]["dataPoints"][0];
+    assert_eq!(point["asDouble"], 42.0);
+    assert_eq!(point["startTimeUnixNano"], "1700000000000000000");
+
+    // Virtual interfaces are exported alongside the physical ones
+    let network = metric(&request, "system.network.io").expect("system.network.io");
+    assert!(network["sum"]["dataPoints"].as_array().unwrap().iter()
+        .any(|point| point["attributes"][0]["value"]["stringValue"] == "tun0"));
+}
@@ -0,0 +1,45 @@
+use peep_core::{parse_plan, DiskDetail, ProcessDetail, RefreshPlan};
+use serde_json::json;
+
+#[test]
+fn omitted_subsystems_are_skipped() {
+    assert_eq!(parse_plan(&json!({})).unwrap(), Some(RefreshPlan::default()));
+    assert_eq!(parse_plan(&json!({ "cpu": true, "network": null })).unwrap(), Some(RefreshPlan::cpu()));
+    assert_eq!(
+        parse_plan(&json!({ "cpu": true, "memory": true, "disks": true, "network": true })).unwrap(),
+        Some(RefreshPlan::system()),
+    );
+    // No object means no plan: the caller collects everything
+    assert_eq!(parse_plan(&json!(null)).unwrap(), None);
+    assert_eq!(parse_plan(&json!(true)).unwrap(), None);
+}
+
+#[test]
+fn disk_detail_is_a_flag_or_a_level() {
+    let disks = |value| parse_plan(&json!({ "disks": value })).map(|plan| plan.unwrap().disks);
+    assert_eq!(disks(json!(true)), Ok(Some(DiskDetail::Volumes)));
+    assert_eq!(disks(json!(false)), Ok(None));
+    assert_eq!(disks(json!("io")), Ok(Some(DiskDetail::Io)));
+    assert_eq!(disks(json!("volumes")), Ok(Some(DiskDetail::Volumes)));
+    assert_eq!(disks(json!("smart")), Err("Unknown disk detail: smart".to_string()));
+    assert!(disks(json!(1)).is_err());
+}
+
+#[test]
+fn process_detail_fills_in_defaults() {
+    let processes = |value| parse_plan(&json!({ "processes": value })).map(|plan| plan.unwrap().processes);
+    assert_eq!(processes(json!(true)), Ok(Some(ProcessDetail::default())));
+    assert!(!ProcessDetail::default().network);
+    assert_eq!(
+        processes(json!({ "network": true })),
+        Ok(Some(ProcessDetail { network: true, ..ProcessDetail::default() })),
+    );
+    assert_eq!(processes(json!(false)), Ok(None));
+    assert_eq!(
+        processes(json!({ "threads": true, "user": false })),
+        Ok(Some(ProcessDetail { threads: true, user: false, ..ProcessDetail::default() })),
+    );
+    assert_eq!(processes(json!({ "command": "yes" })), Err("command must be a boolean".to_string()));
+    assert!(processes(json!("all")).is_err());
+    assert_eq!(parse_plan(&json!({ "memory": 1 })), Err("memory must be a boolean".to_string()));
+}
@@ -0,0 +1,51 @@
+use peep_core::{find_process_by_port, listeners, parse_connection_table, Collector, Protocol};
+
+#[cfg(target_endian = "little")]
+#[test]
+fn picks_listening_sockets() {
+    // A listener, an established connection and a connected UDP socket
+    let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 41325 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000  1000        0 41326 1 0000000000000000 20 4 30 10 -1
+";
+    let udp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+ 100: 00000000:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 20786 2 0000000000000000 0
+ 101: 0201A8C0:C350 08080808:0035 01 00000000:00000000 00:00000000 00000000  1000        0 20787 2 0000000000000000 0
+";
+    let mut connections = parse_connection_table(tcp, Protocol::Tcp);
+    connections.extend(parse_connection_table(udp, Protocol::Udp));
+
+    let found = listeners(&connections);
+    let ports: Vec<(Protocol, u16)> = found.iter().map(|l| (l.protocol, l.port)).collect();
+    assert_eq!(ports, vec![(Protocol::Udp, 53), (Protocol::Tcp, 8080)]);
+    assert_eq!(found[1].address.to_string(), "0.0.0.0");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn finds_this_process_by_port() {
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let udp = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let listener = find_process_by_port(tcp.local_addr().unwrap().port(), Protocol::Tcp).unwrap().expect("tcp listener");
+    assert_eq!(listener.pid, Some(std::process::id()));
+    assert_eq!(listener.protocol, Protocol::Tcp);
+    assert!(!listener.command.is_empty());
+    assert!(listener.user.is_some());
+
+    let socket = find_process_by_port(udp.local_addr().unwrap().port(), Protocol::Udp).unwrap().expect("udp socket");
+    assert_eq!(socket.pid, Some(std::process::id()));
+    assert_eq!(socket.protocol, Protocol::Udp);
+
+    // The TCP port isn't bound over UDP
+    assert_eq!(find_process_by_port(tcp.local_addr().unwrap().port(), Protocol::Udp).unwrap(), None);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn killing_an_unused_port_fails() {
+    let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let result = Collector::new().kill_port(port, Protocol::Tcp, false);
+    assert!(!result.success);
+    assert_eq!(result.message, format!("Nothing is listening on TCP port {}", port));
+}
@@ -0,0 +1,85 @@
+use peep_core::{Collector, FakeSource, Machine, ProcessDetail, ProcessReading, RefreshPlan};
+use std::time::Duration;
+
+fn process(pid: u32, received: Option<u64>, transmitted: Option<u64>) -> ProcessReading {
+    ProcessReading {
+        pid,
+        name: format!("proc{}", pid),
+        total_net_received: received,
+        total_net_transmitted: transmitted,
+        ..ProcessReading::default()
+    }
+}
+
+#[test]
+fn traffic_is_reported_as_rates() {
+    let machine = |step: u64| Machine {
+        processes: vec![
+            process(10, Some(1000 + 4000 * step), Some(500 + 1000 * step)),
+            // The source can't attribute traffic to this one
+            process(11, None, None),
+        ],
+        ..Machine::default()
+    };
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+    collector.processes(false);
+    source.advance(Duration::from_secs(2), machine(1));
+
+    let processes = collector.processes(false);
+    assert_eq!(processes[0].net_rx, Some(2000.0));
+    assert_eq!(processes[0].net_tx, Some(500.0));
+    assert_eq!(processes[1].net_rx, None);
+
+    let record = serde_json::to_value(&processes[1]).unwrap();
+    assert!(record.get("netRx").is_none() && record.get("netTx").is_none());
+}
+
+#[test]
+fn a_refresh_without_traffic_is_not_a_baseline() {
+    let machine = |step: u64| Machine { processes: vec![process(10, Some(1_000_000 + 4000 * step), Some(0))], ..Machine::default() };
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+    // e.g. `getSystemInfo({ processes: true })`, which leaves traffic off
+    collector.collect(&RefreshPlan::processes(ProcessDetail::default()));
+    source.advance(Duration::from_secs(2), machine(1));
+
+    // Nothing to measure against yet, rather than the whole total at once
+    let processes = collector.processes(false);
+    assert_eq!(processes[0].net_rx, Some(0.0));
+    source.advance(Duration::from_secs(2), machine(2));
+    assert_eq!(collector.processes(false)[0].net_rx, Some(2000.0));
+}
+
+// Both ends of a loopback connection belong to this process, so everything
+// sent through it shows up as received and as sent
+#[cfg(target_os = "linux")]
+#[test]
+fn tcp_traffic_is_attributed_to_this_process() {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    const PAYLOAD: usize = 1024 * 1024;
+
+    let mut collector = Collector::new();
+    collector.processes(false);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut received = vec![0u8; PAYLOAD];
+        server.read_exact(&mut received).unwrap();
+        server
+    });
+    client.write_all(&vec![7u8; PAYLOAD]).unwrap();
+    // Keep both sockets open so their counters are still there to read
+    let _server = reader.join().unwrap();
+
+    std::thread::sleep(Duration::from_millis(100));
+    let processes = collector.processes(false);
+    let this = processes.iter().find(|p| p.pid == std::process::id()).unwrap();
+    let rx = this.net_rx.expect("no traffic attributed");
+    let tx = this.net_tx.expect("no traffic attributed");
+    assert!(rx > 0.0 && tx > 0.0, "rx {} tx {}", rx, tx);
+}
@@ -0,0 +1,111 @@
+use peep_core::{process_tree, ProcessNode, ProcessRecord, SubtreeTotal};
+
+const MB: u64 = 1024 * 1024;
+
+fn record(pid: u32, ppid: u32, run_time: u64, memory: u64) -> ProcessRecord {
+    ProcessRecord {
+        pid,
+        name: format!("proc{}", pid),
+        ppid,
+        is_thread: false,
+        cpu: 1.5,
+        memory,
+        run_time,
+        cpu_time: 0,
+        status: "Running".to_string(),
+        user: "alice".to_string(),
+        command: String::new(),
+        disk_read: 10,
+        disk_write: 5,
+        net_rx: None,
+        net_tx: None,
+        start_time: 0,
+        exe: None,
+    }
+}
+
+// (pid, children) at every level, for comparing shapes
+fn shape(nodes: &[ProcessNode]) -> Vec<(u32, Vec<u32>)> {
+    let mut out = Vec::new();
+    for node in nodes {
+        out.push((node.process.pid, node.children.iter().map(|c| c.process.pid).collect()));
+        out.extend(shape(&node.children));
+    }
+    out
+}
+
+#[test]
+fn nests_processes_and_sums_subtrees() {
+    let mut thread = record(201, 200, 100, 400 * MB);
+    thread.is_thread = true;
+    let processes = vec![
+        record(202, 200, 50, 2000 * MB),
+        record(1, 0, 9000, 10 * MB),
+        record(100, 1, 500, 5 * MB),
+        record(200, 100, 100, 1000 * MB),
+        thread,
+        record(2, 0, 9000, 0),
+        record(3, 2, 9000, 0),
+    ];
+
+    let tree = process_tree(&processes);
+    assert_eq!(shape(&tree), vec![
+        (1, vec![100]),
+        (100, vec![200]),
+        (200, vec![202]),
+        (202, vec![]),
+        (2, vec![3]),
+        (3, vec![]),
+    ]);
+
+    assert_eq!(tree[0].total, SubtreeTotal {
+        processes: 4,
+        cpu: 6.0,
+        memory: 3015 * MB,
+        disk_read: 40,
+        disk_write: 20,
+    });
+    let build = &tree[0].children[0].children[0];
+    assert_eq!((build.total.processes, build.total.memory), (2, 3000 * MB));
+}
+
+#[test]
+fn roots_orphans_reused_parents_and_loops() {
+    let processes = vec![
+        record(1, 0, 9000, MB),
+        // Parent exited and isn't listed
+        record(50, 49, 100, MB),
+        // Pid 60 was reused by a process younger than its "child"
+        record(60, 1, 10, MB),
+        record(61, 60, 100, MB),
+        // Its own parent
+        record(70, 70, 100, MB),
+        // Parents of each other, reachable from no root
+        record(81, 80, 100, MB),
+        record(80, 81, 100, MB),
+    ];
+
+    let tree = process_tree(&processes);
+    assert_eq!(shape(&tree), vec![
+        (1, vec![60]),
+        (60, vec![]),
+        (50, vec![]),
+        (61, vec![]),
+        (70, vec![]),
+        (80, vec![81]),
+        (81, vec![]),
+    ]);
+    let placed: u32 = tree.iter().map(|n| n.total.processes).sum();
+    assert_eq!(placed as usize, processes.len());
+}
+
+#[test]
+fn serializes_nodes_flat() {
+    let tree = process_tree(&[record(1, 0, 9000, MB), record(2, 1, 100, MB)]);
+    let json = serde_json::to_value(&tree[0]).unwrap();
+    assert_eq!(json["pid"], 1);
+    assert_eq!(json["children"][0]["pid"], 2);
+    assert_eq!(json["children"][0]["children"], serde_json::json!([]));
+    assert_eq!(json["total"]["processes"], 2);
+    assert_eq!(json["total"]["memory"], 2 * MB);
+}
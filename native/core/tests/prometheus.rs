@@ -0,0 +1,145 @@
+use peep_core::{
+    render_prometheus, BatteryReading, Collector, CpuReading, DiskReading, Exporter, ExporterOptions, FakeSource,
+    InterfaceKind, InterfaceReading, Machine, MemoryReading, ProcessReading,
+};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+fn machine() -> Machine {
+    Machine {
+        cpu: CpuReading { brand: None, usage: 50.0, per_core: vec![25.0, 75.0] },
+        memory: MemoryReading { total: 1000, used: 250, free: 750, ..MemoryReading::default() },
+        disks: vec![DiskReading {
+            name: "/dev/sda1".to_string(),
+            mount_point: "/".to_string(),
+            file_system: "ext4".to_string(),
+            total_space: 1000,
+            available_space: 400,
+            total_read: 300,
+            ..DiskReading::default()
+        }],
+        networks: vec![
+            InterfaceReading {
+                name: "eth0".to_string(),
+                kind: InterfaceKind::Ethernet,
+                physical: true,
+                total_received: 2048,
+                ..InterfaceReading::default()
+            },
+            InterfaceReading {
+                name: "wg0".to_string(),
+                kind: InterfaceKind::Wireguard,
+                total_received: 512,
+                ..InterfaceReading::default()
+            },
+        ],
+        processes: vec![ProcessReading {
+            pid: 7,
+            name: "we\"ird\\name".to_string(),
+            cpu: 150.0,
+            memory: 4096,
+            cpu_time: 3,
+            ..ProcessReading::default()
+        }],
+        battery: Some(BatteryReading { charge: 0.5, state: "Charging".to_string(), ..BatteryReading::default() }),
+    }
+}
+
+fn render(machine: Machine) -> String {
+    let mut collector = Collector::with_source(FakeSource::new(machine));
+    let system = collector.system();
+    let battery = collector.battery();
+    let processes = collector.processes(false);
+    render_prometheus(&system, &battery, &processes)
+}
+
+#[test]
+fn samples_use_base_units_and_escaped_labels() {
+    let text = render(machine());
+
+    for line in [
+        "# HELP peep_cpu_usage_ratio Overall CPU usage.\n# TYPE peep_cpu_usage_ratio gauge\npeep_cpu_usage_ratio 0.5\n",
+        "peep_cpu_core_usage_ratio{core=\"1\"} 0.75\n",
+        "peep_memory_used_bytes 250\n",
+        "peep_disk_read_bytes_total 300\n",
+        "peep_filesystem_used_bytes{device=\"/dev/sda1\",mount_point=\"/\",fs_type=\"ext4\"} 600\n",
+        "peep_network_receive_bytes_total{interface=\"eth0\",type=",
+        // Virtual interfaces are exported alongside the physical ones
+        "peep_network_receive_bytes_total{interface=\"wg0\",type=",
+        "peep_process_cpu_usage_ratio{pid=\"7\",name=\"we\\\"ird\\\\name\"} 1.5\n",
+        "peep_process_cpu_seconds_total{pid=\"7\",name=\"we\\\"ird\\\\name\"} 3\n",
+        "peep_battery_available 1\n",
+        "peep_battery_state{state=\"Charging\"} 1\n",
+        "peep_battery_charge_ratio 0.5\n",
+    ] {
+        assert!(text.contains(line), "missing {:?} in:\n{}", line, text);
+    }
+
+    // Every sample belongs to a family declared before it
+    let mut declared = Vec::new();
+    for line in text.lines() {
+        if let Some(family) = line.strip_prefix("# TYPE ") {
+            declared.push(family.split(' ').next().unwrap().to_string());
+        } else if !line.starts_with('#') {
+            let name = line.split(['{', ' ']).next().unwrap();
+            assert!(declared.iter().any(|family| family == name), "undeclared sample: {}", line);
+            assert!(line.rsplit(' ').next().unwrap().parse::<f64>().is_ok(), "bad value: {}", line);
+        }
+    }
+}
+
+#[test]
+fn missing_battery_only_reports_availability() {
+    let text = render(Machine { battery: None, ..machine() });
+    assert!(text.contains("peep_battery_available 0\n"));
+    assert!(!text.contains("peep_battery_charge_ratio"));
+    assert!(!text.contains("peep_battery_state"));
+}
+
+fn get(exporter: &Exporter, request: &str) -> (String, String) {
+    let mut stream = TcpStream::connect(exporter.local_addr()).unwrap();
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    (head.to_string(), body.to_string())
+}
+
+#[test]
+fn exporter_serves_metrics_over_http() {
+    let exporter = Exporter::start("127.0.0.1:0", ExporterOptions { top_processes: 3 }).unwrap();
+
+    let (head, body) = get(&exporter, "GET /metrics?x=1 HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    assert!(head.starts_with("HTTP/1.1 200 OK\r\n"), "{}", head);
+    assert!(head.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n"));
+    assert!(head.contains(&format!("Content-Length: {}\r\n", body.len())));
+    assert!(body.contains("# TYPE peep_cpu_usage_ratio gauge\n"));
+    assert!(body.contains("peep_scrape_duration_seconds "));
+    assert!(body.lines().filter(|line| line.starts_with("peep_process_memory_bytes")).count() <= 3);
+
+    let (head, _) = get(&exporter, "GET /nope HTTP/1.1\r\n\r\n");
+    assert!(head.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    let (head, _) = get(&exporter, "POST /metrics HTTP/1.1\r\n\r\n");
+    assert!(head.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+
+    exporter.stop();
+}
+
+#[test]
+fn exporter_on_a_wildcard_address_stops() {
+    let exporter = Exporter::start("0.0.0.0:0", ExporterOptions::default()).unwrap();
+    let started = Instant::now();
+    exporter.stop();
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn exporter_scrapes_the_feed_it_is_given() {
+    let feed = Collector::with_source(FakeSource::new(machine()));
+    let exporter = Exporter::start_with("127.0.0.1:0", ExporterOptions::default(), feed).unwrap();
+    let (_, body) = get(&exporter, "GET /metrics HTTP/1.1\r\n\r\n");
+    assert!(body.contains("peep_memory_used_bytes 250\n"), "{}", body);
+    assert!(body.contains("peep_battery_charge_ratio 0.5\n"));
+    exporter.stop();
+}
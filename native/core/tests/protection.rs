@@ -0,0 +1,141 @@
+use peep_core::{
+    send_signal, set_protection_policy, signal_processes, terminate_gracefully, Collector, ControlErrorCode, FakeSource, Machine,
+    ProcessReading, ProcessSignal, ProtectionPolicy, SignalScope, TreeOrder,
+};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+#[cfg(unix)]
+mod orphan;
+
+// The policy is process-wide, so tests that signal anything hold this, and
+// the defaults come back when it's dropped, even after a failure
+static POLICY: Mutex<()> = Mutex::new(());
+
+struct PolicyGuard {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for PolicyGuard {
+    fn drop(&mut self) {
+        set_protection_policy(None);
+    }
+}
+
+fn policy(policy: Option<ProtectionPolicy>) -> PolicyGuard {
+    let guard = PolicyGuard { _lock: POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) };
+    set_protection_policy(policy);
+    guard
+}
+
+#[test]
+fn built_in_rules_cover_init_peep_and_kernel_threads() {
+    let policy = ProtectionPolicy::none();
+    assert_eq!(policy.reason(1, "launchd", None, false, false).unwrap(), "launchd (1) is protected: it's the init process");
+    let peep = std::process::id();
+    assert_eq!(policy.reason(peep, "peep", None, false, false).unwrap(), format!("peep ({}) is protected: this is Peep", peep));
+    assert!(policy.reason(2, "kthreadd", None, true, false).unwrap().ends_with("it's a kernel thread"));
+    #[cfg(unix)]
+    assert!(policy.reason(std::os::unix::process::parent_id(), "electron", None, false, false).unwrap().ends_with("it started Peep"));
+    assert!(policy.reason(u32::MAX, "helper", None, false, true).unwrap().ends_with("Peep started it"));
+    assert_eq!(policy.reason(u32::MAX, "node", Some("/usr/bin/node"), false, false), None);
+}
+
+#[test]
+fn patterns_match_names_or_paths() {
+    let policy = ProtectionPolicy { patterns: vec!["window*".to_string(), "/usr/libexec/*d".to_string()] };
+    let reason = |name: &str, exe: Option<&str>| policy.reason(u32::MAX, name, exe, false, false);
+
+    assert_eq!(reason("WindowServer", None).unwrap(), format!("WindowServer ({}) is protected: it matches \"window*\"", u32::MAX));
+    assert!(reason("syslogd", Some("/usr/libexec/syslogd")).is_some());
+    assert_eq!(reason("xwindow", Some("/opt/xwindow")), None);
+    // Path patterns never match the name, or a process whose path is unknown
+    assert_eq!(reason("/usr/libexec/food", None), None);
+    assert_eq!(reason("syslogd", Some("/usr/libexec/syslog")), None);
+}
+
+#[test]
+fn kill_needs_force_for_protected_processes() {
+    let _policy = policy(None);
+    let process = |pid: u32, parent: Option<u32>, name: &str| {
+        ProcessReading { pid, parent, name: name.to_string(), ..ProcessReading::default() }
+    };
+    let peep = std::process::id();
+    let source = FakeSource::new(Machine {
+        processes: vec![process(1, None, "launchd"), process(42, Some(1), "node"), process(43, Some(peep), "helper")],
+        ..Machine::default()
+    });
+    let mut collector = Collector::with_source(source.clone());
+
+    let refused = collector.kill(1, false);
+    assert!(!refused.success);
+    assert_eq!((refused.code, refused.errno), (Some(ControlErrorCode::ProtectedProcess), Some(1)));
+    assert!(source.killed().is_empty());
+
+    assert!(collector.kill(43, false).message.ends_with("Peep started it"));
+
+    assert!(collector.kill(1, true).success);
+    assert!(collector.kill(42, false).success);
+    assert_eq!(source.killed(), vec![1, 42]);
+}
+
+#[cfg(unix)]
+#[test]
+fn configured_patterns_protect_running_processes() {
+    let pid = orphan::sleep();
+    let _policy = policy(Some(ProtectionPolicy { patterns: vec!["SLEEP".to_string()] }));
+
+    let refused = send_signal(pid, ProcessSignal::Kill, false);
+    assert_eq!(refused.code, Some(ControlErrorCode::ProtectedProcess));
+    assert_eq!(refused.message, format!("sleep ({}) is protected: it matches \"SLEEP\"", pid));
+    assert_eq!(terminate_gracefully(pid, Duration::from_secs(1), false).code, Some(ControlErrorCode::ProtectedProcess));
+    assert!(orphan::is_running(pid));
+
+    // Forcing overrides the policy, except for Peep itself
+    assert_eq!(send_signal(std::process::id(), ProcessSignal::Cont, true).code, Some(ControlErrorCode::ProtectedProcess));
+    assert!(send_signal(pid, ProcessSignal::Kill, true).success);
+    orphan::wait_for_exit(pid);
+}
+
+#[cfg(unix)]
+#[test]
+fn processes_peep_started_need_force() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let _policy = policy(Some(ProtectionPolicy::none()));
+    let mut shell = std::process::Command::new("sh")
+        .args(["-c", "sleep 30 & echo ready; wait"])
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut line = String::new();
+    BufReader::new(shell.stdout.take().unwrap()).read_line(&mut line).unwrap();
+
+    // Its grandchildren too, not just its children
+    let outcomes = signal_processes(shell.id(), ProcessSignal::Kill, SignalScope::Tree(TreeOrder::TopDown), false);
+    assert_eq!(outcomes.len(), 2, "{:?}", outcomes);
+    for outcome in &outcomes {
+        assert_eq!(outcome.code, Some(ControlErrorCode::ProtectedProcess));
+        assert!(outcome.message.ends_with("Peep started it"), "{}", outcome.message);
+    }
+    assert_eq!(shell.try_wait().unwrap(), None);
+
+    let forced = signal_processes(shell.id(), ProcessSignal::Kill, SignalScope::Tree(TreeOrder::BottomUp), true);
+    assert!(forced.iter().all(|o| o.success), "{:?}", forced);
+    shell.wait().unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn refuses_kernel_threads() {
+    let _policy = policy(None);
+    // In a pid namespace pid 2 may be an ordinary process
+    let status = std::fs::read_to_string("/proc/2/status").unwrap_or_default();
+    if !status.starts_with("Name:\tkthreadd") {
+        return;
+    }
+    let refused = send_signal(2, ProcessSignal::Cont, false);
+    assert_eq!(refused.code, Some(ControlErrorCode::ProtectedProcess));
+    assert_eq!(refused.message, "kthreadd (2) is protected: it's a kernel thread");
+}
@@ -0,0 +1,157 @@
+mod common;
+
+use common::snapshot;
+use peep_core::{BatterySnapshot, DiskDetail, ProcessDetail, ProcessRecord, Recorder, Recording, RefreshPlan, Replay};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::Duration;
+
+const STARTED: u64 = 1_700_000_000_000;
+
+fn process(pid: u32, is_thread: bool) -> ProcessRecord {
+    ProcessRecord {
+        pid,
+        name: format!("proc{}", pid),
+        ppid: 1,
+        is_thread,
+        cpu: 1.5,
+        memory: 4096,
+        run_time: 60,
+        cpu_time: 2,
+        status: "Run".to_string(),
+        user: "root".to_string(),
+        command: String::new(),
+        disk_read: 0,
+        disk_write: 0,
+        net_rx: None,
+        net_tx: None,
+        start_time: 0,
+        exe: None,
+    }
+}
+
+fn battery(percentage: f64) -> BatterySnapshot {
+    BatterySnapshot { available: true, percentage: Some(percentage), ..BatterySnapshot::default() }
+}
+
+// System at 0, 1s and 2s; processes at 0 and 2s; battery at 1s
+fn record(path: &std::path::Path) {
+    let mut recorder = Recorder::create(path, STARTED, Some(&peep_core::os_info())).unwrap();
+    recorder.record_system(STARTED, &snapshot(10.0)).unwrap();
+    recorder.record_processes(STARTED, &[process(1, false), process(2, true)]).unwrap();
+    recorder.record_system(STARTED + 1000, &snapshot(90.0)).unwrap();
+    recorder.record_battery(STARTED + 1000, &battery(50.0)).unwrap();
+    recorder.record_system(STARTED + 2000, &snapshot(20.0)).unwrap();
+    recorder.record_processes(STARTED + 2000, &[process(3, false)]).unwrap();
+    assert_eq!(recorder.frames(), 6);
+}
+
+#[test]
+fn recordings_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    record(&path);
+
+    let recording = Recording::open(&path).unwrap();
+    assert_eq!(recording.started_at, STARTED);
+    assert_eq!(recording.os.as_ref().map(|os| os.hostname.clone()), Some(peep_core::os_info().hostname));
+    assert_eq!(recording.frames.len(), 6);
+    assert_eq!(recording.duration(), 2000);
+
+    assert_eq!(recording.frames[0].offset, 0);
+    assert_eq!(recording.frames[0].system, Some(snapshot(10.0)));
+    assert_eq!(recording.frames[0].processes, None);
+    assert_eq!(recording.frames[3].battery, Some(battery(50.0)));
+}
+
+#[test]
+fn lookups_return_the_newest_snapshot_of_each_kind() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    record(&path);
+    let recording = Recording::open(&path).unwrap();
+
+    let usage = |offset| recording.system_at(offset).unwrap().cpu.usage;
+    assert_eq!(usage(0), 10.0);
+    assert_eq!(usage(999), 10.0);
+    assert_eq!(usage(1000), 90.0);
+    assert_eq!(usage(5000), 20.0);
+
+    let pids = |offset| recording.processes_at(offset).unwrap().iter().map(|p| p.pid).collect::<Vec<_>>();
+    assert_eq!(pids(1500), vec![1, 2]);
+    assert_eq!(pids(2000), vec![3]);
+
+    // Before the first battery frame, that first frame stands in
+    assert_eq!(recording.battery_at(0), Some(&battery(50.0)));
+}
+
+#[test]
+fn torn_last_frame_is_ignored() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    record(&path);
+
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(br#"{"offset":3000,"system":{"cpu":{"usa"#).unwrap();
+    drop(file);
+    assert_eq!(Recording::open(&path).unwrap().frames.len(), 6);
+
+    // Anything after it means the file is damaged, not torn
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"\n{\"offset\":4000}\n").unwrap();
+    drop(file);
+    let err = Recording::open(&path).unwrap_err();
+    assert!(err.to_string().contains("line 8"), "{}", err);
+}
+
+#[test]
+fn unknown_files_and_newer_versions_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+
+    fs::write(&path, "{\"timestamp\":1}\n").unwrap();
+    assert!(Recording::open(&path).unwrap_err().to_string().contains("Not a Peep recording"));
+
+    fs::write(&path, "{\"format\":\"peep-recording\",\"version\":99,\"startedAt\":0}\n").unwrap();
+    let err = Recording::open(&path).unwrap_err();
+    assert!(err.to_string().contains("version 99"), "{}", err);
+}
+
+#[test]
+fn replay_scales_and_loops_time() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    record(&path);
+
+    let fast = Replay::new(Recording::open(&path).unwrap(), 4.0, false);
+    assert_eq!(fast.offset_after(Duration::from_millis(250)), 1000);
+    // Holds on the last frame once played through
+    assert_eq!(fast.offset_after(Duration::from_secs(10)), 2000);
+
+    let looped = Replay::new(Recording::open(&path).unwrap(), 1.0, true);
+    assert_eq!(looped.offset_after(Duration::from_millis(2500)), 2500 % 2001);
+    assert!(!looped.finished());
+}
+
+#[test]
+fn replay_answers_refresh_plans_like_the_collector() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("session.jsonl");
+    record(&path);
+    let replay = Replay::new(Recording::open(&path).unwrap(), 1.0, false);
+
+    let partial = replay.collect(&RefreshPlan {
+        cpu: true,
+        disks: Some(DiskDetail::Io),
+        processes: Some(ProcessDetail::default()),
+        ..RefreshPlan::default()
+    });
+    assert_eq!(partial.cpu, Some(snapshot(10.0).cpu));
+    assert_eq!(partial.memory, None);
+    assert_eq!(partial.network, None);
+    // I/O detail leaves the volumes out, as collection does
+    assert!(partial.disk.unwrap().disks.is_empty());
+    // Threads are only included when asked for
+    assert_eq!(partial.processes.unwrap().iter().map(|p| p.pid).collect::<Vec<_>>(), vec![1]);
+    assert_eq!(replay.processes(true).unwrap().len(), 2);
+}
@@ -0,0 +1,351 @@
+use peep_core::{
+    BatteryReading, Collector, ControlErrorCode, CpuReading, DiskDetail, DiskFilter, DiskReading, FakeSource, InterfaceKind,
+    InterfaceReading, Machine, MemoryReading, ProcessDetail, ProcessIdentity, ProcessReading, RefreshPlan,
+};
+use serde_json::json;
+use std::time::Duration;
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+fn disk(name: &str, mount_point: &str, file_system: &str, removable: bool, total_read: u64, total_written: u64) -> DiskReading {
+    DiskReading {
+        name: name.to_string(),
+        mount_point: mount_point.to_string(),
+        file_system: file_system.to_string(),
+        is_removable: removable,
+        total_space: 500 * GB,
+        available_space: 200 * GB,
+        total_read,
+        total_written,
+    }
+}
+
+fn interface(name: &str, kind: InterfaceKind, received: u64, transmitted: u64, packets: u64) -> InterfaceReading {
+    InterfaceReading {
+        name: name.to_string(),
+        kind,
+        physical: kind == InterfaceKind::EthernetOrWireless,
+        total_received: received,
+        total_transmitted: transmitted,
+        total_packets_received: packets,
+        total_packets_transmitted: packets,
+        ..InterfaceReading::default()
+    }
+}
+
+fn process(pid: u32, parent: Option<u32>, name: &str, cpu: f32, disk_read: u64) -> ProcessReading {
+    ProcessReading {
+        pid,
+        parent,
+        name: name.to_string(),
+        is_thread: false,
+        cpu,
+        memory: 64 * 1024 * 1024,
+        run_time: 3600,
+        cpu_time: 120,
+        status: "Running".to_string(),
+        user: "alice".to_string(),
+        command: vec![format!("/usr/bin/{}", name), "--flag".to_string()],
+        total_disk_read: disk_read,
+        total_disk_written: 0,
+        total_net_received: None,
+        total_net_transmitted: None,
+        start_time: 1_700_000_000 + pid as u64,
+        exe: Some(format!("/usr/bin/{}", name)),
+    }
+}
+
+// A two-core Mac-like machine; `step` scales the I/O counters so each step
+// adds a known amount
+fn machine(step: u64) -> Machine {
+    Machine {
+        cpu: CpuReading { brand: Some("Fake CPU".to_string()), usage: 25.0, per_core: vec![20.0, 30.0] },
+        memory: MemoryReading {
+            total: 16 * GB,
+            used: 12 * GB,
+            free: 4 * GB,
+            total_swap: 2 * GB,
+            used_swap: GB,
+            free_swap: GB,
+        },
+        disks: vec![
+            disk("Macintosh HD", "/", "apfs", false, 1000, 2000),
+            disk("Macintosh HD", "/System/Volumes/Data", "apfs", false, 10_000 + 4000 * step, 20_000 + 8000 * step),
+            disk("devfs", "/dev", "devfs", false, 0, 0),
+            disk("Installer", "/Volumes/Installer", "hfs", true, 0, 0),
+        ],
+        networks: vec![
+            interface("en0", InterfaceKind::EthernetOrWireless, 100_000 + 2000 * step, 50_000 + 1000 * step, 100 + 10 * step),
+            interface("lo0", InterfaceKind::Loopback, 5000 + 500 * step, 5000 + 500 * step, 0),
+            interface("utun0", InterfaceKind::Tunnel, 0, 0, 0),
+        ],
+        processes: vec![
+            process(1, None, "launchd", 0.5, 4096),
+            process(42, Some(1), "peep", 12.5, 1000 + 3000 * step),
+            ProcessReading { is_thread: true, ..process(43, Some(42), "peep-sampler", 1.0, 0) },
+        ],
+        battery: Some(BatteryReading {
+            charge: 0.8,
+            state: "Discharging".to_string(),
+            energy: 40.0,
+            energy_full: 50.0,
+            energy_full_design: 62.5,
+            time_to_full: None,
+            time_to_empty: Some(Duration::from_secs(90 * 60)),
+            temperature: Some(31.5),
+        }),
+    }
+}
+
+#[test]
+fn system_snapshot_from_a_known_machine() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone()).with_disk_filter(DiskFilter::macos());
+    source.advance(Duration::from_secs(2), machine(1));
+
+    let system = collector.system();
+    assert_eq!(
+        serde_json::to_value(&system).unwrap(),
+        json!({
+            "cpu": { "usage": 25.0, "cores": 2, "brand": "Fake CPU", "perCore": [20.0, 30.0] },
+            "memory": {
+                "total": 16 * GB,
+                "used": 12 * GB,
+                "free": 4 * GB,
+                "totalSwap": 2 * GB,
+                "usedSwap": GB,
+                "freeSwap": GB,
+            },
+            // I/O covers every disk; only the data volume is listed
+            "disk": {
+                "read": 4000,
+                "write": 8000,
+                "readRate": 2000.0,
+                "writeRate": 4000.0,
+                "totalRead": 15_000,
+                "totalWrite": 30_000,
+                "disks": [{
+                    "name": "Macintosh HD",
+                    "mountPoint": "/System/Volumes/Data",
+                    "totalSpace": 500 * GB,
+                    "availableSpace": 200 * GB,
+                    "usedSpace": 300 * GB,
+                    "fileSystem": "apfs",
+                }],
+            },
+            // Totals include loopback, which isn't listed; virtual interfaces
+            // are listed apart from the adapters
+            "network": {
+                "rx": 2500,
+                "tx": 1500,
+                "rxRate": 1250.0,
+                "txRate": 750.0,
+                "rxPacketsRate": 5.0,
+                "txPacketsRate": 5.0,
+                "totalRx": 107_500,
+                "totalTx": 56_500,
+                "interfaces": [{
+                    "name": "en0",
+                    "type": "Ethernet/Wi-Fi",
+                    "kind": "ethernetOrWireless",
+                    "physical": true,
+                    "received": 2000,
+                    "transmitted": 1000,
+                    "receivedRate": 1000.0,
+                    "transmittedRate": 500.0,
+                    "totalReceived": 102_000,
+                    "totalTransmitted": 51_000,
+                    "packetsReceived": 110,
+                    "packetsTransmitted": 110,
+                    "packetsReceivedRate": 5.0,
+                    "packetsTransmittedRate": 5.0,
+                }],
+                "virtualInterfaces": [{
+                    "name": "utun0",
+                    "type": "VPN Tunnel",
+                    "kind": "tunnel",
+                    "physical": false,
+                    "received": 0,
+                    "transmitted": 0,
+                    "receivedRate": 0.0,
+                    "transmittedRate": 0.0,
+                    "totalReceived": 0,
+                    "totalTransmitted": 0,
+                    "packetsReceived": 0,
+                    "packetsTransmitted": 0,
+                    "packetsReceivedRate": 0.0,
+                    "packetsTransmittedRate": 0.0,
+                }],
+            },
+        })
+    );
+}
+
+#[test]
+fn rates_cover_the_time_between_refreshes_of_each_subsystem() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+
+    // Network refreshed after 1s, disks not until 4s
+    source.advance(Duration::from_secs(1), machine(1));
+    assert_eq!(collector.network().interfaces[0].received_rate, 2000.0);
+    source.advance(Duration::from_secs(3), machine(2));
+    let disk = collector.disks();
+    assert_eq!(disk.read, 8000);
+    assert_eq!(disk.read_rate, 2000.0);
+
+    // The network's previous refresh was 3s ago
+    assert_eq!(collector.network().interfaces[0].received_rate, 2000.0 / 3.0);
+    // Nothing changed and no time passed: no traffic, not a division by zero
+    assert_eq!(collector.network().rx_rate, 0.0);
+}
+
+#[test]
+fn processes_are_shaped_and_filtered() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+    collector.processes(false);
+    source.advance(Duration::from_secs(1), machine(1));
+
+    let processes = collector.processes(false);
+    assert_eq!(
+        serde_json::to_value(&processes).unwrap(),
+        json!([
+            {
+                "pid": 1,
+                "name": "launchd",
+                "ppid": 0,
+                "isThread": false,
+                "cpu": 0.5,
+                "memory": 64 * 1024 * 1024,
+                "runTime": 3600,
+                "cpuTime": 120,
+                "status": "Running",
+                "user": "alice",
+                "command": "/usr/bin/launchd --flag",
+                "diskRead": 0,
+                "diskWrite": 0,
+                "startTime": 1_700_000_001,
+                "exe": "/usr/bin/launchd",
+            },
+            {
+                "pid": 42,
+                "name": "peep",
+                "ppid": 1,
+                "isThread": false,
+                "cpu": 12.5,
+                "memory": 64 * 1024 * 1024,
+                "runTime": 3600,
+                "cpuTime": 120,
+                "status": "Running",
+                "user": "alice",
+                "command": "/usr/bin/peep --flag",
+                "diskRead": 3000,
+                "diskWrite": 0,
+                "startTime": 1_700_000_042,
+                "exe": "/usr/bin/peep",
+            },
+        ])
+    );
+
+    assert_eq!(collector.processes(true).len(), 3);
+
+    // Only what the plan asks for is filled in
+    let plan = RefreshPlan::processes(ProcessDetail { threads: false, command: false, user: false, network: false });
+    let partial = collector.collect(&plan);
+    assert!(partial.cpu.is_none() && partial.disk.is_none());
+    let processes = partial.processes.unwrap();
+    assert!(processes.iter().all(|p| p.command.is_empty() && p.exe.is_none() && p.user.is_empty()));
+}
+
+#[test]
+fn a_reused_pid_starts_its_deltas_over() {
+    let source = FakeSource::new(Machine { processes: vec![process(7, None, "old", 1.0, 1000)], ..machine(0) });
+    let mut collector = Collector::with_source(source.clone());
+    collector.processes(false);
+
+    let reused = ProcessReading { start_time: 1_800_000_000, ..process(7, None, "new", 1.0, 5000) };
+    source.advance(Duration::from_secs(1), Machine { processes: vec![reused], ..machine(1) });
+    let processes = collector.processes(false);
+    assert_eq!(processes[0].name, "new");
+    assert_eq!(processes[0].disk_read, 0);
+}
+
+#[test]
+fn partial_collection_skips_volumes_at_io_detail() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+
+    let partial = collector.collect(&RefreshPlan::disks(DiskDetail::Io));
+    assert_eq!(
+        serde_json::to_value(&partial).unwrap(),
+        json!({
+            "disk": {
+                "read": 0,
+                "write": 0,
+                "readRate": 0.0,
+                "writeRate": 0.0,
+                "totalRead": 11_000,
+                "totalWrite": 22_000,
+                "disks": [],
+            },
+        })
+    );
+}
+
+#[test]
+fn battery_is_shaped_from_readings() {
+    let source = FakeSource::new(machine(0));
+    let collector = Collector::with_source(source.clone());
+    assert_eq!(
+        serde_json::to_value(collector.battery()).unwrap(),
+        json!({
+            "available": true,
+            "percentage": 80.0,
+            "state": "Discharging",
+            "health": 80.0,
+            "energyFullDesign": 62.5,
+            "timeToEmpty": 90.0,
+            "energy": 40.0,
+            "energyFull": 50.0,
+            "temperature": 31.5,
+        })
+    );
+
+    source.update(|machine| machine.battery = None);
+    assert_eq!(serde_json::to_value(collector.battery()).unwrap(), json!({ "available": false }));
+}
+
+#[test]
+fn kill_goes_through_the_source() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+
+    assert!(collector.kill(42, false).success);
+    let missing = collector.kill(42, false);
+    assert!(!missing.success);
+    assert_eq!(missing.message, "Process not found");
+    assert_eq!(missing.code, Some(ControlErrorCode::NoSuchProcess));
+    assert_eq!(source.killed(), vec![42]);
+    assert!(collector.processes(false).iter().all(|p| p.pid != 42));
+}
+
+#[test]
+fn kill_refuses_a_reused_pid() {
+    let source = FakeSource::new(machine(0));
+    let mut collector = Collector::with_source(source.clone());
+    let listed = collector.processes(false).into_iter().find(|p| p.pid == 42).unwrap().identity();
+
+    // Pid 42 exited and was handed to a newer process
+    let stale = ProcessIdentity { start_time: listed.start_time - 60, ..listed.clone() };
+    let refused = collector.kill(stale, false);
+    assert!(!refused.success);
+    assert_eq!(refused.message, "PID 42 now belongs to a different process");
+    assert_eq!((refused.code, refused.errno), (Some(ControlErrorCode::IdentityMismatch), Some(3)));
+    let renamed = ProcessIdentity { exe: Some("/usr/bin/other".to_string()), ..listed.clone() };
+    assert!(!collector.kill(renamed, false).success);
+    assert!(source.killed().is_empty());
+
+    assert!(collector.kill(listed, false).success);
+    assert_eq!(source.killed(), vec![42]);
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use common::snapshot;
+use peep_core::{Store, StoreOptions};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const MINUTE: u64 = 60 * 1000;
+
+fn options() -> StoreOptions {
+    StoreOptions {
+        min_interval: Duration::from_secs(1),
+        segment_span: Duration::from_secs(10 * 60),
+        max_segment_bytes: 1024 * 1024,
+        retention: Duration::from_secs(24 * 60 * 60),
+        compact_after: Duration::from_secs(60 * 60),
+        compact_step: Duration::from_secs(60),
+    }
+}
+
+fn segments(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn samples_survive_reopening() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = Store::open(dir.path(), options()).unwrap();
+        for i in 0..30 {
+            assert!(store.append(i * MINUTE, &snapshot(i as f32)).unwrap());
+        }
+    }
+
+    let store = Store::open(dir.path(), options()).unwrap();
+    let samples = store.query(0, u64::MAX).unwrap();
+    assert_eq!(samples.len(), 30);
+    assert_eq!(samples[7].timestamp, 7 * MINUTE);
+    assert_eq!(samples[7].snapshot, snapshot(7.0));
+    assert_eq!(store.range(), Some((0, 29 * MINUTE)));
+    // 10-minute segments
+    assert_eq!(segments(dir.path()).len(), 3);
+
+    let window = store.query(5 * MINUTE, 12 * MINUTE).unwrap();
+    let timestamps: Vec<_> = window.iter().map(|s| s.timestamp / MINUTE).collect();
+    assert_eq!(timestamps, (5..=12).collect::<Vec<_>>());
+}
+
+#[test]
+fn stored_samples_serialize_like_system_info() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = Store::open(dir.path(), options()).unwrap();
+    store.append(1234, &snapshot(5.0)).unwrap();
+
+    let sample = &store.query(0, u64::MAX).unwrap()[0];
+    let mut expected = serde_json::to_value(snapshot(5.0)).unwrap();
+    expected["timestamp"] = 1234.into();
+    assert_eq!(serde_json::to_value(sample).unwrap(), expected);
+}
+
+#[test]
+fn torn_tail_write_is_truncated_on_open() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = Store::open(dir.path(), options()).unwrap();
+        for i in 0..5 {
+            store.append(i * MINUTE, &snapshot(i as f32)).unwrap();
+        }
+    }
+
+    // Simulate a crash halfway through writing the next record
+    let segment = segments(dir.path()).pop().unwrap();
+    let intact_len = fs::metadata(&segment).unwrap().len();
+    let mut file = OpenOptions::new().append(true).open(&segment).unwrap();
+    file.write_all(&200u32.to_le_bytes()).unwrap();
+    file.write_all(&[0xab; 4]).unwrap();
+    file.write_all(br#"{"timestamp":300000,"cpu":{"#).unwrap();
+    drop(file);
+
+    let mut store = Store::open(dir.path(), options()).unwrap();
+    assert_eq!(fs::metadata(&segment).unwrap().len(), intact_len);
+    assert_eq!(store.query(0, u64::MAX).unwrap().len(), 5);
+
+    // New samples land after the recovered records
+    assert!(store.append(5 * MINUTE, &snapshot(5.0)).unwrap());
+    drop(store);
+    let store = Store::open(dir.path(), options()).unwrap();
+    let samples = store.query(0, u64::MAX).unwrap();
+    assert_eq!(samples.len(), 6);
+    assert_eq!(samples[5].snapshot, snapshot(5.0));
+}
+
+#[test]
+fn corrupt_record_drops_the_rest_of_its_segment() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = Store::open(dir.path(), options()).unwrap();
+        for i in 0..3 {
+            store.append(i * MINUTE, &snapshot(i as f32)).unwrap();
+        }
+    }
+
+    // Flip a payload byte in the last record so its checksum no longer matches
+    let segment = segments(dir.path()).pop().unwrap();
+    let mut bytes = fs::read(&segment).unwrap();
+    let last = bytes.len() - 2;
+    bytes[last] ^= 0xff;
+    fs::write(&segment, bytes).unwrap();
+
+    let store = Store::open(dir.path(), options()).unwrap();
+    let samples = store.query(0, u64::MAX).unwrap();
+    assert_eq!(samples.len(), 2);
+    assert_eq!(store.range(), Some((0, MINUTE)));
+}
+
+#[test]
+fn only_the_newest_segment_is_truncated_on_open() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = Store::open(dir.path(), options()).unwrap();
+        for i in 0..15 {
+            store.append(i * MINUTE, &snapshot(i as f32)).unwrap();
+        }
+    }
+
+    // Damage the tail of the older, sealed segment
+    let oldest = segments(dir.path()).remove(0);
+    let mut bytes = fs::read(&oldest).unwrap();
+    let last = bytes.len() - 2;
+    bytes[last] ^= 0xff;
+    fs::write(&oldest, &bytes).unwrap();
+
+    let store = Store::open(dir.path(), options()).unwrap();
+    assert_eq!(fs::read(&oldest).unwrap(), bytes);
+    // The bad record is skipped, everything else is still readable
+    let samples = store.query(0, u64::MAX).unwrap();
+    assert_eq!(samples.len(), 14);
+    assert!(samples.iter().all(|s| s.timestamp != 9 * MINUTE));
+}
+
+#[test]
+fn prepared_queries_read_without_the_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = Store::open(dir.path(), options()).unwrap();
+    for i in 0..15 {
+        store.append(i * MINUTE, &snapshot(i as f32)).unwrap();
+    }
+
+    let query = store.prepare_query(5 * MINUTE, u64::MAX);
+    store.append(15 * MINUTE, &snapshot(15.0)).unwrap();
+    drop(store);
+    // Segments it picked are read as they are now
+    assert_eq!(query.run().unwrap().len(), 11);
+}
+
+#[test]
+fn empty_segment_from_crash_before_first_write_is_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    {
+        let mut store = Store::open(dir.path(), options()).unwrap();
+        store.append(0, &snapshot(1.0)).unwrap();
+    }
+    fs::write(dir.path().join(format!("{:013}.seg", 20 * MINUTE)), b"").unwrap();
+    fs::write(dir.path().join(format!("{:013}.tmp", 0)), b"partial").unwrap();
+
+    let store = Store::open(dir.path(), options()).unwrap();
+    assert_eq!(segments(dir.path()).len(), 1);
+    assert_eq!(store.query(0, u64::MAX).unwrap().len(), 1);
+}
+
+#[test]
+fn samples_closer_than_min_interval_are_skipped() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = Store::open(dir.path(), options()).unwrap();
+    assert!(store.append(10_000, &snapshot(1.0)).unwrap());
+    assert!(!store.append(10_500, &snapshot(2.0)).unwrap());
+    assert!(!store.append(9_000, &snapshot(3.0)).unwrap());
+    assert!(store.append(11_000, &snapshot(4.0)).unwrap());
+    assert_eq!(store.query(0, u64::MAX).unwrap().len(), 2);
+}
+
+#[test]
+fn old_segments_are_compacted_then_expired() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut store = Store::open(dir.path(), options()).unwrap();
+
+    // Two hours of 10s samples
+    let second = 1000;
+    for i in 0..720 {
+        store.append(i * 10 * second, &snapshot(0.0)).unwrap();
+    }
+
+    // Everything older than an hour is down to one sample per minute
+    let now = 120 * MINUTE;
+    store.maintain(now).unwrap();
+    let old = store.query(0, 50 * MINUTE - 1).unwrap();
+    assert_eq!(old.len(), 50);
+    assert!(old.iter().all(|s| s.timestamp % MINUTE == 0));
+    let recent = store.query(110 * MINUTE, now).unwrap();
+    assert_eq!(recent.len(), 60);
+
+    // Compaction survives reopening
+    drop(store);
+    let mut store = Store::open(dir.path(), options()).unwrap();
+    assert_eq!(store.query(0, 50 * MINUTE - 1).unwrap().len(), 50);
+
+    // A day later only the segment still being appended to remains
+    store.maintain(now + 24 * 60 * MINUTE).unwrap();
+    assert_eq!(segments(dir.path()).len(), 1);
+    assert_eq!(store.range(), Some((110 * MINUTE, 7190 * second)));
+}
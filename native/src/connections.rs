@@ -0,0 +1,69 @@
+use neon::prelude::*;
+use peep_core::{connections, listening_ports, ConnectionFilter, Protocol};
+use serde::de::DeserializeOwned;
+
+use crate::task::fallible_promise;
+
+// Read a list of enum names, e.g. ['tcp', 'udp6'], by their serialized form
+fn names<T: DeserializeOwned>(cx: &mut FunctionContext, obj: Handle<JsObject>, key: &str) -> NeonResult<Vec<T>> {
+    let Some(array) = obj.get_opt::<JsArray, _, _>(cx, key)? else {
+        return Ok(Vec::new());
+    };
+    let mut values = Vec::new();
+    for value in array.to_vec(cx)? {
+        let name = value.downcast_or_throw::<JsString, _>(cx)?.value(cx);
+        match serde_json::from_value(serde_json::Value::String(name.clone())) {
+            Ok(value) => values.push(value),
+            Err(_) => return cx.throw_error(format!("Unknown value in {}: {}", key, name)),
+        }
+    }
+    Ok(values)
+}
+
+// The (port, proto) arguments of the port lookups; proto is 'tcp' or 'udp'
+// and defaults to 'tcp'
+pub fn port_arguments(cx: &mut FunctionContext) -> NeonResult<(u16, Protocol)> {
+    let port = cx.argument::<JsNumber>(0)?.value(cx);
+    if !(0.0..=65535.0).contains(&port) || port.fract() != 0.0 {
+        return cx.throw_range_error(format!("Invalid port: {}", port));
+    }
+    let protocol = match cx.argument_opt(1).and_then(|v| v.downcast::<JsString, _>(cx).ok()) {
+        Some(name) => {
+            let name = name.value(cx);
+            match serde_json::from_value(serde_json::Value::String(name.clone())) {
+                Ok(protocol) => protocol,
+                Err(_) => return cx.throw_error(format!("Unknown protocol: {}", name)),
+            }
+        }
+        None => Protocol::Tcp,
+    };
+    Ok((port as u16, protocol))
+}
+
+// getConnections(filter?) resolves to every TCP and UDP socket with its
+// owning process (Linux only; empty elsewhere):
+//
+//     { protocols?: ('tcp' | 'tcp6' | 'udp' | 'udp6')[],
+//       states?: ('established' | 'listen' | 'timeWait' | ...)[], pid?: number }
+pub fn get_connections(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let mut filter = ConnectionFilter::default();
+    if let Some(obj) = cx.argument_opt(0).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) {
+        filter.protocols = names(&mut cx, obj, "protocols")?;
+        filter.states = names(&mut cx, obj, "states")?;
+        filter.pid = obj.get_opt::<JsNumber, _, _>(&mut cx, "pid")?.map(|pid| pid.value(&mut cx) as u32);
+    }
+    fallible_promise(&mut cx, move || connections(&filter).map_err(|err| err.to_string()))
+}
+
+// getListeningPorts() resolves to every listening TCP and UDP port with the
+// pid, name, command and user of its owner (Linux only; empty elsewhere)
+pub fn get_listening_ports(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    fallible_promise(&mut cx, || listening_ports().map_err(|err| err.to_string()))
+}
+
+// findProcessByPort(port, proto?) resolves to the listener on that port, or
+// null when nothing listens there
+pub fn find_process_by_port(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let (port, protocol) = port_arguments(&mut cx)?;
+    fallible_promise(&mut cx, move || peep_core::find_process_by_port(port, protocol).map_err(|err| err.to_string()))
+}
@@ -0,0 +1,98 @@
+use neon::prelude::*;
+use peep_core::{ProcessIdentity, ProcessSignal, ProcessTarget, SignalScope, TreeOrder};
+use std::time::Duration;
+
+use crate::task::promise;
+
+// How long terminateGracefully waits for SIGTERM when no timeout is given
+const DEFAULT_TERMINATE_TIMEOUT_MS: f64 = 5000.0;
+
+// A pid, or a process's identity as listed: { pid, startTime, exe? }. An
+// identity is only acted on while the pid still belongs to that process.
+pub fn target_argument(cx: &mut FunctionContext, i: usize) -> NeonResult<ProcessTarget> {
+    let value = cx.argument::<JsValue>(i)?;
+    if let Ok(pid) = value.downcast::<JsNumber, _>(cx) {
+        return Ok(ProcessTarget::Pid(pid.value(cx) as u32));
+    }
+    let obj = value.downcast_or_throw::<JsObject, _>(cx)?;
+    let pid = obj.get::<JsNumber, _, _>(cx, "pid")?.value(cx) as u32;
+    let start_time = obj.get::<JsNumber, _, _>(cx, "startTime")?.value(cx) as u64;
+    let exe = obj.get_opt::<JsString, _, _>(cx, "exe")?.map(|v| v.value(cx));
+    Ok(ProcessTarget::Identity(ProcessIdentity { pid, start_time, exe }))
+}
+
+// A signal name like 'TERM', 'SIGHUP' or 'usr1'; KILL when omitted
+fn signal_argument(cx: &mut FunctionContext, i: usize) -> NeonResult<ProcessSignal> {
+    match optional_string(cx, i) {
+        Some(name) => match name.parse() {
+            Ok(signal) => Ok(signal),
+            Err(err) => cx.throw_error(err),
+        },
+        None => Ok(ProcessSignal::Kill),
+    }
+}
+
+// The trailing `force` argument: protected processes are refused without it
+pub fn force_argument(cx: &mut FunctionContext, i: usize) -> bool {
+    cx.argument_opt(i)
+        .and_then(|v| v.downcast::<JsBoolean, _>(cx).ok())
+        .is_some_and(|v| v.value(cx))
+}
+
+fn optional_string(cx: &mut FunctionContext, i: usize) -> Option<String> {
+    cx.argument_opt(i)
+        .and_then(|v| v.downcast::<JsString, _>(cx).ok())
+        .map(|v| v.value(cx))
+}
+
+// sendSignal(target, signal, force?) resolves to { success, message, code?, errno? }
+pub fn send_signal(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let target = target_argument(&mut cx, 0)?;
+    // Required here, unlike for the tree and group exports
+    cx.argument::<JsString>(1)?;
+    let signal = signal_argument(&mut cx, 1)?;
+    let force = force_argument(&mut cx, 2);
+    promise(&mut cx, move || peep_core::send_signal(target, signal, force))
+}
+
+// signalProcessTree(target, signal?, order?, force?) signals the process and all its
+// descendants, 'bottomUp' (the default) or 'topDown', and resolves to
+// [{ pid, name, success, message }] in the order they were signalled
+pub fn signal_process_tree(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let target = target_argument(&mut cx, 0)?;
+    let signal = signal_argument(&mut cx, 1)?;
+    let order = match optional_string(&mut cx, 2).as_deref() {
+        None | Some("bottomUp") => TreeOrder::BottomUp,
+        Some("topDown") => TreeOrder::TopDown,
+        Some(order) => return cx.throw_error(format!("Unknown order: {}", order)),
+    };
+    let force = force_argument(&mut cx, 3);
+    promise(&mut cx, move || peep_core::signal_processes(target, signal, SignalScope::Tree(order), force))
+}
+
+// signalProcessGroup(target, signal?, scope?, force?) signals every process in the
+// process's 'group' (the default) or 'session', with the same report
+pub fn signal_process_group(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let target = target_argument(&mut cx, 0)?;
+    let signal = signal_argument(&mut cx, 1)?;
+    let scope = match optional_string(&mut cx, 2).as_deref() {
+        None | Some("group") => SignalScope::Group,
+        Some("session") => SignalScope::Session,
+        Some(scope) => return cx.throw_error(format!("Unknown scope: {}", scope)),
+    };
+    let force = force_argument(&mut cx, 3);
+    promise(&mut cx, move || peep_core::signal_processes(target, signal, scope, force))
+}
+
+// terminateGracefully(target, timeoutMs?, force?) resolves to { success, message, endedBy? }
+// once the process has exited or can't be ended; endedBy is 'term' or 'kill'
+pub fn terminate_gracefully(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let target = target_argument(&mut cx, 0)?;
+    let timeout = cx.argument_opt(1)
+        .and_then(|v| v.downcast::<JsNumber, _>(&mut cx).ok())
+        .map(|v| v.value(&mut cx))
+        .unwrap_or(DEFAULT_TERMINATE_TIMEOUT_MS);
+    let timeout = Duration::from_secs_f64(timeout.max(0.0) / 1000.0);
+    let force = force_argument(&mut cx, 2);
+    promise(&mut cx, move || peep_core::terminate_gracefully(target, timeout, force))
+}
@@ -0,0 +1,76 @@
+use neon::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+
+// Convert any serializable peep-core type into the equivalent JS value.
+// The snapshot types already serialize with the renderer's field names, so
+// this and `from_js` are the only places that need to know about JS at all.
+pub fn to_js<'a, C: Context<'a>, T: Serialize>(cx: &mut C, value: &T) -> JsResult<'a, JsValue> {
+    match serde_json::to_value(value) {
+        Ok(value) => value_to_js(cx, &value),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+fn value_to_js<'a, C: Context<'a>>(cx: &mut C, value: &Value) -> JsResult<'a, JsValue> {
+    match value {
+        Value::Null => Ok(cx.null().upcast()),
+        Value::Bool(b) => Ok(cx.boolean(*b).upcast()),
+        Value::Number(n) => Ok(cx.number(n.as_f64().unwrap_or(0.0)).upcast()),
+        Value::String(s) => Ok(cx.string(s).upcast()),
+        Value::Array(items) => {
+            let array = JsArray::new(cx, items.len());
+            for (i, item) in items.iter().enumerate() {
+                let item = value_to_js(cx, item)?;
+                array.set(cx, i as u32, item)?;
+            }
+            Ok(array.upcast())
+        }
+        Value::Object(fields) => {
+            let obj = cx.empty_object();
+            for (key, field) in fields {
+                let field = value_to_js(cx, field)?;
+                obj.set(cx, key.as_str(), field)?;
+            }
+            Ok(obj.upcast())
+        }
+    }
+}
+
+// Convert a JS argument into JSON for peep-core to parse. Functions and
+// other values JSON can't hold become null.
+pub fn from_js<'a, C: Context<'a>>(cx: &mut C, value: Handle<'a, JsValue>) -> NeonResult<Value> {
+    if let Ok(b) = value.downcast::<JsBoolean, _>(cx) {
+        return Ok(Value::Bool(b.value(cx)));
+    }
+    if let Ok(n) = value.downcast::<JsNumber, _>(cx) {
+        return Ok(serde_json::Number::from_f64(n.value(cx)).map_or(Value::Null, Value::Number));
+    }
+    if let Ok(s) = value.downcast::<JsString, _>(cx) {
+        return Ok(Value::String(s.value(cx)));
+    }
+    if let Ok(array) = value.downcast::<JsArray, _>(cx) {
+        let mut items = Vec::new();
+        for item in array.to_vec(cx)? {
+            items.push(from_js(cx, item)?);
+        }
+        return Ok(Value::Array(items));
+    }
+    if value.is_a::<JsFunction, _>(cx) {
+        return Ok(Value::Null);
+    }
+    if let Ok(obj) = value.downcast::<JsObject, _>(cx) {
+        let mut fields = serde_json::Map::new();
+        let keys = obj.get_own_property_names(cx)?.to_vec(cx)?;
+        for key in keys {
+            let Ok(key) = key.downcast::<JsString, _>(cx) else {
+                continue;
+            };
+            let key = key.value(cx);
+            let field: Handle<JsValue> = obj.get(cx, key.as_str())?;
+            fields.insert(key, from_js(cx, field)?);
+        }
+        return Ok(Value::Object(fields));
+    }
+    Ok(Value::Null)
+}
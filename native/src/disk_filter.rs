@@ -0,0 +1,82 @@
+use neon::prelude::*;
+use peep_core::{disk_filter, set_disk_filter, DiskFilter, Shadowed};
+
+use crate::convert::to_js;
+
+fn string_list(cx: &mut FunctionContext, obj: Handle<JsObject>, key: &str) -> NeonResult<Option<Vec<String>>> {
+    let Some(array) = obj.get_opt::<JsArray, _, _>(cx, key)? else {
+        return Ok(None);
+    };
+    let mut values = Vec::new();
+    for value in array.to_vec(cx)? {
+        values.push(value.downcast_or_throw::<JsString, _>(cx)?.value(cx));
+    }
+    Ok(Some(values))
+}
+
+fn flag(cx: &mut FunctionContext, obj: Handle<JsObject>, key: &str) -> NeonResult<Option<bool>> {
+    Ok(obj.get_opt::<JsBoolean, _, _>(cx, key)?.map(|v| v.value(cx)))
+}
+
+fn shadowed(cx: &mut FunctionContext, obj: Handle<JsObject>) -> NeonResult<Option<Vec<Shadowed>>> {
+    let Some(array) = obj.get_opt::<JsArray, _, _>(cx, "shadowed")? else {
+        return Ok(None);
+    };
+    let mut rules = Vec::new();
+    for rule in array.to_vec(cx)? {
+        let rule = rule.downcast_or_throw::<JsObject, _>(cx)?;
+        rules.push(Shadowed {
+            mount_point: rule.get::<JsString, _, _>(cx, "mountPoint")?.value(cx),
+            shadowed_by: rule.get::<JsString, _, _>(cx, "shadowedBy")?.value(cx),
+        });
+    }
+    Ok(Some(rules))
+}
+
+// setDiskFilter(filter?) replaces the volume filter used by every disk API:
+//
+//     { excludeFileSystems?: string[], excludeMountPrefixes?: string[],
+//       excludeRemovable?: boolean, includeFileSystems?: string[],
+//       includeMountPrefixes?: string[],
+//       shadowed?: { mountPoint: string, shadowedBy: string }[],
+//       dedupDevices?: boolean }
+//
+// Omitted fields keep this OS's defaults; no argument restores them all.
+// Returns the filter now in effect.
+pub fn set_filter(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let Some(obj) = cx.argument_opt(0).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) else {
+        set_disk_filter(None);
+        return to_js(&mut cx, &disk_filter());
+    };
+
+    let mut filter = DiskFilter::default();
+    if let Some(values) = string_list(&mut cx, obj, "excludeFileSystems")? {
+        filter.exclude_file_systems = values;
+    }
+    if let Some(values) = string_list(&mut cx, obj, "excludeMountPrefixes")? {
+        filter.exclude_mount_prefixes = values;
+    }
+    if let Some(value) = flag(&mut cx, obj, "excludeRemovable")? {
+        filter.exclude_removable = value;
+    }
+    if let Some(values) = string_list(&mut cx, obj, "includeFileSystems")? {
+        filter.include_file_systems = values;
+    }
+    if let Some(values) = string_list(&mut cx, obj, "includeMountPrefixes")? {
+        filter.include_mount_prefixes = values;
+    }
+    if let Some(rules) = shadowed(&mut cx, obj)? {
+        filter.shadowed = rules;
+    }
+    if let Some(value) = flag(&mut cx, obj, "dedupDevices")? {
+        filter.dedup_devices = value;
+    }
+
+    set_disk_filter(Some(filter.clone()));
+    to_js(&mut cx, &filter)
+}
+
+// getDiskFilter() returns the filter in effect
+pub fn get_filter(mut cx: FunctionContext) -> JsResult<JsValue> {
+    to_js(&mut cx, &disk_filter())
+}
@@ -0,0 +1,56 @@
+use neon::prelude::*;
+use peep_core::{Exporter, ExporterOptions};
+use std::sync::Mutex;
+
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_PORT: u16 = 9101;
+
+lazy_static::lazy_static! {
+    static ref EXPORTER: Mutex<Option<Exporter>> = Mutex::new(None);
+}
+
+// startExporter({ host?, port?, topProcesses? }) -> "host:port" it listens on.
+// Serves Prometheus metrics at /metrics, replacing any running exporter.
+pub fn start_exporter(mut cx: FunctionContext) -> JsResult<JsString> {
+    let mut host = DEFAULT_HOST.to_string();
+    let mut port = DEFAULT_PORT;
+    let mut options = ExporterOptions::default();
+
+    if let Some(obj) = cx.argument_opt(0).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) {
+        if let Some(value) = obj.get_opt::<JsString, _, _>(&mut cx, "host")? {
+            host = value.value(&mut cx);
+        }
+        if let Some(value) = obj.get_opt::<JsNumber, _, _>(&mut cx, "port")? {
+            port = value.value(&mut cx) as u16;
+        }
+        if let Some(value) = obj.get_opt::<JsNumber, _, _>(&mut cx, "topProcesses")? {
+            options.top_processes = value.value(&mut cx) as usize;
+        }
+    }
+
+    // Release the old listener first in case it holds the same port
+    let mut exporter = EXPORTER.lock().unwrap();
+    if let Some(old) = exporter.take() {
+        old.stop();
+    }
+    match Exporter::start_with((host.as_str(), port), options, crate::replay::Feed::default()) {
+        Ok(started) => {
+            let addr = started.local_addr().to_string();
+            *exporter = Some(started);
+            Ok(cx.string(addr))
+        }
+        Err(err) => cx.throw_error(format!("Failed to start exporter on {}:{}: {}", host, port, err)),
+    }
+}
+
+// stopExporter() -> whether an exporter was running
+pub fn stop_exporter(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let stopped = match EXPORTER.lock().unwrap().take() {
+        Some(exporter) => {
+            exporter.stop();
+            true
+        }
+        None => false,
+    };
+    Ok(cx.boolean(stopped))
+}
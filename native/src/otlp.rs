@@ -0,0 +1,59 @@
+use neon::prelude::*;
+use peep_core::{OtlpExporter, OtlpOptions};
+use std::sync::Mutex;
+use std::time::Duration;
+
+lazy_static::lazy_static! {
+    static ref OTLP_EXPORTER: Mutex<Option<OtlpExporter>> = Mutex::new(None);
+}
+
+// startOtlpExporter({ endpoint?, intervalMs?, topProcesses?, headers? })
+// Pushes OTLP/HTTP JSON metrics on an interval, replacing any running pusher.
+pub fn start_otlp_exporter(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let mut options = OtlpOptions::default();
+
+    if let Some(obj) = cx.argument_opt(0).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) {
+        if let Some(value) = obj.get_opt::<JsString, _, _>(&mut cx, "endpoint")? {
+            options.endpoint = value.value(&mut cx);
+        }
+        if let Some(value) = obj.get_opt::<JsNumber, _, _>(&mut cx, "intervalMs")? {
+            options.interval = Duration::from_millis(value.value(&mut cx).max(1000.0) as u64);
+        }
+        if let Some(value) = obj.get_opt::<JsNumber, _, _>(&mut cx, "topProcesses")? {
+            options.top_processes = value.value(&mut cx) as usize;
+        }
+        if let Some(headers) = obj.get_opt::<JsObject, _, _>(&mut cx, "headers")? {
+            let names = headers.get_own_property_names(&mut cx)?.to_vec(&mut cx)?;
+            for name in names {
+                let name = name.downcast_or_throw::<JsString, _>(&mut cx)?.value(&mut cx);
+                let value = headers.get::<JsString, _, _>(&mut cx, name.as_str())?.value(&mut cx);
+                options.headers.push((name, value));
+            }
+        }
+    }
+
+    let endpoint = options.endpoint.clone();
+    let mut exporter = OTLP_EXPORTER.lock().unwrap();
+    if let Some(old) = exporter.take() {
+        old.stop();
+    }
+    match OtlpExporter::start_with(options, crate::replay::Feed::default()) {
+        Ok(started) => {
+            *exporter = Some(started);
+            Ok(cx.undefined())
+        }
+        Err(err) => cx.throw_error(format!("Failed to start OTLP exporter for {}: {}", endpoint, err)),
+    }
+}
+
+// stopOtlpExporter() -> whether an exporter was running
+pub fn stop_otlp_exporter(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let stopped = match OTLP_EXPORTER.lock().unwrap().take() {
+        Some(exporter) => {
+            exporter.stop();
+            true
+        }
+        None => false,
+    };
+    Ok(cx.boolean(stopped))
+}
@@ -0,0 +1,17 @@
+use neon::prelude::*;
+use peep_core::{parse_plan, RefreshPlan};
+
+use crate::convert::from_js;
+
+// Read an optional refresh plan argument. See peep_core::parse_plan for its
+// shape; omitted subsystems are skipped.
+pub fn plan_argument(cx: &mut FunctionContext, i: usize) -> NeonResult<Option<RefreshPlan>> {
+    let Some(plan) = cx.argument_opt(i) else {
+        return Ok(None);
+    };
+    let plan = from_js(cx, plan)?;
+    match parse_plan(&plan) {
+        Ok(plan) => Ok(plan),
+        Err(err) => cx.throw_error(err),
+    }
+}
@@ -0,0 +1,36 @@
+use neon::prelude::*;
+use peep_core::{protection_policy, set_protection_policy, ProtectionPolicy};
+
+use crate::convert::to_js;
+
+// setProtectionPolicy(policy?) replaces the patterns of processes that kill
+// and signal calls refuse without `force`:
+//
+//     { patterns?: string[] }
+//
+// Patterns are case-insensitive names, or executable paths when they contain
+// a path separator, with `*` matching anything. Init, Peep, its parent, the
+// processes it started and kernel threads are always protected. No argument
+// restores this OS's defaults. Returns the policy now in effect.
+pub fn set_policy(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let Some(obj) = cx.argument_opt(0).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) else {
+        set_protection_policy(None);
+        return to_js(&mut cx, &protection_policy());
+    };
+
+    let mut policy = ProtectionPolicy::default();
+    if let Some(array) = obj.get_opt::<JsArray, _, _>(&mut cx, "patterns")? {
+        policy.patterns.clear();
+        for value in array.to_vec(&mut cx)? {
+            policy.patterns.push(value.downcast_or_throw::<JsString, _>(&mut cx)?.value(&mut cx));
+        }
+    }
+
+    set_protection_policy(Some(policy.clone()));
+    to_js(&mut cx, &policy)
+}
+
+// getProtectionPolicy() returns the policy in effect
+pub fn get_policy(mut cx: FunctionContext) -> JsResult<JsValue> {
+    to_js(&mut cx, &protection_policy())
+}
@@ -0,0 +1,134 @@
+use neon::prelude::*;
+use peep_core::{
+    BatterySnapshot, Collector, MetricsFeed, PartialSnapshot, ProcessRecord, Recorder, Recording, RefreshPlan, Replay,
+    SystemSnapshot,
+};
+use std::sync::Mutex;
+
+// Session recording and replay. While a recording is open, everything the
+// collection exports return is appended to it. While a replay is running,
+// those same exports return the recorded snapshots instead of collecting.
+lazy_static::lazy_static! {
+    static ref RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+    static ref REPLAY: Mutex<Option<Replay>> = Mutex::new(None);
+}
+
+fn record(write: impl FnOnce(&mut Recorder) -> std::io::Result<()>) {
+    let mut recorder = RECORDER.lock().unwrap();
+    if let Some(active) = recorder.as_mut() {
+        if let Err(err) = write(active) {
+            // Stop rather than log the same failure on every sample
+            eprintln!("Failed to record sample, recording stopped: {}", err);
+            *recorder = None;
+        }
+    }
+}
+
+pub fn record_system(timestamp: u64, system: &SystemSnapshot) {
+    record(|recorder| recorder.record_system(timestamp, system));
+}
+
+pub fn record_processes(timestamp: u64, processes: &[ProcessRecord]) {
+    record(|recorder| recorder.record_processes(timestamp, processes));
+}
+
+pub fn record_battery(timestamp: u64, battery: &BatterySnapshot) {
+    record(|recorder| recorder.record_battery(timestamp, battery));
+}
+
+// The replayed counterparts of the collection exports; None when no replay
+// is running, or the recording has nothing of that kind
+pub fn system() -> Option<SystemSnapshot> {
+    REPLAY.lock().unwrap().as_ref()?.system().cloned()
+}
+
+pub fn collect(plan: &RefreshPlan) -> Option<PartialSnapshot> {
+    REPLAY.lock().unwrap().as_ref().map(|replay| replay.collect(plan))
+}
+
+pub fn processes(show_threads: bool) -> Option<Vec<ProcessRecord>> {
+    REPLAY.lock().unwrap().as_ref()?.processes(show_threads)
+}
+
+pub fn battery() -> Option<BatterySnapshot> {
+    REPLAY.lock().unwrap().as_ref()?.battery().cloned()
+}
+
+// What the Prometheus and OTLP exporters read: the running replay, or their
+// own collector when there is none
+pub struct Feed {
+    collector: Collector,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Feed { collector: Collector::new() }
+    }
+}
+
+impl MetricsFeed for Feed {
+    fn system(&mut self) -> SystemSnapshot {
+        system().unwrap_or_else(|| self.collector.system())
+    }
+
+    fn battery(&mut self) -> BatterySnapshot {
+        battery().unwrap_or_else(|| self.collector.battery())
+    }
+
+    fn processes(&mut self) -> Vec<ProcessRecord> {
+        processes(false).unwrap_or_else(|| MetricsFeed::processes(&mut self.collector))
+    }
+}
+
+// startRecording(path) starts writing a new recording to `path`, replacing
+// any recording in progress
+pub fn start_recording(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let os = peep_core::os_info();
+    match Recorder::create(&path, peep_core::now_millis(), Some(&os)) {
+        Ok(recorder) => {
+            *RECORDER.lock().unwrap() = Some(recorder);
+            Ok(cx.undefined())
+        }
+        Err(err) => cx.throw_error(format!("Failed to start recording {}: {}", path, err)),
+    }
+}
+
+// stopRecording() -> number of frames written, or null when not recording
+pub fn stop_recording(mut cx: FunctionContext) -> JsResult<JsValue> {
+    match RECORDER.lock().unwrap().take() {
+        Some(recorder) => Ok(cx.number(recorder.frames() as f64).upcast()),
+        None => Ok(cx.null().upcast()),
+    }
+}
+
+// startReplay(path, { speed?, loop? }) -> recording duration in ms.
+// `speed` of 2 plays twice as fast; `loop` starts over after the last frame.
+pub fn start_replay(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let path = cx.argument::<JsString>(0)?.value(&mut cx);
+    let mut speed = 1.0;
+    let mut looped = false;
+    if let Some(obj) = cx.argument_opt(1).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) {
+        if let Some(value) = obj.get_opt::<JsNumber, _, _>(&mut cx, "speed")? {
+            speed = value.value(&mut cx);
+        }
+        if let Some(value) = obj.get_opt::<JsBoolean, _, _>(&mut cx, "loop")? {
+            looped = value.value(&mut cx);
+        }
+    }
+
+    match Recording::open(&path) {
+        Ok(recording) => {
+            let duration = recording.duration();
+            *REPLAY.lock().unwrap() = Some(Replay::new(recording, speed, looped));
+            Ok(cx.number(duration as f64))
+        }
+        Err(err) => cx.throw_error(format!("Failed to open recording {}: {}", path, err)),
+    }
+}
+
+// stopReplay() -> whether a replay was running. Collection resumes with live data.
+pub fn stop_replay(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let stopped = REPLAY.lock().unwrap().take().is_some();
+    Ok(cx.boolean(stopped))
+}
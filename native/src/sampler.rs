@@ -0,0 +1,159 @@
+use neon::prelude::*;
+use peep_core::{Collector, SystemSnapshot};
+use std::sync::{Arc, Mutex, Condvar};
+use std::time::{Duration, Instant};
+use std::thread;
+
+use crate::convert::to_js;
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(1000);
+
+// A single subscriber: each gets pushed samples at its own interval, but all
+// of them share one sampling loop
+struct Subscription {
+    id: u32,
+    interval: Duration,
+    next_due: Instant,
+    channel: Channel,
+    callback: Arc<Root<JsFunction>>,
+}
+
+#[derive(Default)]
+struct State {
+    next_id: u32,
+    subscriptions: Vec<Subscription>,
+    running: bool,
+}
+
+// Background sampler that owns its own collector (and so its own System,
+// Networks and Disks handles) so collection never happens on the JS thread
+pub struct Sampler {
+    state: Mutex<State>,
+    wakeup: Condvar,
+}
+
+lazy_static::lazy_static! {
+    pub static ref SAMPLER: Arc<Sampler> = Arc::new(Sampler {
+        state: Mutex::new(State::default()),
+        wakeup: Condvar::new(),
+    });
+}
+
+impl Sampler {
+    pub fn subscribe(self: &Arc<Self>, interval: Duration, channel: Channel, callback: Root<JsFunction>) -> u32 {
+        let mut state = self.state.lock().unwrap();
+
+        state.next_id += 1;
+        let id = state.next_id;
+
+        // Due immediately so a new subscriber doesn't wait a full interval for its first sample
+        state.subscriptions.push(Subscription {
+            id,
+            interval,
+            next_due: Instant::now(),
+            channel,
+            callback: Arc::new(callback),
+        });
+
+        if !state.running {
+            state.running = true;
+            let sampler = Arc::clone(self);
+            thread::Builder::new()
+                .name("peep-sampler".to_string())
+                .spawn(move || sampler.run())
+                .expect("failed to spawn sampler thread");
+        }
+
+        self.wakeup.notify_one();
+        id
+    }
+
+    pub fn unsubscribe(&self, id: u32) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let before = state.subscriptions.len();
+        state.subscriptions.retain(|sub| sub.id != id);
+        let removed = state.subscriptions.len() != before;
+        self.wakeup.notify_one();
+        removed
+    }
+
+    fn run(&self) {
+        let mut collector = Collector::new();
+
+        loop {
+            // Sleep until at least one subscriber is due
+            {
+                let mut state = self.state.lock().unwrap();
+                loop {
+                    let now = Instant::now();
+                    match state.subscriptions.iter().map(|sub| sub.next_due).min() {
+                        None => state = self.wakeup.wait(state).unwrap(),
+                        Some(due) if due > now => {
+                            state = self.wakeup.wait_timeout(state, due - now).unwrap().0;
+                        }
+                        Some(_) => break,
+                    }
+                }
+            }
+
+            // Collect without holding the lock so subscribe/unsubscribe never wait on a refresh
+            let timestamp = peep_core::now_millis();
+            let sample: Arc<SystemSnapshot> = Arc::new(match crate::replay::system() {
+                Some(replayed) => replayed,
+                None => {
+                    let sample = collector.system();
+                    crate::record_sample(timestamp, &sample);
+                    sample
+                }
+            });
+
+            let now = Instant::now();
+            let mut state = self.state.lock().unwrap();
+            for sub in state.subscriptions.iter_mut().filter(|sub| sub.next_due <= now) {
+                sub.next_due = now + sub.interval;
+
+                let sample = Arc::clone(&sample);
+                let callback = Arc::clone(&sub.callback);
+                sub.channel.send(move |mut cx| {
+                    let callback = callback.to_inner(&mut cx);
+                    let obj = to_js(&mut cx, &*sample)?.downcast_or_throw::<JsObject, _>(&mut cx)?;
+                    let timestamp = cx.number(timestamp as f64);
+                    obj.set(&mut cx, "timestamp", timestamp)?;
+                    callback.call_with(&cx).arg(obj).exec(&mut cx)
+                });
+            }
+        }
+    }
+}
+
+// subscribe(options, callback) -> subscription id
+pub fn subscribe(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let options = cx.argument_opt(0);
+    let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    let mut interval = DEFAULT_INTERVAL;
+    if let Some(options) = options {
+        if let Ok(options) = options.downcast::<JsObject, _>(&mut cx) {
+            if let Some(ms) = options.get_opt::<JsNumber, _, _>(&mut cx, "intervalMs")? {
+                let ms = ms.value(&mut cx);
+                if ms.is_finite() && ms > 0.0 {
+                    interval = Duration::from_millis(ms as u64);
+                }
+            }
+        }
+    }
+    // CPU usage is meaningless when refreshed faster than sysinfo's minimum interval
+    let interval = interval.max(peep_core::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let channel = cx.channel();
+    let id = SAMPLER.subscribe(interval, channel, callback);
+
+    Ok(cx.number(id))
+}
+
+// unsubscribe(id) -> whether a subscription was removed
+pub fn unsubscribe(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let removed = SAMPLER.unsubscribe(id);
+    Ok(cx.boolean(removed))
+}
@@ -0,0 +1,96 @@
+use neon::prelude::*;
+use peep_core::{Store, StoreOptions, SystemSnapshot};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::task::fallible_promise;
+
+// The on-disk store, once opened. Sampled snapshots are appended to it from
+// the same places that feed the in-memory history.
+lazy_static::lazy_static! {
+    static ref STORE: Mutex<Option<Store>> = Mutex::new(None);
+}
+
+// Persist a snapshot if a store is open. Write failures are logged rather
+// than surfaced, since sampling must keep going regardless.
+pub fn append(timestamp: u64, snapshot: &SystemSnapshot) {
+    if let Some(store) = STORE.lock().unwrap().as_mut() {
+        if let Err(err) = store.append(timestamp, snapshot) {
+            eprintln!("Failed to persist sample: {}", err);
+        }
+    }
+}
+
+fn duration_option(cx: &mut FunctionContext, options: Handle<JsObject>, key: &str) -> NeonResult<Option<Duration>> {
+    let ms = options.get_opt::<JsNumber, _, _>(cx, key)?.map(|ms| ms.value(cx));
+    Ok(ms.filter(|ms| ms.is_finite() && *ms > 0.0).map(|ms| Duration::from_millis(ms as u64)))
+}
+
+// openStore(directory, options?) -> Promise<void> opens (or creates) the
+// store, replacing any store that was already open. Options are in ms:
+// minIntervalMs, segmentSpanMs, retentionMs, compactAfterMs and
+// compactStepMs, plus maxSegmentBytes. Opening reads the whole store back,
+// so it happens off the main thread.
+pub fn open_store(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let dir = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    let mut options = StoreOptions::default();
+    if let Some(obj) = cx.argument_opt(1).and_then(|v| v.downcast::<JsObject, _>(&mut cx).ok()) {
+        if let Some(d) = duration_option(&mut cx, obj, "minIntervalMs")? {
+            options.min_interval = d;
+        }
+        if let Some(d) = duration_option(&mut cx, obj, "segmentSpanMs")? {
+            options.segment_span = d;
+        }
+        if let Some(d) = duration_option(&mut cx, obj, "retentionMs")? {
+            options.retention = d;
+        }
+        if let Some(d) = duration_option(&mut cx, obj, "compactAfterMs")? {
+            options.compact_after = d;
+        }
+        if let Some(d) = duration_option(&mut cx, obj, "compactStepMs")? {
+            options.compact_step = d;
+        }
+        if let Some(bytes) = obj.get_opt::<JsNumber, _, _>(&mut cx, "maxSegmentBytes")? {
+            let bytes = bytes.value(&mut cx);
+            if bytes.is_finite() && bytes > 0.0 {
+                options.max_segment_bytes = bytes as u64;
+            }
+        }
+    }
+
+    fallible_promise(&mut cx, move || {
+        // Drop the old store first so its active segment is synced before
+        // reopening. Samples taken while opening aren't persisted, but
+        // sampling isn't held up behind the lock either.
+        STORE.lock().unwrap().take();
+        let opened = Store::open(&dir, options).map_err(|err| format!("Failed to open store at {}: {}", dir, err))?;
+        *STORE.lock().unwrap() = Some(opened);
+        Ok(())
+    })
+}
+
+// closeStore() flushes and closes the store; sampling continues without it
+pub fn close_store(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    STORE.lock().unwrap().take();
+    Ok(cx.undefined())
+}
+
+// queryStore(from?, to?) -> Promise<[{ timestamp, cpu, memory, disk, network }]>
+// Resolves with stored samples in the same shape as getSystemInfo.
+pub fn query_store(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let from = crate::optional_number(&mut cx, 0).unwrap_or(0.0) as u64;
+    let to = crate::optional_number(&mut cx, 1)
+        .map(|to| to as u64)
+        .unwrap_or_else(peep_core::now_millis);
+
+    fallible_promise(&mut cx, move || {
+        // Only pick the segments under the lock so appends aren't held up
+        // while they're read
+        let query = STORE.lock().unwrap().as_ref().map(|store| store.prepare_query(from, to));
+        match query {
+            Some(query) => query.run().map_err(|err| err.to_string()),
+            None => Err("Store is not open".to_string()),
+        }
+    })
+}
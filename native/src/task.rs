@@ -0,0 +1,80 @@
+use neon::prelude::*;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::convert::to_js;
+
+// Tracks the newest request made to one export. Older requests that are
+// still queued (or still running) when a newer one arrives are superseded:
+// they skip their work, or discard their result, and reject instead.
+pub struct Latest(AtomicU64);
+
+impl Latest {
+    pub const fn new() -> Self {
+        Latest(AtomicU64::new(0))
+    }
+
+    fn next(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    fn is_current(&self, ticket: u64) -> bool {
+        self.0.load(Ordering::SeqCst) == ticket
+    }
+}
+
+// Run `work` on the libuv thread pool and resolve the returned promise with its result
+pub fn promise<'a, T, F>(cx: &mut FunctionContext<'a>, work: F) -> JsResult<'a, JsPromise>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let promise = cx
+        .task(work)
+        .promise(|mut cx, value| to_js(&mut cx, &value));
+    Ok(promise)
+}
+
+// Like `promise`, but rejects with the error message when `work` fails
+pub fn fallible_promise<'a, T, F>(cx: &mut FunctionContext<'a>, work: F) -> JsResult<'a, JsPromise>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    let promise = cx
+        .task(work)
+        .promise(|mut cx, result| match result {
+            Ok(value) => to_js(&mut cx, &value),
+            Err(err) => cx.throw_error(err),
+        });
+    Ok(promise)
+}
+
+// Like `promise`, but rejects with `code: "superseded"` if a newer request to
+// the same export arrives before this one has finished
+pub fn latest_promise<'a, T, F>(cx: &mut FunctionContext<'a>, latest: &'static Latest, work: F) -> JsResult<'a, JsPromise>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let ticket = latest.next();
+    let promise = cx
+        .task(move || {
+            // Don't start work that has already been superseded while queued
+            if !latest.is_current(ticket) {
+                return None;
+            }
+            let value = work();
+            latest.is_current(ticket).then_some(value)
+        })
+        .promise(|mut cx, value| match value {
+            Some(value) => to_js(&mut cx, &value),
+            None => {
+                let err = cx.error("Superseded by a newer request")?;
+                let code = cx.string("superseded");
+                err.set(&mut cx, "code", code)?;
+                cx.throw(err)
+            }
+        });
+    Ok(promise)
+}
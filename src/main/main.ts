@@ -1,5 +1,6 @@
 import { app, BrowserWindow, ipcMain } from 'electron';
 import * as path from 'path';
+import type { ProcessTarget, ProtectionPolicy } from '../shared/types';
 
 // Import the native module
 let native: any;
@@ -43,6 +44,54 @@ function createWindow() {
 }
 
 app.whenReady().then(() => {
+  // Persist sampled metrics so history survives restarts. Opening reads the
+  // stored samples back, so it isn't awaited.
+  native?.openStore(path.join(app.getPath('userData'), 'metrics')).catch((error: unknown) => {
+    console.error('Failed to open metrics store:', error);
+  });
+
+  // Opt-in Prometheus exporter, e.g. PEEP_METRICS_PORT=9101
+  if (process.env.PEEP_METRICS_PORT) {
+    try {
+      const address = native?.startExporter({
+        host: process.env.PEEP_METRICS_HOST,
+        port: Number(process.env.PEEP_METRICS_PORT),
+      });
+      console.log(`Serving Prometheus metrics on http://${address}/metrics`);
+    } catch (error) {
+      console.error('Failed to start metrics exporter:', error);
+    }
+  }
+
+  // Opt-in OTLP push, e.g. PEEP_OTLP_ENDPOINT=http://localhost:4318
+  if (process.env.PEEP_OTLP_ENDPOINT) {
+    try {
+      native?.startOtlpExporter({
+        endpoint: process.env.PEEP_OTLP_ENDPOINT,
+        intervalMs: process.env.PEEP_OTLP_INTERVAL_MS ? Number(process.env.PEEP_OTLP_INTERVAL_MS) : undefined,
+      });
+      console.log(`Pushing OTLP metrics to ${process.env.PEEP_OTLP_ENDPOINT}`);
+    } catch (error) {
+      console.error('Failed to start OTLP exporter:', error);
+    }
+  }
+
+  // Session capture for bug reports: PEEP_RECORD=session.jsonl records what
+  // Peep collects, PEEP_REPLAY=session.jsonl shows a recording instead of
+  // this machine (PEEP_REPLAY_SPEED=10 plays it ten times faster)
+  try {
+    if (process.env.PEEP_REPLAY) {
+      native?.startReplay(process.env.PEEP_REPLAY, {
+        speed: process.env.PEEP_REPLAY_SPEED ? Number(process.env.PEEP_REPLAY_SPEED) : undefined,
+        loop: true,
+      });
+    } else if (process.env.PEEP_RECORD) {
+      native?.startRecording(process.env.PEEP_RECORD);
+    }
+  } catch (error) {
+    console.error('Failed to start session recording or replay:', error);
+  }
+
   createWindow();
 
   app.on('activate', () => {
@@ -52,14 +101,27 @@ app.whenReady().then(() => {
   });
 });
 
+app.on('will-quit', () => {
+  native?.stopExporter();
+  native?.stopOtlpExporter();
+  native?.stopRecording();
+  native?.closeStore();
+});
+
 app.on('window-all-closed', () => {
   if (process.platform !== 'darwin') {
     app.quit();
   }
 });
 
+// Async native calls reject with code 'superseded' when a newer call to the
+// same export overtakes them; the caller simply skips that update
+function isSuperseded(error: any): boolean {
+  return error?.code === 'superseded';
+}
+
 // IPC handlers for system monitoring
-ipcMain.handle('get-system-info', async () => {
+ipcMain.handle('get-system-info', async (_event, plan?: object) => {
   try {
     if (!native) {
       return {
@@ -70,23 +132,88 @@ ipcMain.handle('get-system-info', async () => {
       };
     }
     
-    const systemInfo = native.getSystemInfo();
+    const systemInfo = await native.getSystemInfoAsync(plan);
     return systemInfo;
   } catch (error) {
-    console.error('Error getting system info:', error);
+    if (!isSuperseded(error)) {
+      console.error('Error getting system info:', error);
+    }
     return null;
   }
 });
 
+// Push samples from the native sampler thread to subscribed windows.
+// All windows share one sampling loop; each keeps its own subscription id.
+const sampleSubscriptions = new Map<number, number>();
+
+function unsubscribeSystemInfo(webContentsId: number) {
+  const id = sampleSubscriptions.get(webContentsId);
+  if (id !== undefined) {
+    native?.unsubscribe(id);
+    sampleSubscriptions.delete(webContentsId);
+  }
+}
+
+ipcMain.on('subscribe-system-info', (event, intervalMs: number = 2000) => {
+  if (!native) {
+    return;
+  }
+
+  const sender = event.sender;
+  unsubscribeSystemInfo(sender.id);
+
+  const id = native.subscribe({ intervalMs }, (sample: any) => {
+    if (!sender.isDestroyed()) {
+      sender.send('system-info', sample);
+    }
+  });
+  sampleSubscriptions.set(sender.id, id);
+
+  sender.once('destroyed', () => unsubscribeSystemInfo(sender.id));
+});
+
+ipcMain.on('unsubscribe-system-info', (event) => {
+  unsubscribeSystemInfo(event.sender.id);
+});
+
+ipcMain.handle('get-history', async (_event, metric: string, from?: number, to?: number, resolution?: number) => {
+  try {
+    if (!native) {
+      return [];
+    }
+
+    return native.getHistory(metric, from, to, resolution);
+  } catch (error) {
+    console.error('Error getting history:', error);
+    return [];
+  }
+});
+
+ipcMain.handle('query-store', async (_event, from?: number, to?: number) => {
+  try {
+    if (!native) {
+      return [];
+    }
+
+    return await native.queryStore(from, to);
+  } catch (error) {
+    console.error('Error querying stored samples:', error);
+    return [];
+  }
+});
+
 ipcMain.handle('get-os-info', async () => {
   try {
     if (!native) {
       return { name: 'Unknown', version: 'Unknown', kernelVersion: 'Unknown', hostname: 'Unknown' };
     }
     
-    const osInfo = native.getOsInfo();
+    const osInfo = await native.getOsInfoAsync();
     return osInfo;
   } catch (error) {
+    if (isSuperseded(error)) {
+      return null;
+    }
     console.error('Error getting OS info:', error);
     return { name: 'Unknown', version: 'Unknown', kernelVersion: 'Unknown', hostname: 'Unknown' };
   }
@@ -98,9 +225,12 @@ ipcMain.handle('get-battery-info', async () => {
       return { available: false };
     }
     
-    const batteryInfo = native.getBatteryInfo();
+    const batteryInfo = await native.getBatteryInfoAsync();
     return batteryInfo;
   } catch (error) {
+    if (isSuperseded(error)) {
+      return null;
+    }
     console.error('Error getting battery info:', error);
     return { available: false };
   }
@@ -112,11 +242,12 @@ ipcMain.handle('get-processes', async (_event, showThreads: boolean = false) =>
       return [];
     }
     
-    const processes = native.getProcesses(showThreads);
-    
     // Calculate memory percentage based on total system memory
-    const systemInfo = native.getSystemInfo();
-    const totalMemory = systemInfo.memory.total;
+    const [processes, memoryInfo] = await Promise.all([
+      native.getProcessesAsync(showThreads),
+      native.getMemoryInfoAsync(),
+    ]);
+    const totalMemory = memoryInfo.total;
     
     return processes.map((proc: any) => ({
       ...proc,
@@ -124,22 +255,179 @@ ipcMain.handle('get-processes', async (_event, showThreads: boolean = false) =>
       memoryPercentage: totalMemory > 0 ? (proc.memory / totalMemory) * 100 : 0,
     }));
   } catch (error) {
+    if (isSuperseded(error)) {
+      return null;
+    }
     console.error('Error getting processes:', error);
     return [];
   }
 });
 
-ipcMain.handle('kill-process', async (_event, pid: number) => {
+ipcMain.handle('get-process-tree', async () => {
+  try {
+    if (!native) {
+      return [];
+    }
+
+    const [tree, memoryInfo] = await Promise.all([
+      native.getProcessTreeAsync(),
+      native.getMemoryInfoAsync(),
+    ]);
+    const totalMemory = memoryInfo.total;
+    const withMemory = (node: any): any => ({
+      ...node,
+      memoryBytes: node.memory,
+      memoryPercentage: totalMemory > 0 ? (node.memory / totalMemory) * 100 : 0,
+      children: node.children.map(withMemory),
+    });
+
+    return tree.map(withMemory);
+  } catch (error) {
+    if (isSuperseded(error)) {
+      return null;
+    }
+    console.error('Error getting process tree:', error);
+    return [];
+  }
+});
+
+const targetPid = (target: ProcessTarget): number => (typeof target === 'number' ? target : target.pid);
+
+ipcMain.handle('kill-process', async (_event, target: ProcessTarget, force?: boolean) => {
   try {
     if (!native) {
-      return { success: false, message: 'Native module not loaded' };
+      return { success: false, code: 'failed', message: 'Native module not loaded' };
     }
     
-    const result = native.killProcess(pid);
+    const result = await native.killProcessAsync(target, force);
     return result;
   } catch (error) {
     console.error('Error killing process:', error);
-    return { success: false, message: 'Failed to kill process' };
+    return { success: false, code: 'failed', message: 'Failed to kill process' };
   }
 });
 
+ipcMain.handle('send-signal', async (_event, target: ProcessTarget, signal: string, force?: boolean) => {
+  try {
+    if (!native) {
+      return { success: false, code: 'failed', message: 'Native module not loaded' };
+    }
+    return await native.sendSignal(target, signal, force);
+  } catch (error) {
+    console.error('Error sending signal:', error);
+    return { success: false, code: 'failed', message: `Failed to send ${signal}` };
+  }
+});
+
+ipcMain.handle('terminate-gracefully', async (_event, target: ProcessTarget, timeoutMs?: number, force?: boolean) => {
+  try {
+    if (!native) {
+      return { success: false, code: 'failed', message: 'Native module not loaded' };
+    }
+    return await native.terminateGracefully(target, timeoutMs, force);
+  } catch (error) {
+    console.error('Error terminating process:', error);
+    return { success: false, code: 'failed', message: 'Failed to terminate process' };
+  }
+});
+
+ipcMain.handle('signal-process-tree', async (_event, target: ProcessTarget, signal?: string, order?: string, force?: boolean) => {
+  try {
+    return native ? await native.signalProcessTree(target, signal, order, force) : [];
+  } catch (error) {
+    console.error('Error signalling process tree:', error);
+    return [{ pid: targetPid(target), name: '', success: false, code: 'failed', message: String(error) }];
+  }
+});
+
+ipcMain.handle('signal-process-group', async (_event, target: ProcessTarget, signal?: string, scope?: string, force?: boolean) => {
+  try {
+    return native ? await native.signalProcessGroup(target, signal, scope, force) : [];
+  } catch (error) {
+    console.error('Error signalling process group:', error);
+    return [{ pid: targetPid(target), name: '', success: false, code: 'failed', message: String(error) }];
+  }
+});
+
+
+ipcMain.handle('set-disk-filter', async (_event, filter?: object) => {
+  try {
+    return native ? native.setDiskFilter(filter) : null;
+  } catch (error) {
+    console.error('Error setting disk filter:', error);
+    return null;
+  }
+});
+
+ipcMain.handle('get-disk-filter', async () => {
+  return native ? native.getDiskFilter() : null;
+});
+
+// Only `{ patterns?: string[] }` is accepted. Patterns can only add to what's
+// protected: Peep, its parent, the processes it started, init and kernel
+// threads stay protected in the native module whatever the renderer sends.
+const isProtectionPolicy = (policy: unknown): policy is Partial<ProtectionPolicy> => {
+  if (typeof policy !== 'object' || policy === null || Array.isArray(policy)) {
+    return false;
+  }
+  const { patterns, ...rest } = policy as Record<string, unknown>;
+  return Object.keys(rest).length === 0
+    && (patterns === undefined
+      || (Array.isArray(patterns) && patterns.every((pattern) => typeof pattern === 'string' && pattern.length > 0)));
+};
+
+ipcMain.handle('set-protection-policy', async (_event, policy?: unknown) => {
+  try {
+    if (policy !== undefined && !isProtectionPolicy(policy)) {
+      console.error('Invalid protection policy:', policy);
+      return null;
+    }
+    return native ? native.setProtectionPolicy(policy) : null;
+  } catch (error) {
+    console.error('Error setting protection policy:', error);
+    return null;
+  }
+});
+
+ipcMain.handle('get-protection-policy', async () => {
+  return native ? native.getProtectionPolicy() : null;
+});
+
+ipcMain.handle('get-connections', async (_event, filter?: object) => {
+  try {
+    return native ? await native.getConnections(filter) : [];
+  } catch (error) {
+    console.error('Error getting connections:', error);
+    return [];
+  }
+});
+
+ipcMain.handle('get-listening-ports', async () => {
+  try {
+    return native ? await native.getListeningPorts() : [];
+  } catch (error) {
+    console.error('Error getting listening ports:', error);
+    return [];
+  }
+});
+
+ipcMain.handle('find-process-by-port', async (_event, port: number, proto?: string) => {
+  try {
+    return native ? await native.findProcessByPort(port, proto) : null;
+  } catch (error) {
+    console.error('Error finding process by port:', error);
+    return null;
+  }
+});
+
+ipcMain.handle('kill-process-by-port', async (_event, port: number, proto?: string, force?: boolean) => {
+  try {
+    if (!native) {
+      return { success: false, code: 'failed', message: 'Native module not loaded' };
+    }
+    return await native.killProcessByPort(port, proto, force);
+  } catch (error) {
+    console.error('Error killing process by port:', error);
+    return { success: false, code: 'failed', message: 'Failed to kill process' };
+  }
+});
@@ -1,11 +1,47 @@
-import { contextBridge, ipcRenderer } from 'electron';
-import type { SystemInfo, ProcessInfo, BatteryInfo, OsInfo, KillProcessResult } from '../shared/types';
+import { contextBridge, ipcRenderer, IpcRendererEvent } from 'electron';
+import type { SystemInfo, ProcessInfo, ProcessTreeNode, BatteryInfo, OsInfo, KillProcessResult, HistoryMetric, HistoryResolution, HistoryPoint, StoredSample, RefreshPlan, DiskFilter, Connection, ConnectionFilter, ListeningPort, ProcessSignal, ProcessTarget, ProtectionPolicy, TerminateResult, SignalOutcome } from '../shared/types';
 
 contextBridge.exposeInMainWorld('electronAPI', {
-  getSystemInfo: (): Promise<SystemInfo | null> => ipcRenderer.invoke('get-system-info'),
-  getProcesses: (showThreads?: boolean): Promise<ProcessInfo[]> => ipcRenderer.invoke('get-processes', showThreads),
-  getBatteryInfo: (): Promise<BatteryInfo> => ipcRenderer.invoke('get-battery-info'),
-  getOsInfo: (): Promise<OsInfo> => ipcRenderer.invoke('get-os-info'),
-  killProcess: (pid: number): Promise<KillProcessResult> =>
-    ipcRenderer.invoke('kill-process', pid),
+  getSystemInfo: (plan?: RefreshPlan): Promise<SystemInfo | null> => ipcRenderer.invoke('get-system-info', plan),
+  onSystemInfo: (callback: (info: SystemInfo) => void, intervalMs?: number): (() => void) => {
+    const listener = (_event: IpcRendererEvent, info: SystemInfo) => callback(info);
+    ipcRenderer.on('system-info', listener);
+    ipcRenderer.send('subscribe-system-info', intervalMs);
+    return () => {
+      ipcRenderer.removeListener('system-info', listener);
+      ipcRenderer.send('unsubscribe-system-info');
+    };
+  },
+  getHistory: (metric: HistoryMetric, from?: number, to?: number, resolution?: HistoryResolution): Promise<HistoryPoint[]> =>
+    ipcRenderer.invoke('get-history', metric, from, to, resolution),
+  getStoredSamples: (from?: number, to?: number): Promise<StoredSample[]> =>
+    ipcRenderer.invoke('query-store', from, to),
+  getProcesses: (showThreads?: boolean): Promise<ProcessInfo[] | null> => ipcRenderer.invoke('get-processes', showThreads),
+  getProcessTree: (): Promise<ProcessTreeNode[] | null> => ipcRenderer.invoke('get-process-tree'),
+  getBatteryInfo: (): Promise<BatteryInfo | null> => ipcRenderer.invoke('get-battery-info'),
+  getOsInfo: (): Promise<OsInfo | null> => ipcRenderer.invoke('get-os-info'),
+  killProcess: (target: ProcessTarget, force?: boolean): Promise<KillProcessResult> =>
+    ipcRenderer.invoke('kill-process', target, force),
+  sendSignal: (target: ProcessTarget, signal: ProcessSignal, force?: boolean): Promise<KillProcessResult> =>
+    ipcRenderer.invoke('send-signal', target, signal, force),
+  terminateGracefully: (target: ProcessTarget, timeoutMs?: number, force?: boolean): Promise<TerminateResult> =>
+    ipcRenderer.invoke('terminate-gracefully', target, timeoutMs, force),
+  signalProcessTree: (
+    target: ProcessTarget, signal?: ProcessSignal, order?: 'bottomUp' | 'topDown', force?: boolean
+  ): Promise<SignalOutcome[]> => ipcRenderer.invoke('signal-process-tree', target, signal, order, force),
+  signalProcessGroup: (
+    target: ProcessTarget, signal?: ProcessSignal, scope?: 'group' | 'session', force?: boolean
+  ): Promise<SignalOutcome[]> => ipcRenderer.invoke('signal-process-group', target, signal, scope, force),
+  setProtectionPolicy: (policy?: Partial<ProtectionPolicy>): Promise<ProtectionPolicy | null> =>
+    ipcRenderer.invoke('set-protection-policy', policy),
+  getProtectionPolicy: (): Promise<ProtectionPolicy | null> => ipcRenderer.invoke('get-protection-policy'),
+  setDiskFilter: (filter?: Partial<DiskFilter>): Promise<DiskFilter | null> =>
+    ipcRenderer.invoke('set-disk-filter', filter),
+  getDiskFilter: (): Promise<DiskFilter | null> => ipcRenderer.invoke('get-disk-filter'),
+  getConnections: (filter?: ConnectionFilter): Promise<Connection[]> => ipcRenderer.invoke('get-connections', filter),
+  getListeningPorts: (): Promise<ListeningPort[]> => ipcRenderer.invoke('get-listening-ports'),
+  findProcessByPort: (port: number, proto?: 'tcp' | 'udp'): Promise<ListeningPort | null> =>
+    ipcRenderer.invoke('find-process-by-port', port, proto),
+  killProcessByPort: (port: number, proto?: 'tcp' | 'udp', force?: boolean): Promise<KillProcessResult> =>
+    ipcRenderer.invoke('kill-process-by-port', port, proto, force),
 });
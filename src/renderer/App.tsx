@@ -29,11 +29,6 @@ const App: React.FC = () => {
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
   const [showThreads, setShowThreads] = useState(false);
-  
-  // Track previous values for network rate calculation
-  const [prevNetworkRx, setPrevNetworkRx] = useState<number>(0);
-  const [prevNetworkTx, setPrevNetworkTx] = useState<number>(0);
-  const [prevTimestamp, setPrevTimestamp] = useState<number>(Date.now());
 
   const [history, setHistory] = useState<HistoricalData[]>(() =>
     Array.from({ length: 900 }, (_, i) => ({
@@ -53,7 +48,9 @@ const App: React.FC = () => {
     const fetchOsInfo = async () => {
       try {
         const info = await window.electronAPI.getOsInfo();
-        setOsInfo(info);
+        if (info) {
+          setOsInfo(info);
+        }
       } catch (err) {
         console.error('Failed to fetch OS info:', err);
       }
@@ -61,65 +58,52 @@ const App: React.FC = () => {
     fetchOsInfo();
   }, []);
 
+  useEffect(() => {
+    // System info is pushed from the native sampler; no polling needed
+    const unsubscribe = window.electronAPI.onSystemInfo((info) => {
+      setLoading(false);
+
+      const currentTimestamp = info.timestamp ?? Date.now();
+      setSystemInfo(info);
+
+      // Add to history (keep last 30 minutes at 2-second intervals = 900 data points)
+      const newDataPoint: HistoricalData = {
+        timestamp: currentTimestamp,
+        cpu: info.cpu?.usage ?? 0,
+        perCore: info.cpu?.perCore ?? [],
+        memory: info.memory ? (info.memory.used / info.memory.total) * 100 : 0,
+        swap: info.memory && info.memory.totalSwap > 0 ? (info.memory.usedSwap / info.memory.totalSwap) * 100 : 0,
+        diskRead: info.disk?.readRate ?? 0,
+        diskWrite: info.disk?.writeRate ?? 0,
+        networkRx: info.network?.rxRate ?? 0,
+        networkTx: info.network?.txRate ?? 0,
+      };
+
+      setHistory((prev) => {
+        const updated = [...prev, newDataPoint];
+        // Keep only last 900 data points (30 minutes)
+        return updated.slice(-900);
+      });
+    }, 2000);
+
+    return unsubscribe;
+  }, []);
+
   useEffect(() => {
     const fetchData = async () => {
       try {
-        const [info, procs, battery] = await Promise.all([
-          window.electronAPI.getSystemInfo(),
+        const [procs, battery] = await Promise.all([
           window.electronAPI.getProcesses(showThreads),
           window.electronAPI.getBatteryInfo(),
         ]);
 
-        setSystemInfo(info);
-        setProcesses(procs);
-        setBatteryInfo(battery);
-        setLoading(false);
-
-        // Network still needs rate calculation (cumulative totals)
-        const currentTimestamp = Date.now();
-        const timeDelta = (currentTimestamp - prevTimestamp) / 1000; // seconds
-        
-        const networkRx = info?.network?.rx ?? 0;
-        const networkTx = info?.network?.tx ?? 0;
-        
-        // Calculate network bytes per second
-        const networkRxRate = timeDelta > 0 ? (networkRx - prevNetworkRx) / timeDelta : 0;
-        const networkTxRate = timeDelta > 0 ? (networkTx - prevNetworkTx) / timeDelta : 0;
-        
-        // Update previous values
-        setPrevNetworkRx(networkRx);
-        setPrevNetworkTx(networkTx);
-        setPrevTimestamp(currentTimestamp);
-        
-        // Update systemInfo with network rates (disk values are already incremental)
-        const updatedInfo = {
-          ...info,
-          network: {
-            ...info?.network,
-            rx: Math.max(0, networkRxRate),
-            tx: Math.max(0, networkTxRate),
-          },
-        };
-        setSystemInfo(updatedInfo);
-
-        // Add to history (keep last 30 minutes at 2-second intervals = 900 data points)
-        const newDataPoint: HistoricalData = {
-          timestamp: currentTimestamp,
-          cpu: info?.cpu?.usage ?? 0,
-          perCore: info?.cpu?.perCore ?? [],
-          memory: info?.memory ? (info.memory.used / info.memory.total) * 100 : 0,
-          swap: info?.memory && info.memory.totalSwap > 0 ? (info.memory.usedSwap / info.memory.totalSwap) * 100 : 0,
-          diskRead: info?.disk?.read ?? 0,
-          diskWrite: info?.disk?.write ?? 0,
-          networkRx: Math.max(0, networkRxRate),
-          networkTx: Math.max(0, networkTxRate),
-        };
-
-        setHistory((prev) => {
-          const updated = [...prev, newDataPoint];
-          // Keep only last 900 data points (30 minutes)
-          return updated.slice(-900);
-        });
+        // null means a newer request superseded this one; keep the current data
+        if (procs) {
+          setProcesses(procs);
+        }
+        if (battery) {
+          setBatteryInfo(battery);
+        }
       } catch (err) {
         setError("Failed to fetch system information");
         setLoading(false);
@@ -293,10 +277,10 @@ const App: React.FC = () => {
           processes={processes}
           showThreads={showThreads}
           onToggleThreads={() => setShowThreads(!showThreads)}
-          onKillProcess={async (pid) => {
-            const result = await window.electronAPI.killProcess(pid);
+          onKillProcess={async (identity, force) => {
+            const result = await window.electronAPI.killProcess(identity, force);
             if (result.success) {
-              setProcesses(processes.filter((p) => p.pid !== pid));
+              setProcesses(processes.filter((p) => p.pid !== identity.pid));
             }
             return result;
           }}
@@ -13,8 +13,8 @@ interface DiskMonitorProps {
 }
 
 const DiskMonitor: React.FC<DiskMonitorProps> = ({ data, history = [] }) => {
-  const read = data?.read ?? 0;
-  const write = data?.write ?? 0;
+  const read = data?.readRate ?? 0;
+  const write = data?.writeRate ?? 0;
   const disks = data?.disks ?? [];
 
   // Get last 5 minutes of data (150 data points at 2-second intervals)
@@ -2,21 +2,32 @@ import React, { memo, useMemo, useState, useEffect } from 'react';
 import { Line } from 'react-chartjs-2';
 import '../chartConfig';
 import { formatBytes, formatStorage } from '../../shared/utils';
+import type { NetworkInterface } from '../../shared/types';
 
-interface NetworkInterface {
-  name: string;
-  type: string;
-  received: number;
-  transmitted: number;
-  packetsReceived: number;
-  packetsTransmitted: number;
-}
+// "up · 1 Gb/s full duplex · MTU 1500", leaving out whatever isn't known
+const linkSummary = (iface: NetworkInterface): string => {
+  const parts: string[] = [];
+  if (iface.state) {
+    parts.push(iface.state);
+  }
+  if (iface.speedMbps) {
+    const speed = iface.speedMbps >= 1000 ? `${iface.speedMbps / 1000} Gb/s` : `${iface.speedMbps} Mb/s`;
+    parts.push(iface.duplex ? `${speed} ${iface.duplex} duplex` : speed);
+  }
+  if (iface.mtu) {
+    parts.push(`MTU ${iface.mtu}`);
+  }
+  return parts.join(' · ');
+};
 
 interface NetworkMonitorProps {
   data?: {
     rx: number;
     tx: number;
+    rxRate?: number;
+    txRate?: number;
     interfaces?: NetworkInterface[];
+    virtualInterfaces?: NetworkInterface[];
   };
   history?: Array<{
     timestamp: number;
@@ -26,9 +37,12 @@ interface NetworkMonitorProps {
 }
 
 const NetworkMonitor: React.FC<NetworkMonitorProps> = ({ data, history = [] }) => {
-  const rx = data?.rx ?? 0;
-  const tx = data?.tx ?? 0;
+  const rx = data?.rxRate ?? 0;
+  const tx = data?.txRate ?? 0;
   const incomingInterfaces = data?.interfaces ?? [];
+  // Tunnels, bridges and the like, listed apart from the physical interfaces
+  const virtualInterfaces = data?.virtualInterfaces ?? [];
+  const [showVirtual, setShowVirtual] = useState(false);
 
   // Track interfaces that have ever had traffic (persist even when traffic stops)
   const [persistedInterfaces, setPersistedInterfaces] = useState<Map<string, NetworkInterface>>(new Map());
@@ -55,6 +69,38 @@ const NetworkMonitor: React.FC<NetworkMonitorProps> = ({ data, history = [] }) =
   // Convert map to array for rendering
   const interfaces = Array.from(persistedInterfaces.values());
 
+  const renderInterface = (iface: NetworkInterface) => (
+    <div key={iface.name} style={{ marginBottom: '12px', fontSize: '12px' }}>
+      <div style={{ color: 'var(--color-text-primary)', fontWeight: '500', marginBottom: '2px' }}>
+        {iface.name}
+      </div>
+      <div style={{ color: 'var(--color-text-secondary)', fontSize: '10px', marginBottom: '4px', opacity: 0.8 }}>
+        {iface.type}
+      </div>
+      {(iface.addresses?.length || iface.macAddress) && (
+        <div style={{ color: 'var(--color-text-secondary)', fontSize: '10px', marginBottom: '4px', opacity: 0.8 }}>
+          {iface.addresses?.map((a) => (
+            <div key={a.address}>{a.address}/{a.prefix}</div>
+          ))}
+          {iface.macAddress && <div>{iface.macAddress}</div>}
+        </div>
+      )}
+      {linkSummary(iface) && (
+        <div style={{ color: 'var(--color-text-secondary)', fontSize: '10px', marginBottom: '4px', opacity: 0.8 }}>
+          {linkSummary(iface)}
+        </div>
+      )}
+      <div style={{ display: 'flex', justifyContent: 'space-between', color: 'var(--color-text-secondary)', fontSize: '11px' }}>
+        <span>RX: {formatStorage(iface.received)}</span>
+        <span>TX: {formatStorage(iface.transmitted)}</span>
+      </div>
+      <div style={{ display: 'flex', justifyContent: 'space-between', color: 'var(--color-text-secondary)', fontSize: '11px', opacity: 0.7 }}>
+        <span>↓ {iface.packetsReceived.toLocaleString()} pkts</span>
+        <span>↑ {iface.packetsTransmitted.toLocaleString()} pkts</span>
+      </div>
+    </div>
+  );
+
   // Get last 5 minutes of data (150 data points at 2-second intervals)
   const recentHistory = useMemo(() => {
     return history.slice(-150);
@@ -160,24 +206,22 @@ const NetworkMonitor: React.FC<NetworkMonitorProps> = ({ data, history = [] }) =
         
           <div>
             <h3 style={{ fontSize: '14px', marginBottom: '10px', color: 'var(--color-text-primary)', marginTop: 0 }}>Network Interfaces</h3>
-            {interfaces.map((iface, index) => (
-              <div key={index} style={{ marginBottom: '12px', fontSize: '12px' }}>
-                <div style={{ color: 'var(--color-text-primary)', fontWeight: '500', marginBottom: '2px' }}>
-                  {iface.name}
-                </div>
-                <div style={{ color: 'var(--color-text-secondary)', fontSize: '10px', marginBottom: '4px', opacity: 0.8 }}>
-                  {iface.type}
-                </div>
-                <div style={{ display: 'flex', justifyContent: 'space-between', color: 'var(--color-text-secondary)', fontSize: '11px' }}>
-                  <span>RX: {formatStorage(iface.received)}</span>
-                  <span>TX: {formatStorage(iface.transmitted)}</span>
-                </div>
-                <div style={{ display: 'flex', justifyContent: 'space-between', color: 'var(--color-text-secondary)', fontSize: '11px', opacity: 0.7 }}>
-                  <span>↓ {iface.packetsReceived.toLocaleString()} pkts</span>
-                  <span>↑ {iface.packetsTransmitted.toLocaleString()} pkts</span>
-                </div>
-              </div>
-            ))}
+            {interfaces.map(renderInterface)}
+            {virtualInterfaces.length > 0 && (
+              <label style={{ display: 'flex', alignItems: 'center', cursor: 'pointer', fontSize: '12px', gap: '8px', marginBottom: '10px' }}>
+                <span>Virtual interfaces ({virtualInterfaces.length})</span>
+                <input
+                  type="checkbox"
+                  role="switch"
+                  checked={showVirtual}
+                  onChange={(e) => setShowVirtual(e.target.checked)}
+                  aria-label="Show virtual network interfaces"
+                  aria-checked={showVirtual}
+                  style={{ cursor: 'pointer', accentColor: 'rgb(102, 126, 234)' }}
+                />
+              </label>
+            )}
+            {showVirtual && virtualInterfaces.map(renderInterface)}
           </div>
       </div>
       
@@ -1,6 +1,6 @@
 import React, { memo, useState, useEffect, useRef } from "react";
 import { formatBytes, formatRunTime, formatCpuTime } from '../../shared/utils';
-import { ProcessInfo } from '../../shared/types';
+import { KillProcessResult, ProcessIdentity, ProcessInfo } from '../../shared/types';
 
 const contextMenuItemStyle: React.CSSProperties = {
   display: 'block',
@@ -19,9 +19,12 @@ interface ProcessListProps {
   processes: ProcessInfo[];
   showThreads: boolean;
   onToggleThreads: () => void;
+  // Given the identity the process was listed with, so a reused PID isn't
+  // killed; `force` overrides the protection policy
   onKillProcess: (
-    pid: number
-  ) => Promise<{ success: boolean; message: string }>;
+    identity: ProcessIdentity,
+    force?: boolean
+  ) => Promise<KillProcessResult>;
 }
 
 const ProcessList: React.FC<ProcessListProps> = ({
@@ -31,13 +34,12 @@ const ProcessList: React.FC<ProcessListProps> = ({
   onKillProcess,
 }) => {
   const [sortBy, setSortBy] = useState<
-    "cpu" | "memoryBytes" | "memoryPercentage" | "pid" | "name" | "user" | "runTime" | "cpuTime" | "status" | "command" | "diskRead" | "diskWrite"
+    "cpu" | "memoryBytes" | "memoryPercentage" | "pid" | "name" | "user" | "runTime" | "cpuTime" | "status" | "command" | "diskRead" | "diskWrite" | "netRx" | "netTx"
   >("cpu");
   const [sortDesc, setSortDesc] = useState(true);
   const [selectedPid, setSelectedPid] = useState<number | null>(null);
   const [showConfirmDialog, setShowConfirmDialog] = useState(false);
-  const [processToKill, setProcessToKill] = useState<{
-    pid: number;
+  const [processToKill, setProcessToKill] = useState<ProcessIdentity & {
     name: string;
   } | null>(null);
   const [searchQuery, setSearchQuery] = useState("");
@@ -88,8 +90,13 @@ const ProcessList: React.FC<ProcessListProps> = ({
     command: false,
     diskRead: true,
     diskWrite: true,
+    netRx: true,
+    netTx: true,
   });
 
+  // Per-process traffic is only attributed on Linux
+  const hasNetwork = processes.some((process) => process.netRx !== undefined);
+
   const toggleColumn = (column: keyof typeof visibleColumns) => {
     setVisibleColumns(prev => ({
       ...prev,
@@ -97,7 +104,7 @@ const ProcessList: React.FC<ProcessListProps> = ({
     }));
   };
 
-  const handleSort = (column: "cpu" | "memoryBytes" | "memoryPercentage" | "pid" | "name" | "user" | "runTime" | "cpuTime" | "status" | "command" | "diskRead" | "diskWrite") => {
+  const handleSort = (column: "cpu" | "memoryBytes" | "memoryPercentage" | "pid" | "name" | "user" | "runTime" | "cpuTime" | "status" | "command" | "diskRead" | "diskWrite" | "netRx" | "netTx") => {
     if (sortBy === column) {
       setSortDesc(!sortDesc);
     } else {
@@ -106,18 +113,29 @@ const ProcessList: React.FC<ProcessListProps> = ({
     }
   };
 
-  const handleKillProcess = async (pid: number, name: string) => {
-    setProcessToKill({ pid, name });
+  const handleKillProcess = async ({ pid, name, startTime, exe }: ProcessInfo) => {
+    setProcessToKill({ pid, name, startTime, exe });
     setShowConfirmDialog(true);
   };
 
   const confirmKill = async () => {
     if (processToKill) {
-      const result = await onKillProcess(processToKill.pid);
+      const { pid, startTime, exe } = processToKill;
+      let result = await onKillProcess({ pid, startTime, exe });
+      const forced = result.code === 'protectedProcess' && confirm(`${result.message}\n\nKill it anyway?`);
+      if (forced) {
+        result = await onKillProcess({ pid, startTime, exe }, true);
+      }
       if (result.success) {
         setSelectedPid(null);
       } else {
-        alert(`Failed to kill process: ${result.message}`);
+        const hint = result.code === 'permissionDenied'
+          ? '\n\nThis process belongs to another user. Run Peep as an administrator to kill it.'
+          : '';
+        // Declining to force it is not a failure
+        if (result.code !== 'protectedProcess' || forced) {
+          alert(`Failed to kill process: ${result.message}${hint}`);
+        }
       }
     }
     setShowConfirmDialog(false);
@@ -227,6 +245,8 @@ const ProcessList: React.FC<ProcessListProps> = ({
         {visibleColumns.command && <td style={{ maxWidth: '300px', overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }} title={process.command}>{process.command}</td>}
         {visibleColumns.diskRead && <td>{formatBytes(process.diskRead)}</td>}
         {visibleColumns.diskWrite && <td>{formatBytes(process.diskWrite)}</td>}
+        {hasNetwork && visibleColumns.netRx && <td>{formatBytes(process.netRx ?? 0, { perSecond: true })}</td>}
+        {hasNetwork && visibleColumns.netTx && <td>{formatBytes(process.netTx ?? 0, { perSecond: true })}</td>}
       </tr>
     );
     
@@ -260,9 +280,9 @@ const ProcessList: React.FC<ProcessListProps> = ({
       }
 
       // Handle numeric sorting for cpu, memory, pid
-      return sortDesc
-        ? (bVal as number) - (aVal as number)
-        : (aVal as number) - (bVal as number);
+      const aNum = (aVal as number | undefined) ?? 0;
+      const bNum = (bVal as number | undefined) ?? 0;
+      return sortDesc ? bNum - aNum : aNum - bNum;
     });
 
   return (
@@ -438,6 +458,7 @@ const ProcessList: React.FC<ProcessListProps> = ({
                     command: "Command",
                     diskRead: "Disk Read",
                     diskWrite: "Disk Write",
+                    ...(hasNetwork ? { netRx: "Net In", netTx: "Net Out" } : {}),
                   }).map(([key, label]) => (
                     <label
                       key={key}
@@ -479,7 +500,7 @@ const ProcessList: React.FC<ProcessListProps> = ({
             if (selectedPid !== null) {
               const process = processes.find((p) => p.pid === selectedPid);
               if (process) {
-                handleKillProcess(selectedPid, process.name);
+                handleKillProcess(process);
               }
             } else {
               alert("Please select a process first");
@@ -592,6 +613,22 @@ const ProcessList: React.FC<ProcessListProps> = ({
                 Disk Write {sortBy === "diskWrite" && (sortDesc ? "↓" : "↑")}
               </th>
             )}
+            {hasNetwork && visibleColumns.netRx && (
+              <th
+                onClick={() => handleSort("netRx")}
+                style={{ cursor: "pointer" }}
+              >
+                Net In {sortBy === "netRx" && (sortDesc ? "↓" : "↑")}
+              </th>
+            )}
+            {hasNetwork && visibleColumns.netTx && (
+              <th
+                onClick={() => handleSort("netTx")}
+                style={{ cursor: "pointer" }}
+              >
+                Net Out {sortBy === "netTx" && (sortDesc ? "↓" : "↑")}
+              </th>
+            )}
           </tr>
         </thead>
         <tbody>
@@ -627,6 +664,8 @@ const ProcessList: React.FC<ProcessListProps> = ({
               {visibleColumns.command && <td style={{ maxWidth: '300px', overflow: 'hidden', textOverflow: 'ellipsis', whiteSpace: 'nowrap' }} title={process.command}>{process.command}</td>}
               {visibleColumns.diskRead && <td>{formatBytes(process.diskRead)}</td>}
               {visibleColumns.diskWrite && <td>{formatBytes(process.diskWrite)}</td>}
+              {hasNetwork && visibleColumns.netRx && <td>{formatBytes(process.netRx ?? 0, { perSecond: true })}</td>}
+              {hasNetwork && visibleColumns.netTx && <td>{formatBytes(process.netTx ?? 0, { perSecond: true })}</td>}
             </tr>
           ))}
         </tbody>
@@ -700,7 +739,7 @@ const ProcessList: React.FC<ProcessListProps> = ({
           {/* Kill Process */}
           <button
             onClick={() => {
-              handleKillProcess(contextMenu.process.pid, contextMenu.process.name);
+              handleKillProcess(contextMenu.process);
               setContextMenu(null);
             }}
             style={{ ...contextMenuItemStyle, color: '#f87171' }}
@@ -17,8 +17,15 @@ export interface MemoryInfo {
 }
 
 export interface DiskInfo {
+  // Bytes since the previous native refresh
   read: number;
   write: number;
+  // Bytes per second, normalized natively against the refresh interval
+  readRate?: number;
+  writeRate?: number;
+  // Cumulative bytes since boot
+  totalRead?: number;
+  totalWrite?: number;
   disks?: DiskDetails[];
 }
 
@@ -31,19 +38,69 @@ export interface DiskDetails {
   fileSystem: string;
 }
 
+// Which volumes are listed. Volumes matching an include rule, or no exclude
+// rule, are listed; shadowed mount points and duplicate devices never are.
+export interface DiskFilter {
+  excludeFileSystems: string[];
+  excludeMountPrefixes: string[];
+  excludeRemovable: boolean;
+  includeFileSystems: string[];
+  includeMountPrefixes: string[];
+  shadowed: { mountPoint: string; shadowedBy: string }[];
+  dedupDevices: boolean;
+}
+
+export type InterfaceKind =
+  | 'ethernet' | 'wireless' | 'ethernetOrWireless' | 'loopback' | 'bridge' | 'bond' | 'vlan'
+  | 'veth' | 'tap' | 'tunnel' | 'wireguard' | 'ppp' | 'peerToPeer' | 'awdl' | 'firewire' | 'other';
+
+export interface InterfaceAddress {
+  address: string;
+  prefix: number;
+}
+
 export interface NetworkInfo {
+  // Bytes since the previous native refresh
   rx: number;
   tx: number;
+  // Bytes and packets per second, normalized natively against the refresh interval
+  rxRate?: number;
+  txRate?: number;
+  rxPacketsRate?: number;
+  txPacketsRate?: number;
+  // Cumulative bytes since boot
+  totalRx?: number;
+  totalTx?: number;
+  // Physical adapters
   interfaces?: NetworkInterface[];
+  // Bridges, tunnels, container links and other software interfaces
+  virtualInterfaces?: NetworkInterface[];
 }
 
 export interface NetworkInterface {
   name: string;
+  // Display label for `kind`
   type: string;
+  kind?: InterfaceKind;
+  // False for bridges, tunnels, container links and other software interfaces
+  physical?: boolean;
+  macAddress?: string;
+  addresses?: InterfaceAddress[];
+  mtu?: number;
+  state?: 'up' | 'down' | 'dormant' | 'lowerLayerDown' | 'notPresent' | 'testing';
+  // Negotiated link speed in Mbit/s
+  speedMbps?: number;
+  duplex?: 'full' | 'half';
   received: number;
   transmitted: number;
+  receivedRate?: number;
+  transmittedRate?: number;
+  totalReceived?: number;
+  totalTransmitted?: number;
   packetsReceived: number;
   packetsTransmitted: number;
+  packetsReceivedRate?: number;
+  packetsTransmittedRate?: number;
 }
 
 export interface SystemInfo {
@@ -51,6 +108,88 @@ export interface SystemInfo {
   memory?: MemoryInfo;
   disk?: DiskInfo;
   network: NetworkInfo;
+  // Only present when requested by a RefreshPlan; memory is in bytes
+  processes?: (Omit<ProcessInfo, 'memoryBytes' | 'memoryPercentage'> & { memory: number })[];
+  // Set on samples pushed by the native sampler (ms since epoch)
+  timestamp?: number;
+}
+
+// Limits what getSystemInfo refreshes and returns. Omitted subsystems are
+// skipped; `disks: 'io'` skips querying volume space.
+export interface RefreshPlan {
+  cpu?: boolean;
+  memory?: boolean;
+  disks?: boolean | 'io' | 'volumes';
+  network?: boolean;
+  // Per-process traffic (`network`, Linux only) is off unless asked for
+  processes?: boolean | { threads?: boolean; command?: boolean; user?: boolean; network?: boolean };
+}
+
+// Series kept by the native metric history. CPU, memory and swap are
+// percentages; disk and network are bytes per second.
+export type HistoryMetric =
+  | 'cpu'
+  | `cpu.${number}`
+  | 'memory'
+  | 'swap'
+  | 'disk.read'
+  | 'disk.write'
+  | 'network.rx'
+  | 'network.tx';
+
+// Bucket width in seconds: 1s for 10 minutes, 10s for 6 hours, 1m for 7 days
+export type HistoryResolution = 1 | 10 | 60;
+
+export interface HistoryPoint {
+  timestamp: number;
+  min: number;
+  avg: number;
+  max: number;
+}
+
+// A sample persisted by the native store: same shape as getSystemInfo
+export interface StoredSample extends SystemInfo {
+  timestamp: number;
+}
+
+export type Protocol = 'tcp' | 'tcp6' | 'udp' | 'udp6';
+
+export type ConnectionState =
+  | 'established' | 'synSent' | 'synRecv' | 'finWait1' | 'finWait2' | 'timeWait' | 'close'
+  | 'closeWait' | 'lastAck' | 'listen' | 'closing' | 'newSynRecv';
+
+export interface Connection {
+  protocol: Protocol;
+  localAddress: string;
+  localPort: number;
+  remoteAddress: string;
+  remotePort: number;
+  state: ConnectionState;
+  sendQueue: number;
+  receiveQueue: number;
+  uid: number;
+  inode: number;
+  pid?: number;
+  processName?: string;
+}
+
+// Empty or omitted lists match everything
+export interface ConnectionFilter {
+  protocols?: Protocol[];
+  states?: ConnectionState[];
+  pid?: number;
+}
+
+// A socket accepting traffic on a local port; the owner is only known for
+// other users' sockets when running as root
+export interface ListeningPort {
+  protocol: Protocol;
+  address: string;
+  port: number;
+  pid?: number;
+  processName?: string;
+  command?: string[];
+  user?: string;
 }
 
 export interface ProcessInfo {
@@ -67,7 +206,46 @@ export interface ProcessInfo {
   command: string;
   diskRead: number;
   diskWrite: number;
+  // TCP bytes per second (Linux only)
+  netRx?: number;
+  netTx?: number;
   isThread: boolean;
+  // Seconds since the Unix epoch
+  startTime: number;
+  // Executable path, where the OS lets us read it
+  exe?: string;
+}
+
+// Which process a PID referred to when it was listed
+export interface ProcessIdentity {
+  pid: number;
+  startTime: number;
+  exe?: string;
+}
+
+// Control APIs given an identity refuse to act once the PID has been reused
+export type ProcessTarget = number | ProcessIdentity;
+
+// Processes that kill and signal calls refuse without `force`, on top of
+// init, Peep, the parent process that started it, the processes it started
+// and kernel threads. Patterns are case-insensitive names, or executable
+// paths when they contain a path separator; `*` matches anything.
+export interface ProtectionPolicy {
+  patterns: string[];
+}
+
+// Sums over a process and all its descendants
+export interface SubtreeTotal {
+  processes: number;
+  cpu: number;
+  memory: number;
+  diskRead: number;
+  diskWrite: number;
+}
+
+export interface ProcessTreeNode extends ProcessInfo {
+  children: ProcessTreeNode[];
+  total: SubtreeTotal;
 }
 
 export interface BatteryInfo {
@@ -90,17 +268,75 @@ export interface OsInfo {
   uptime?: number;
 }
 
+// Why a process-control call failed
+export type ControlErrorCode =
+  | 'permissionDenied' // EPERM, usually another user's process
+  | 'noSuchProcess' // ESRCH
+  | 'signalUnsupported' // not available on this platform
+  | 'protectedProcess' // e.g. Peep itself
+  | 'identityMismatch' // the PID now belongs to a different process
+  | 'failed';
+
 export interface KillProcessResult {
   success: boolean;
   message: string;
+  // Only on failure; errno is the OS's, or what kill(2) would have set
+  code?: ControlErrorCode;
+  errno?: number;
+}
+
+// Signal names as sysinfo knows them; which ones work depends on the
+// platform (Windows only supports KILL)
+export type ProcessSignal =
+  | 'HUP' | 'INT' | 'QUIT' | 'ILL' | 'TRAP' | 'ABRT' | 'IOT' | 'BUS' | 'FPE' | 'KILL' | 'USR1' | 'SEGV'
+  | 'USR2' | 'PIPE' | 'ALRM' | 'TERM' | 'CHLD' | 'CONT' | 'STOP' | 'TSTP' | 'TTIN' | 'TTOU' | 'URG'
+  | 'XCPU' | 'XFSZ' | 'VTALRM' | 'PROF' | 'WINCH' | 'IO' | 'POLL' | 'PWR' | 'SYS';
+
+export interface TerminateResult extends KillProcessResult {
+  // Which signal ended the process, when it did end
+  endedBy?: 'term' | 'kill';
+}
+
+// One process's result from signalling a tree, group or session
+export interface SignalOutcome extends KillProcessResult {
+  pid: number;
+  name: string;
 }
 
 export interface ElectronAPI {
-  getSystemInfo: () => Promise<SystemInfo | null>;
-  getProcesses: (showThreads?: boolean) => Promise<ProcessInfo[]>;
-  getBatteryInfo: () => Promise<BatteryInfo>;
-  getOsInfo: () => Promise<OsInfo>;
-  killProcess: (pid: number) => Promise<KillProcessResult>;
+  getSystemInfo: (plan?: RefreshPlan) => Promise<SystemInfo | null>;
+  onSystemInfo: (callback: (info: SystemInfo) => void, intervalMs?: number) => () => void;
+  getHistory: (metric: HistoryMetric, from?: number, to?: number, resolution?: HistoryResolution) => Promise<HistoryPoint[]>;
+  getStoredSamples: (from?: number, to?: number) => Promise<StoredSample[]>;
+  // Resolve to null when a newer request superseded this one
+  getProcesses: (showThreads?: boolean) => Promise<ProcessInfo[] | null>;
+  getProcessTree: () => Promise<ProcessTreeNode[] | null>;
+  getBatteryInfo: () => Promise<BatteryInfo | null>;
+  getOsInfo: () => Promise<OsInfo | null>;
+  // Protected processes fail with code 'protectedProcess' unless forced
+  killProcess: (target: ProcessTarget, force?: boolean) => Promise<KillProcessResult>;
+  sendSignal: (target: ProcessTarget, signal: ProcessSignal, force?: boolean) => Promise<KillProcessResult>;
+  // SIGTERM, then SIGKILL after timeoutMs (default 5000) if still running
+  terminateGracefully: (target: ProcessTarget, timeoutMs?: number, force?: boolean) => Promise<TerminateResult>;
+  // Signal defaults to KILL; outcomes are in the order processes were signalled
+  signalProcessTree: (
+    target: ProcessTarget, signal?: ProcessSignal, order?: 'bottomUp' | 'topDown', force?: boolean
+  ) => Promise<SignalOutcome[]>;
+  signalProcessGroup: (
+    target: ProcessTarget, signal?: ProcessSignal, scope?: 'group' | 'session', force?: boolean
+  ) => Promise<SignalOutcome[]>;
+  // No policy restores the OS defaults
+  setProtectionPolicy: (policy?: Partial<ProtectionPolicy>) => Promise<ProtectionPolicy | null>;
+  getProtectionPolicy: () => Promise<ProtectionPolicy | null>;
+  // Omitted fields keep the OS defaults; no filter restores them all
+  setDiskFilter: (filter?: Partial<DiskFilter>) => Promise<DiskFilter | null>;
+  getDiskFilter: () => Promise<DiskFilter | null>;
+  // Linux only; empty elsewhere
+  getConnections: (filter?: ConnectionFilter) => Promise<Connection[]>;
+  getListeningPorts: () => Promise<ListeningPort[]>;
+  // 'tcp' also finds tcp6 listeners; null when nothing listens on the port
+  findProcessByPort: (port: number, proto?: 'tcp' | 'udp') => Promise<ListeningPort | null>;
+  killProcessByPort: (port: number, proto?: 'tcp' | 'udp', force?: boolean) => Promise<KillProcessResult>;
 }
 
 declare global {